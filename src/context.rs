@@ -1,47 +1,205 @@
 
-use std::collections::HashMap;
+use std::{cell::{Cell, RefCell}, collections::HashMap, path::Path, sync::Arc};
 
-use crate::{event::{EventReturnCode, EventReceiver, self}, font::Font};
+use crate::{css, event::{EventReturnCode, self}, font::{FontDatabase, FontLoadError}, util};
 
 #[derive(Debug)]
 pub struct YarglInitError {
     pub message: String,
 }
 
-pub struct Context<'f, 'ff> {
+/// Mouse instance id of the mouse events that sdl synthesizes from touch input.
+const TOUCH_MOUSE_ID: u32 = u32::MAX;
+
+pub struct Context {
     sdl: sdl2::Sdl,
     pub video_subsystem: sdl2::VideoSubsystem,
-    pub fonts: HashMap<String, &'f Font<'ff>>,
+    /// Loaded font families. Fonts can be added at any time, i.e. while windows are open.
+    pub fonts: RefCell<FontDatabase>,
+    // the system cursor that is currently shown, kept alive while it is set
+    cursor: RefCell<Option<(css::Cursor, sdl2::mouse::Cursor)>>,
+    // pointer ids of the fingers that currently touch a device, by touch device and finger id
+    touch_pointers: RefCell<HashMap<(i64, i64), i64>>,
+    next_pointer_id: Cell<i64>,
+    // last position of the mouse in window coordinates, scroll events are dispatched to the element below it
+    mouse_position: Cell<(i32, i32)>,
+    // files and text that are dropped onto a window from another application, collected until the drop is complete
+    pending_drop: RefCell<Option<event::DataTransfer>>,
 }
 
-pub fn init<'f, 'ff>() -> Result<Context<'f, 'ff>, YarglInitError> {
+pub fn init() -> Result<Context, YarglInitError> {
+    // closing the last window is a close request of that window, which listeners may cancel, and not a quit event
+    sdl2::hint::set("SDL_QUIT_ON_LAST_WINDOW_CLOSE", "0");
     let sdl = sdl2::init().map_err(|e| YarglInitError { message: format!("Failed to initialize SDL: {}", e) })?;
     let video_subsystem = sdl.video().map_err(|e| YarglInitError { message: format!("Failed to initialize SDL video subsystem: {}", e) })?;
     Ok(Context {
         sdl,
         video_subsystem,
-        fonts: HashMap::new(),
+        fonts: RefCell::new(FontDatabase::new()),
+        cursor: RefCell::new(None),
+        touch_pointers: RefCell::new(HashMap::new()),
+        next_pointer_id: Cell::new(event::MOUSE_POINTER_ID + 1),
+        mouse_position: Cell::new((0, 0)),
+        pending_drop: RefCell::new(None),
     })
 }
 
-impl Context<'_, '_> {
+impl Context {
+    /// Reads a font (collection) file and adds all of its faces to the given family.
+    /// Returns the number of faces that were added.
+    pub fn load_font_file(&self, family: &str, path: impl AsRef<Path>) -> Result<usize, FontLoadError> {
+        self.fonts.borrow_mut().load_font_file(family, path)
+    }
+
+    /// Adds all faces of the given font (collection) data to the given family.
+    /// Returns the number of faces that were added.
+    pub fn load_font_bytes(&self, family: &str, data: impl Into<Arc<[u8]>>) -> Result<usize, FontLoadError> {
+        self.fonts.borrow_mut().load_font_bytes(family, data)
+    }
+
+    /// Shows the system cursor that matches the given css cursor. Does nothing if it is already shown.
+    fn set_cursor(&self, cursor: css::Cursor) {
+        let mut current = self.cursor.borrow_mut();
+        if current.as_ref().is_some_and(|(current_cursor, _)| *current_cursor == cursor) {
+            return;
+        }
+        self.sdl.mouse().show_cursor(cursor != css::Cursor::None);
+        let system_cursor = match cursor {
+            css::Cursor::Auto | css::Cursor::Default | css::Cursor::None => sdl2::mouse::SystemCursor::Arrow,
+            css::Cursor::Pointer => sdl2::mouse::SystemCursor::Hand,
+            css::Cursor::Text => sdl2::mouse::SystemCursor::IBeam,
+            css::Cursor::Wait => sdl2::mouse::SystemCursor::Wait,
+            css::Cursor::Progress => sdl2::mouse::SystemCursor::WaitArrow,
+            css::Cursor::Crosshair => sdl2::mouse::SystemCursor::Crosshair,
+            css::Cursor::Move => sdl2::mouse::SystemCursor::SizeAll,
+            css::Cursor::NotAllowed => sdl2::mouse::SystemCursor::No,
+            css::Cursor::EwResize => sdl2::mouse::SystemCursor::SizeWE,
+            css::Cursor::NsResize => sdl2::mouse::SystemCursor::SizeNS,
+            css::Cursor::NeswResize => sdl2::mouse::SystemCursor::SizeNESW,
+            css::Cursor::NwseResize => sdl2::mouse::SystemCursor::SizeNWSE,
+        };
+        match sdl2::mouse::Cursor::from_system(system_cursor) {
+            Ok(sdl_cursor) => {
+                sdl_cursor.set();
+                *current = Some((cursor, sdl_cursor));
+            },
+            Err(_) => *current = None,
+        }
+    }
+
+    /// The window that receives touch events, which are not associated with a window: the window with mouse focus, or with keyboard focus.
+    /// If no window has focus, the first window.
+    fn touch_window<'a, 'b, 'c>(&self, windows: &'a mut [crate::window::Window<'b, 'c>]) -> Option<&'a mut crate::window::Window<'b, 'c>> {
+        match self.sdl.mouse().focused_window_id().or_else(|| self.sdl.keyboard().focused_window_id()) {
+            Some(window_id) => windows.iter_mut().find(|window| window.sdl_window().id() == window_id),
+            None => windows.first_mut(),
+        }
+    }
+
+    /// Returns the pointer id of a finger, assigning a new one when the finger touches the device.
+    fn touch_pointer_id(&self, touch_id: i64, finger_id: i64) -> i64 {
+        *self.touch_pointers.borrow_mut().entry((touch_id, finger_id)).or_insert_with(|| {
+            let pointer_id = self.next_pointer_id.get();
+            self.next_pointer_id.set(pointer_id + 1);
+            pointer_id
+        })
+    }
+
+    /// Adds dropped files or text to the drop in progress, or drops them right away if no drop began (older platforms don't send the begin and complete events).
+    fn drop_data(&self, windows: &mut [crate::window::Window], window_id: u32, timestamp: u32, add: impl FnOnce(&event::DataTransfer)) -> Option<EventReturnCode> {
+        if let Some(data_transfer) = self.pending_drop.borrow().as_ref() {
+            add(data_transfer);
+            return None;
+        }
+        let data_transfer = event::DataTransfer::new();
+        add(&data_transfer);
+        Some(self.dispatch_drop(windows, window_id, data_transfer, timestamp))
+    }
+
+    /// Dispatches a drop from another application to the element below the mouse cursor.
+    fn dispatch_drop(&self, windows: &mut [crate::window::Window], window_id: u32, data_transfer: event::DataTransfer, timestamp: u32) -> EventReturnCode {
+        let window = match windows.iter_mut().position(|window| window.sdl_window().id() == window_id) {
+            Some(index) => &mut windows[index],
+            None => match self.touch_window(windows) {
+                Some(window) => window,
+                None => return EventReturnCode::Continue,
+            }
+        };
+        // the window doesn't receive mouse motion events while something is dragged onto it, so the position is read from the global mouse state
+        let (x, y) = util::global_mouse_position(window.sdl_window());
+        let target = window.get_node_handle_at(x, y);
+        let data = event::DragEventData { x, y, data_transfer: std::rc::Rc::new(data_transfer), timestamp };
+        event::dispatch(window, |window| &window.drop_events, &event::Event::new(event::DropEvent { data }, target))
+    }
+
+    /// Dispatches an event of a window's lifecycle.
+    fn window_event(&self, window: &mut crate::window::Window, win_event: sdl2::event::WindowEvent, timestamp: u32) -> EventReturnCode {
+        match win_event {
+            sdl2::event::WindowEvent::SizeChanged(..) => {
+                let _ = window.update_size();
+                let (width, height) = (window.width(), window.height());
+                event::dispatch(window, |window| &window.resize_events, &event::Event::new(event::ResizeEvent { width, height, timestamp }, None))
+            },
+            sdl2::event::WindowEvent::Moved(x, y) => {
+                let return_code = event::dispatch(window, |window| &window.move_events, &event::Event::new(event::MoveEvent { x, y, timestamp }, None));
+                if return_code != EventReturnCode::Quit && window.update_display() {
+                    return Self::dispatch_display_change(window, timestamp);
+                }
+                return_code
+            },
+            sdl2::event::WindowEvent::FocusGained | sdl2::event::WindowEvent::FocusLost => {
+                let focused = win_event == sdl2::event::WindowEvent::FocusGained;
+                event::dispatch(window, |window| &window.window_focus_events, &event::Event::new(event::WindowFocusEvent { focused, timestamp }, None))
+            },
+            sdl2::event::WindowEvent::Minimized | sdl2::event::WindowEvent::Maximized | sdl2::event::WindowEvent::Restored => {
+                let state = match win_event {
+                    sdl2::event::WindowEvent::Minimized => event::WindowState::Minimized,
+                    sdl2::event::WindowEvent::Maximized => event::WindowState::Maximized,
+                    _ => event::WindowState::Restored,
+                };
+                event::dispatch(window, |window| &window.window_state_events, &event::Event::new(event::WindowStateEvent { state, timestamp }, None))
+            },
+            sdl2::event::WindowEvent::Close => {
+                let return_code = event::dispatch(window, |window| &window.close_requested_events, &event::Event::new(event::CloseRequestedEvent { timestamp }, None));
+                if return_code == EventReturnCode::Continue {
+                    window.close();
+                }
+                return_code
+            },
+            sdl2::event::WindowEvent::Leave => {
+                let (mouse_x, mouse_y) = self.mouse_position.get();
+                window.set_pointer_target(event::MOUSE_POINTER_ID, None, mouse_x, mouse_y, timestamp)
+            },
+            _ => EventReturnCode::Continue
+        }
+    }
+
+    fn dispatch_display_change(window: &mut crate::window::Window, timestamp: u32) -> EventReturnCode {
+        let (display_index, device_pixel_ratio) = (window.sdl_window().display_index().unwrap_or(0), window.device_pixel_ratio());
+        event::dispatch(window, |window| &window.display_change_events, &event::Event::new(event::DisplayChangeEvent { display_index, device_pixel_ratio, timestamp }, None))
+    }
+
     /// Polls events from the sdl event pump and triggers the appropriate events on the windows.
-    pub fn poll_events(&self, windows: &Vec<crate::window::Window>) -> EventReturnCode {
+    pub fn poll_events(&self, windows: &mut [crate::window::Window]) -> EventReturnCode {
         if let Ok(mut event_pump) = self.sdl.event_pump() {
             let mut return_code = EventReturnCode::Continue;
             for event in event_pump.poll_iter() {
                 match event {
                     sdl2::event::Event::Quit {..} => {
                         return_code = EventReturnCode::Quit;
-                        break
                     },
+                    // mouse events that sdl synthesizes from touch input are skipped, the fingers are dispatched as pointers of their own
+                    sdl2::event::Event::MouseButtonDown { which: TOUCH_MOUSE_ID, .. } | sdl2::event::Event::MouseButtonUp { which: TOUCH_MOUSE_ID, .. } |
+                    sdl2::event::Event::MouseMotion { which: TOUCH_MOUSE_ID, .. } => {},
                     sdl2::event::Event::MouseButtonDown { timestamp, window_id, which: _, mouse_btn, clicks, x, y } => {
-                        for window in windows {
+                        for window in windows.iter_mut() {
                             if window.sdl_window().id() == window_id {
-                                window.pointer_down_events.trigger(&event::Event(event::PointerDownEvent { 
+                                let target = window.get_node_handle_at(x, y);
+                                return_code = event::dispatch(window, |window| &window.pointer_down_events, &event::Event::new(event::PointerDownEvent {
                                     data: event::PointerEventData {
                                         x,
                                         y,
+                                        pointer_id: event::MOUSE_POINTER_ID,
                                         mouse_data: Some(event::MouseButtonEventData {
                                             button: mouse_btn,
                                             clicks,
@@ -49,87 +207,334 @@ impl Context<'_, '_> {
                                         finger_data: None,
                                         timestamp,
                                     }
-                                }), window);
+                                }, target));
+                                if mouse_btn == sdl2::mouse::MouseButton::Left && return_code != EventReturnCode::Quit {
+                                    // cancelling the pointer down event prevents focusing and dragging
+                                    let cancelled = return_code == EventReturnCode::Cancel;
+                                    if window.press_pointer(event::MOUSE_POINTER_ID, target, cancelled) == EventReturnCode::Quit {
+                                        return_code = EventReturnCode::Quit;
+                                    } else if !cancelled {
+                                        window.begin_drag(target, x, y);
+                                        window.begin_selection(x, y, self.sdl.keyboard().mod_state().intersects(sdl2::keyboard::Mod::LSHIFTMOD | sdl2::keyboard::Mod::RSHIFTMOD));
+                                    }
+                                }
                                 break;
                             }
                         }
                     },
-                    // TODO support touch input
-                    // sdl2::event::Event::FingerDown { timestamp, touch_id, finger_id, x, y, dx, dy, pressure } => {
-                        
-                    // },
+                    sdl2::event::Event::FingerDown { timestamp, touch_id, finger_id, x, y, dx, dy, pressure } => {
+                        if let Some(window) = self.touch_window(windows) {
+                            let (window_x, window_y) = ((x * window.width() as f32) as i32, (y * window.height() as f32) as i32);
+                            let target = window.get_node_handle_at(window_x, window_y);
+                            let pointer_id = self.touch_pointer_id(touch_id, finger_id);
+                            // a finger only hovers an element while it touches it
+                            return_code = window.set_pointer_target(pointer_id, target, window_x, window_y, timestamp);
+                            if return_code == EventReturnCode::Quit {
+                                break;
+                            }
+                            return_code = event::dispatch(window, |window| &window.pointer_down_events, &event::Event::new(event::PointerDownEvent {
+                                data: event::PointerEventData {
+                                    x: window_x,
+                                    y: window_y,
+                                    pointer_id,
+                                    mouse_data: None,
+                                    finger_data: Some(event::FingerEventData {
+                                        norm_x: x,
+                                        norm_y: y,
+                                        dx,
+                                        dy,
+                                        touch_id,
+                                        finger_id,
+                                        norm_pressure: pressure,
+                                    }),
+                                    timestamp,
+                                }
+                            }, target));
+                            if return_code != EventReturnCode::Quit && window.press_pointer(pointer_id, target, return_code == EventReturnCode::Cancel) == EventReturnCode::Quit {
+                                return_code = EventReturnCode::Quit;
+                            }
+                        }
+                    },
                     sdl2::event::Event::MouseButtonUp { timestamp, window_id, which: _, mouse_btn, clicks, x, y } => {
-                        for window in windows {
+                        for window in windows.iter_mut() {
                             if window.sdl_window().id() == window_id {
-                                return_code = window.pointer_up_events.trigger(&event::Event(event::PointerUpEvent { 
-                                    data: event::PointerEventData {
-                                        x,
-                                        y,
-                                        mouse_data: Some(event::MouseButtonEventData {
-                                            button: mouse_btn,
-                                            clicks,
-                                        }),
-                                        finger_data: None,
-                                        timestamp,
+                                // the end of a drag replaces the pointer up event
+                                if mouse_btn == sdl2::mouse::MouseButton::Left {
+                                    window.end_selection();
+                                    if let Some(drag_return_code) = window.drag_end(x, y, timestamp) {
+                                        return_code = drag_return_code;
+                                        break;
                                     }
-                                }), window);
+                                }
+                                let target = window.get_node_handle_at(x, y);
+                                let data = event::PointerEventData {
+                                    x,
+                                    y,
+                                    pointer_id: event::MOUSE_POINTER_ID,
+                                    mouse_data: Some(event::MouseButtonEventData {
+                                        button: mouse_btn,
+                                        clicks,
+                                    }),
+                                    finger_data: None,
+                                    timestamp,
+                                };
+                                return_code = event::dispatch(window, |window| &window.pointer_up_events, &event::Event::new(event::PointerUpEvent { data: data.clone() }, target));
+                                if mouse_btn == sdl2::mouse::MouseButton::Left && return_code != EventReturnCode::Quit {
+                                    return_code = window.release_pointer(data, target);
+                                }
                                 break;
                             }
                         }
                     },
-                    // sdl2::event::Event::FingerUp { timestamp, touch_id, finger_id, x, y, dx, dy, pressure } => {
-    
-                    // },
+                    sdl2::event::Event::FingerUp { timestamp, touch_id, finger_id, x, y, dx, dy, pressure } => {
+                        let pointer_id = self.touch_pointer_id(touch_id, finger_id);
+                        self.touch_pointers.borrow_mut().remove(&(touch_id, finger_id));
+                        if let Some(window) = self.touch_window(windows) {
+                            let (window_x, window_y) = ((x * window.width() as f32) as i32, (y * window.height() as f32) as i32);
+                            let target = window.get_node_handle_at(window_x, window_y);
+                            let data = event::PointerEventData {
+                                x: window_x,
+                                y: window_y,
+                                pointer_id,
+                                mouse_data: None,
+                                finger_data: Some(event::FingerEventData {
+                                    norm_x: x,
+                                    norm_y: y,
+                                    dx,
+                                    dy,
+                                    touch_id,
+                                    finger_id,
+                                    norm_pressure: pressure,
+                                }),
+                                timestamp,
+                            };
+                            return_code = event::dispatch(window, |window| &window.pointer_up_events, &event::Event::new(event::PointerUpEvent { data: data.clone() }, target));
+                            if return_code != EventReturnCode::Quit {
+                                return_code = window.release_pointer(data, target);
+                            }
+                            if return_code != EventReturnCode::Quit {
+                                return_code = window.set_pointer_target(pointer_id, None, window_x, window_y, timestamp);
+                            }
+                        }
+                    },
                     sdl2::event::Event::MouseMotion { timestamp, window_id, which: _, mousestate, x, y, xrel, yrel } => {
-                        for window in windows {
+                        self.mouse_position.set((x, y));
+                        for window in windows.iter_mut() {
                             if window.sdl_window().id() == window_id {
-                                return_code = window.pointer_move_events.trigger(&event::Event(event::PointerMoveEvent {
+                                let target = window.get_node_handle_at(x, y);
+                                return_code = window.set_pointer_target(event::MOUSE_POINTER_ID, target, x, y, timestamp);
+                                if return_code == EventReturnCode::Quit {
+                                    break;
+                                }
+                                // while an element is dragged, drag over events replace the pointer move events
+                                if let Some(drag_return_code) = window.drag_move(x, y, timestamp) {
+                                    return_code = drag_return_code;
+                                    break;
+                                }
+                                window.select_to(x, y);
+                                return_code = event::dispatch(window, |window| &window.pointer_move_events, &event::Event::new(event::PointerMoveEvent {
                                     x,
                                     y,
                                     x_rel: xrel,
                                     y_rel: yrel,
+                                    pointer_id: event::MOUSE_POINTER_ID,
                                     mouse_data: Some(event::MouseMoveData {
                                         state: mousestate,
                                     }),
                                     finger_data: None,
                                     timestamp,
-                                }), window);
+                                }, target));
+                                self.set_cursor(window.cursor_at(x, y));
                                 break;
                             }
                         }
                     },
-                    // sdl2::event::Event::FingerMotion { timestamp, touch_id, finger_id, x, y, dx, dy, pressure } => {
-    
-                    // },
+                    sdl2::event::Event::FingerMotion { timestamp, touch_id, finger_id, x, y, dx, dy, pressure } => {
+                        if let Some(window) = self.touch_window(windows) {
+                            let (width, height) = (window.width() as f32, window.height() as f32);
+                            let (window_x, window_y) = ((x * width) as i32, (y * height) as i32);
+                            let target = window.get_node_handle_at(window_x, window_y);
+                            let pointer_id = self.touch_pointer_id(touch_id, finger_id);
+                            return_code = window.set_pointer_target(pointer_id, target, window_x, window_y, timestamp);
+                            if return_code == EventReturnCode::Quit {
+                                break;
+                            }
+                            return_code = event::dispatch(window, |window| &window.pointer_move_events, &event::Event::new(event::PointerMoveEvent {
+                                x: window_x,
+                                y: window_y,
+                                x_rel: (dx * width) as i32,
+                                y_rel: (dy * height) as i32,
+                                pointer_id,
+                                mouse_data: None,
+                                finger_data: Some(event::FingerEventData {
+                                    norm_x: x,
+                                    norm_y: y,
+                                    dx,
+                                    dy,
+                                    touch_id,
+                                    finger_id,
+                                    norm_pressure: pressure,
+                                }),
+                                timestamp,
+                            }, target));
+                        }
+                    },
                     sdl2::event::Event::MouseWheel { timestamp, window_id, which: _, x, y, direction } => {
-                        for window in windows {
+                        let (mouse_x, mouse_y) = self.mouse_position.get();
+                        for window in windows.iter_mut() {
                             if window.sdl_window().id() == window_id {
-                                return_code = window.scroll_events.trigger(&event::Event(event::ScrollEvent {
+                                let target = window.get_node_handle_at(mouse_x, mouse_y);
+                                return_code = event::dispatch(window, |window| &window.scroll_events, &event::Event::new(event::ScrollEvent {
                                     x: if direction == sdl2::mouse::MouseWheelDirection::Normal {x} else {-x},
                                     y: if direction == sdl2::mouse::MouseWheelDirection::Normal {y} else {-y},
                                     timestamp,
-                                }), window);
+                                }, target));
+                                break;
+                            }
+                        }
+                    },
+                    sdl2::event::Event::MultiGesture { timestamp, touch_id, d_theta, d_dist, x, y, num_fingers } => {
+                        if let Some(window) = self.touch_window(windows) {
+                            let (window_x, window_y) = ((x * window.width() as f32) as i32, (y * window.height() as f32) as i32);
+                            let target = window.get_node_handle_at(window_x, window_y);
+                            return_code = event::dispatch(window, |window| &window.gesture_events, &event::Event::new(event::GestureEvent {
+                                x: window_x,
+                                y: window_y,
+                                rotation: d_theta,
+                                pinch: d_dist,
+                                num_fingers,
+                                touch_id,
+                                timestamp,
+                            }, target));
+                        }
+                    },
+                    sdl2::event::Event::TextInput { timestamp, window_id, text } => {
+                        for window in windows.iter_mut() {
+                            if window.sdl_window().id() == window_id {
+                                let target = window.focused();
+                                return_code = event::dispatch(window, |window| &window.text_input_events, &event::Event::new(event::TextInputEvent {
+                                    text,
+                                    composition: None,
+                                    timestamp,
+                                }, target));
+                                break;
+                            }
+                        }
+                    },
+                    sdl2::event::Event::TextEditing { timestamp, window_id, text, start, length } => {
+                        for window in windows.iter_mut() {
+                            if window.sdl_window().id() == window_id {
+                                let target = window.focused();
+                                return_code = event::dispatch(window, |window| &window.text_input_events, &event::Event::new(event::TextInputEvent {
+                                    text,
+                                    composition: Some((start, length)),
+                                    timestamp,
+                                }, target));
                                 break;
                             }
                         }
                     },
-                    // sdl2::event::Event::MultiGesture { timestamp, touch_id, d_theta, d_dist, x, y, num_fingers } => {
-    
-                    // },
-                    // TODO support keyboard input
-                    // sdl2::event::Event::TextInput { timestamp, window_id, text } => {
-    
-                    // },
-                    // sdl2::event::Event::KeyDown { timestamp, window_id, keycode, scancode, keymod, repeat } => {
-    
-                    // },
-                    // sdl2::event::Event::KeyUp { timestamp, window_id, keycode, scancode, keymod, repeat } => {
-    
-                    // },
+                    sdl2::event::Event::KeyDown { timestamp, window_id, keycode, scancode, keymod, repeat } => {
+                        for window in windows.iter_mut() {
+                            if window.sdl_window().id() == window_id {
+                                // accelerators are triggered before the key reaches the focused element
+                                if let Some(keycode) = keycode {
+                                    return_code = event::dispatch_accelerator(window, keycode, keymod);
+                                    if return_code != EventReturnCode::Continue {
+                                        break;
+                                    }
+                                }
+                                let target = window.focused();
+                                return_code = event::dispatch(window, |window| &window.key_down_events, &event::Event::new(event::KeyDownEvent {
+                                    data: event::KeyboardEventData {
+                                        keycode,
+                                        scancode,
+                                        modifiers: keymod,
+                                        repeat,
+                                        timestamp,
+                                    }
+                                }, target));
+                                // the default action of Tab and Shift+Tab is the sequential focus navigation, other keys select, copy and paste text
+                                match keycode {
+                                    Some(sdl2::keyboard::Keycode::Tab) if return_code == EventReturnCode::Continue => {
+                                        return_code = window.focus_next(keymod.intersects(sdl2::keyboard::Mod::LSHIFTMOD | sdl2::keyboard::Mod::RSHIFTMOD));
+                                    },
+                                    Some(keycode) if return_code == EventReturnCode::Continue => window.key_default_action(keycode, keymod),
+                                    _ => {},
+                                }
+                                break;
+                            }
+                        }
+                    },
+                    sdl2::event::Event::KeyUp { timestamp, window_id, keycode, scancode, keymod, repeat } => {
+                        for window in windows.iter_mut() {
+                            if window.sdl_window().id() == window_id {
+                                let target = window.focused();
+                                return_code = event::dispatch(window, |window| &window.key_up_events, &event::Event::new(event::KeyUpEvent {
+                                    data: event::KeyboardEventData {
+                                        keycode,
+                                        scancode,
+                                        modifiers: keymod,
+                                        repeat,
+                                        timestamp,
+                                    }
+                                }, target));
+                                break;
+                            }
+                        }
+                    },
+                    sdl2::event::Event::Window { timestamp, window_id, win_event } => {
+                        if let Some(window) = windows.iter_mut().find(|window| window.sdl_window().id() == window_id) {
+                            return_code = self.window_event(window, win_event, timestamp);
+                            // the application quits when its last window is closed
+                            if window.is_closed() && windows.iter().all(|window| window.is_closed()) {
+                                return_code = EventReturnCode::Quit;
+                            }
+                        }
+                    },
+                    sdl2::event::Event::DropBegin { .. } => {
+                        *self.pending_drop.borrow_mut() = Some(event::DataTransfer::new());
+                    },
+                    sdl2::event::Event::DropFile { timestamp, window_id, filename } => {
+                        if let Some(drop_return_code) = self.drop_data(windows, window_id, timestamp, |data_transfer| data_transfer.add_file(std::path::PathBuf::from(filename))) {
+                            return_code = drop_return_code;
+                        }
+                    },
+                    sdl2::event::Event::DropText { timestamp, window_id, filename } => {
+                        if let Some(drop_return_code) = self.drop_data(windows, window_id, timestamp, |data_transfer| data_transfer.set_data("text/plain", &filename)) {
+                            return_code = drop_return_code;
+                        }
+                    },
+                    sdl2::event::Event::DropComplete { timestamp, window_id } => {
+                        let data_transfer = self.pending_drop.borrow_mut().take();
+                        if let Some(data_transfer) = data_transfer.filter(|data_transfer| !data_transfer.is_empty()) {
+                            return_code = self.dispatch_drop(windows, window_id, data_transfer, timestamp);
+                        }
+                    },
+                    sdl2::event::Event::Display { timestamp, .. } => {
+                        for window in windows.iter_mut() {
+                            if window.update_display() {
+                                return_code = Self::dispatch_display_change(window, timestamp);
+                                if return_code == EventReturnCode::Quit {
+                                    break;
+                                }
+                            }
+                        }
+                    },
                     _ => {
                         // TODO handle other events
                     }
                 }
+                // a quit is not overwritten by the events after it, they stay queued
+                if return_code == EventReturnCode::Quit {
+                    break;
+                }
+            }
+            // listeners and state changes (i.e. :hover) may have restyled the windows
+            for window in windows.iter_mut() {
+                if window.needs_redraw() {
+                    window.draw();
+                }
             }
             return_code
         } else {
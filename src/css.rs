@@ -14,7 +14,7 @@ pub struct CssParseError {
 }
 
 /// Unit of a css value.
-#[derive(Debug, PartialEq, EnumIter)]
+#[derive(Debug, PartialEq, Clone, Copy, EnumIter)]
 pub enum Unit {
     Px,
     Pt,
@@ -77,102 +77,1420 @@ impl FromStr for Unit {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum TextAlign {
+    /// Left in left-to-right text, right in right-to-left text.
+    Start,
+    /// Right in left-to-right text, left in right-to-left text.
+    End,
     Left,
     Right,
     Center,
+    Justify,
 }
 
 impl ToString for TextAlign {
     fn to_string(&self) -> String {
         match self {
+            TextAlign::Start => "start".to_string(),
+            TextAlign::End => "end".to_string(),
             TextAlign::Left => "left".to_string(),
             TextAlign::Right => "right".to_string(),
             TextAlign::Center => "center".to_string(),
+            TextAlign::Justify => "justify".to_string(),
+        }
+    }
+}
+
+impl FromStr for TextAlign {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "start" => Ok(TextAlign::Start),
+            "end" => Ok(TextAlign::End),
+            "left" => Ok(TextAlign::Left),
+            "right" => Ok(TextAlign::Right),
+            "center" => Ok(TextAlign::Center),
+            "justify" => Ok(TextAlign::Justify),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TextTransform {
+    None,
+    Uppercase,
+    Lowercase,
+    Capitalize,
+}
+
+impl ToString for TextTransform {
+    fn to_string(&self) -> String {
+        match self {
+            TextTransform::None => "none".to_string(),
+            TextTransform::Uppercase => "uppercase".to_string(),
+            TextTransform::Lowercase => "lowercase".to_string(),
+            TextTransform::Capitalize => "capitalize".to_string(),
+        }
+    }
+}
+
+impl FromStr for TextTransform {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(TextTransform::None),
+            "uppercase" => Ok(TextTransform::Uppercase),
+            "lowercase" => Ok(TextTransform::Lowercase),
+            "capitalize" => Ok(TextTransform::Capitalize),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+impl TextTransform {
+    /// Applies this transformation to the given text.
+    ///
+    /// ```
+    /// use yargl::css::TextTransform;
+    /// assert_eq!(TextTransform::Uppercase.apply("Hello world"), "HELLO WORLD");
+    /// assert_eq!(TextTransform::Capitalize.apply("hello big\nworld"), "Hello Big\nWorld");
+    /// ```
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            TextTransform::None => text.to_string(),
+            TextTransform::Uppercase => text.to_uppercase(),
+            TextTransform::Lowercase => text.to_lowercase(),
+            TextTransform::Capitalize => {
+                let mut result = String::with_capacity(text.len());
+                let mut word_start = true;
+                for c in text.chars() {
+                    if word_start && c.is_alphabetic() {
+                        result.extend(c.to_uppercase());
+                    } else {
+                        result.push(c);
+                    }
+                    word_start = c.is_whitespace();
+                }
+                result
+            }
+        }
+    }
+
+    /// The index of the character of the given text that each character of the transformed text comes from.
+    /// A transformation may turn one character into several.
+    ///
+    /// ```
+    /// use yargl::css::TextTransform;
+    /// assert_eq!(TextTransform::Uppercase.apply("aßc"), "ASSC");
+    /// assert_eq!(TextTransform::Uppercase.source_indices("aßc"), vec![0, 1, 1, 2]);
+    /// assert_eq!(TextTransform::Capitalize.source_indices("ßa ßa"), vec![0, 0, 1, 2, 3, 3, 4]);
+    /// ```
+    pub fn source_indices(&self, text: &str) -> Vec<usize> {
+        let mut word_start = true;
+        text.chars().enumerate().flat_map(|(index, c)| {
+            let count = match self {
+                TextTransform::None => 1,
+                TextTransform::Uppercase => c.to_uppercase().count(),
+                TextTransform::Lowercase => c.to_lowercase().count(),
+                TextTransform::Capitalize if word_start && c.is_alphabetic() => c.to_uppercase().count(),
+                TextTransform::Capitalize => 1,
+            };
+            word_start = c.is_whitespace();
+            std::iter::repeat_n(index, count)
+        }).collect()
+    }
+}
+
+/// Lines of a text decoration. Parsed from a space-separated list of "underline", "overline" and "line-through", or "none".
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct TextDecorationLine {
+    pub underline: bool,
+    pub overline: bool,
+    pub line_through: bool,
+}
+
+impl ToString for TextDecorationLine {
+    fn to_string(&self) -> String {
+        let mut lines = Vec::new();
+        if self.underline {
+            lines.push("underline");
+        }
+        if self.overline {
+            lines.push("overline");
+        }
+        if self.line_through {
+            lines.push("line-through");
+        }
+        if lines.is_empty() {
+            "none".to_string()
+        } else {
+            lines.join(" ")
+        }
+    }
+}
+
+impl FromStr for TextDecorationLine {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut line = TextDecorationLine::default();
+        for keyword in s.split_whitespace() {
+            match keyword {
+                "none" => {},
+                "underline" => line.underline = true,
+                "overline" => line.overline = true,
+                "line-through" => line.line_through = true,
+                _ => return Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+            }
+        }
+        Ok(line)
+    }
+}
+
+/// The text-decoration shorthand: lines, color and thickness in any order.
+///
+/// ```
+/// use yargl::css::{TextDecoration, Unit};
+/// use std::str::FromStr;
+/// let decoration = TextDecoration::from_str("underline overline #ff0000 2px").unwrap();
+/// assert!(decoration.line.underline && decoration.line.overline && !decoration.line.line_through);
+/// assert_eq!(decoration.color.unwrap().sdl_color, sdl2::pixels::Color::RGB(255, 0, 0));
+/// assert_eq!(decoration.thickness, Some((2.0, Some(Unit::Px))));
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct TextDecoration {
+    pub line: TextDecorationLine,
+    pub color: Option<CssColor>,
+    pub thickness: Option<(f32, Option<Unit>)>,
+}
+
+impl FromStr for TextDecoration {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut decoration = TextDecoration::default();
+        for part in s.split_whitespace() {
+            if let Ok(color) = CssColor::from_str(part) {
+                decoration.color = Some(color);
+            } else if let Some(dimension) = parse_dimension(part) {
+                decoration.thickness = Some(dimension);
+            } else {
+                let line = TextDecorationLine::from_str(part)?;
+                decoration.line.underline |= line.underline;
+                decoration.line.overline |= line.overline;
+                decoration.line.line_through |= line.line_through;
+            }
+        }
+        Ok(decoration)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Direction {
+    Ltr,
+    Rtl,
+}
+
+impl ToString for Direction {
+    fn to_string(&self) -> String {
+        match self {
+            Direction::Ltr => "ltr".to_string(),
+            Direction::Rtl => "rtl".to_string(),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ltr" => Ok(Direction::Ltr),
+            "rtl" => Ok(Direction::Rtl),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+/// How the element's direction takes part in the Unicode Bidirectional Algorithm.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UnicodeBidi {
+    Normal,
+    Embed,
+    Isolate,
+    /// All characters are ordered strictly in the element's direction.
+    BidiOverride,
+    IsolateOverride,
+    /// The direction is determined from the first strong character of each paragraph, ignoring `direction`.
+    Plaintext,
+}
+
+impl ToString for UnicodeBidi {
+    fn to_string(&self) -> String {
+        match self {
+            UnicodeBidi::Normal => "normal".to_string(),
+            UnicodeBidi::Embed => "embed".to_string(),
+            UnicodeBidi::Isolate => "isolate".to_string(),
+            UnicodeBidi::BidiOverride => "bidi-override".to_string(),
+            UnicodeBidi::IsolateOverride => "isolate-override".to_string(),
+            UnicodeBidi::Plaintext => "plaintext".to_string(),
+        }
+    }
+}
+
+impl FromStr for UnicodeBidi {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(UnicodeBidi::Normal),
+            "embed" => Ok(UnicodeBidi::Embed),
+            "isolate" => Ok(UnicodeBidi::Isolate),
+            "bidi-override" => Ok(UnicodeBidi::BidiOverride),
+            "isolate-override" => Ok(UnicodeBidi::IsolateOverride),
+            "plaintext" => Ok(UnicodeBidi::Plaintext),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WhiteSpace {
+    Normal,
+    NoWrap,
+}
+
+impl ToString for WhiteSpace {
+    fn to_string(&self) -> String {
+        match self {
+            WhiteSpace::Normal => "normal".to_string(),
+            WhiteSpace::NoWrap => "nowrap".to_string(),
+        }
+    }
+}
+
+impl FromStr for WhiteSpace {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(WhiteSpace::Normal),
+            "nowrap" => Ok(WhiteSpace::NoWrap),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Overflow {
+    Visible,
+    Hidden,
+    Clip,
+    Scroll,
+    Auto,
+}
+
+impl ToString for Overflow {
+    fn to_string(&self) -> String {
+        match self {
+            Overflow::Visible => "visible".to_string(),
+            Overflow::Hidden => "hidden".to_string(),
+            Overflow::Clip => "clip".to_string(),
+            Overflow::Scroll => "scroll".to_string(),
+            Overflow::Auto => "auto".to_string(),
+        }
+    }
+}
+
+impl FromStr for Overflow {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "visible" => Ok(Overflow::Visible),
+            "hidden" => Ok(Overflow::Hidden),
+            "clip" => Ok(Overflow::Clip),
+            "scroll" => Ok(Overflow::Scroll),
+            "auto" => Ok(Overflow::Auto),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TextOverflow {
+    Clip,
+    Ellipsis,
+}
+
+impl ToString for TextOverflow {
+    fn to_string(&self) -> String {
+        match self {
+            TextOverflow::Clip => "clip".to_string(),
+            TextOverflow::Ellipsis => "ellipsis".to_string(),
+        }
+    }
+}
+
+impl FromStr for TextOverflow {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "clip" => Ok(TextOverflow::Clip),
+            "ellipsis" => Ok(TextOverflow::Ellipsis),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BoxSizing {
+    ContentBox,
+    BorderBox,
+}
+
+impl ToString for BoxSizing {
+    fn to_string(&self) -> String {
+        match self {
+            BoxSizing::ContentBox => "content-box".to_string(),
+            BoxSizing::BorderBox => "border-box".to_string(),
+        }
+    }
+}
+
+impl FromStr for BoxSizing {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "content-box" => Ok(BoxSizing::ContentBox),
+            "border-box" => Ok(BoxSizing::BorderBox),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Display {
+    Block,
+    InlineBlock,
+    Inline,
+    Flex,
+    Grid,
+    None,
+}
+
+impl ToString for Display {
+    fn to_string(&self) -> String {
+        match self {
+            Display::Block => "block".to_string(),
+            Display::InlineBlock => "inline-block".to_string(),
+            Display::Inline => "inline".to_string(),
+            Display::Flex => "flex".to_string(),
+            Display::Grid => "grid".to_string(),
+            Display::None => "none".to_string(),
+        }
+    }
+}
+
+impl FromStr for Display {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "block" => Ok(Display::Block),
+            "inline-block" => Ok(Display::InlineBlock),
+            "inline" => Ok(Display::Inline),
+            "flex" => Ok(Display::Flex),
+            "grid" => Ok(Display::Grid),
+            "none" => Ok(Display::None),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+/// Whether an element is painted. Hidden elements keep their place in the layout.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Visibility {
+    Visible,
+    Hidden,
+    Collapse,
+}
+
+impl ToString for Visibility {
+    fn to_string(&self) -> String {
+        match self {
+            Visibility::Visible => "visible".to_string(),
+            Visibility::Hidden => "hidden".to_string(),
+            Visibility::Collapse => "collapse".to_string(),
+        }
+    }
+}
+
+impl FromStr for Visibility {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "visible" => Ok(Visibility::Visible),
+            "hidden" => Ok(Visibility::Hidden),
+            "collapse" => Ok(Visibility::Collapse),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+/// The mouse cursor shown over an element. Single direction resize cursors are parsed as the matching two way cursor.
+///
+/// ```
+/// use yargl::css::Cursor;
+/// use std::str::FromStr;
+/// assert_eq!(Cursor::from_str("pointer").unwrap(), Cursor::Pointer);
+/// assert_eq!(Cursor::from_str("w-resize").unwrap(), Cursor::EwResize);
+/// assert!(Cursor::from_str("url(cursor.png)").is_err());
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Cursor {
+    Auto,
+    Default,
+    None,
+    Pointer,
+    Text,
+    Wait,
+    Progress,
+    Crosshair,
+    Move,
+    NotAllowed,
+    EwResize,
+    NsResize,
+    NeswResize,
+    NwseResize,
+}
+
+impl ToString for Cursor {
+    fn to_string(&self) -> String {
+        match self {
+            Cursor::Auto => "auto".to_string(),
+            Cursor::Default => "default".to_string(),
+            Cursor::None => "none".to_string(),
+            Cursor::Pointer => "pointer".to_string(),
+            Cursor::Text => "text".to_string(),
+            Cursor::Wait => "wait".to_string(),
+            Cursor::Progress => "progress".to_string(),
+            Cursor::Crosshair => "crosshair".to_string(),
+            Cursor::Move => "move".to_string(),
+            Cursor::NotAllowed => "not-allowed".to_string(),
+            Cursor::EwResize => "ew-resize".to_string(),
+            Cursor::NsResize => "ns-resize".to_string(),
+            Cursor::NeswResize => "nesw-resize".to_string(),
+            Cursor::NwseResize => "nwse-resize".to_string(),
+        }
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Cursor::Auto),
+            "default" => Ok(Cursor::Default),
+            "none" => Ok(Cursor::None),
+            "pointer" => Ok(Cursor::Pointer),
+            "text" | "vertical-text" => Ok(Cursor::Text),
+            "wait" => Ok(Cursor::Wait),
+            "progress" => Ok(Cursor::Progress),
+            "crosshair" => Ok(Cursor::Crosshair),
+            "move" | "all-scroll" => Ok(Cursor::Move),
+            "not-allowed" | "no-drop" => Ok(Cursor::NotAllowed),
+            "ew-resize" | "e-resize" | "w-resize" | "col-resize" => Ok(Cursor::EwResize),
+            "ns-resize" | "n-resize" | "s-resize" | "row-resize" => Ok(Cursor::NsResize),
+            "nesw-resize" | "ne-resize" | "sw-resize" => Ok(Cursor::NeswResize),
+            "nwse-resize" | "nw-resize" | "se-resize" => Ok(Cursor::NwseResize),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+/// Style of one side of a border.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BorderStyle {
+    None,
+    Hidden,
+    Solid,
+    Dashed,
+    Dotted,
+    Double,
+    Groove,
+    Ridge,
+    Inset,
+    Outset,
+}
+
+impl ToString for BorderStyle {
+    fn to_string(&self) -> String {
+        match self {
+            BorderStyle::None => "none".to_string(),
+            BorderStyle::Hidden => "hidden".to_string(),
+            BorderStyle::Solid => "solid".to_string(),
+            BorderStyle::Dashed => "dashed".to_string(),
+            BorderStyle::Dotted => "dotted".to_string(),
+            BorderStyle::Double => "double".to_string(),
+            BorderStyle::Groove => "groove".to_string(),
+            BorderStyle::Ridge => "ridge".to_string(),
+            BorderStyle::Inset => "inset".to_string(),
+            BorderStyle::Outset => "outset".to_string(),
+        }
+    }
+}
+
+impl FromStr for BorderStyle {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(BorderStyle::None),
+            "hidden" => Ok(BorderStyle::Hidden),
+            "solid" => Ok(BorderStyle::Solid),
+            "dashed" => Ok(BorderStyle::Dashed),
+            "dotted" => Ok(BorderStyle::Dotted),
+            "double" => Ok(BorderStyle::Double),
+            "groove" => Ok(BorderStyle::Groove),
+            "ridge" => Ok(BorderStyle::Ridge),
+            "inset" => Ok(BorderStyle::Inset),
+            "outset" => Ok(BorderStyle::Outset),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+/// The outline shorthand: width, style and color in any order. The `auto` style is parsed as solid.
+///
+/// ```
+/// use yargl::css::{Outline, BorderStyle, Unit};
+/// use std::str::FromStr;
+/// let outline = Outline::from_str("2px auto #0000ff").unwrap();
+/// assert_eq!(outline.width, Some((2.0, Some(Unit::Px))));
+/// assert_eq!(outline.style, Some(BorderStyle::Solid));
+/// assert_eq!(outline.color.unwrap().sdl_color, sdl2::pixels::Color::RGB(0, 0, 255));
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Outline {
+    pub width: Option<(f32, Option<Unit>)>,
+    pub style: Option<BorderStyle>,
+    pub color: Option<CssColor>,
+}
+
+impl FromStr for Outline {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut outline = Outline::default();
+        for part in s.split_whitespace() {
+            if part == "auto" {
+                outline.style = Some(BorderStyle::Solid);
+            } else if let Ok(style) = BorderStyle::from_str(part) {
+                outline.style = Some(style);
+            } else if let Some(dimension) = parse_dimension(part) {
+                outline.width = Some(dimension);
+            } else {
+                outline.color = Some(CssColor::from_str(part)?);
+            }
+        }
+        Ok(outline)
+    }
+}
+
+/// Expands the 1 to 4 values of a shorthand like `border-style` to the values for the top, right, bottom and left side.
+/// Returns None for any other number of values.
+///
+/// ```
+/// use yargl::css::expand_sides;
+/// assert_eq!(expand_sides(&["a"]), Some(["a", "a", "a", "a"]));
+/// assert_eq!(expand_sides(&["a", "b"]), Some(["a", "b", "a", "b"]));
+/// assert_eq!(expand_sides(&["a", "b", "c"]), Some(["a", "b", "c", "b"]));
+/// assert_eq!(expand_sides(&["a", "b", "c", "d"]), Some(["a", "b", "c", "d"]));
+/// assert_eq!(expand_sides::<&str>(&[]), None);
+/// ```
+pub fn expand_sides<T: Clone>(values: &[T]) -> Option<[T; 4]> {
+    match values {
+        [all] => Some([all.clone(), all.clone(), all.clone(), all.clone()]),
+        [vertical, horizontal] => Some([vertical.clone(), horizontal.clone(), vertical.clone(), horizontal.clone()]),
+        [top, horizontal, bottom] => Some([top.clone(), horizontal.clone(), bottom.clone(), horizontal.clone()]),
+        [top, right, bottom, left] => Some([top.clone(), right.clone(), bottom.clone(), left.clone()]),
+        _ => None,
+    }
+}
+
+/// How a replaced element's content (i.e. an image) is fitted into its box.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ObjectFit {
+    Fill,
+    Contain,
+    Cover,
+    None,
+    ScaleDown,
+}
+
+impl ToString for ObjectFit {
+    fn to_string(&self) -> String {
+        match self {
+            ObjectFit::Fill => "fill".to_string(),
+            ObjectFit::Contain => "contain".to_string(),
+            ObjectFit::Cover => "cover".to_string(),
+            ObjectFit::None => "none".to_string(),
+            ObjectFit::ScaleDown => "scale-down".to_string(),
+        }
+    }
+}
+
+impl FromStr for ObjectFit {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fill" => Ok(ObjectFit::Fill),
+            "contain" => Ok(ObjectFit::Contain),
+            "cover" => Ok(ObjectFit::Cover),
+            "none" => Ok(ObjectFit::None),
+            "scale-down" => Ok(ObjectFit::ScaleDown),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+/// Position of an object inside a box, as used by `object-position` and `background-position`.
+/// Keywords are stored as percentages, i.e. `right` is 100%. A missing second value is `center`.
+///
+/// ```
+/// use yargl::css::{Position, Unit};
+/// let position: Position = "right 10px".parse().unwrap();
+/// assert_eq!(position, Position { x: (100.0, Some(Unit::Percent)), y: (10.0, Some(Unit::Px)) });
+/// let position: Position = "top".parse().unwrap();
+/// assert_eq!(position, Position { x: (50.0, Some(Unit::Percent)), y: (0.0, Some(Unit::Percent)) });
+/// let position: Position = "bottom left".parse().unwrap();
+/// assert_eq!(position, Position { x: (0.0, Some(Unit::Percent)), y: (100.0, Some(Unit::Percent)) });
+/// assert!("left right".parse::<Position>().is_err());
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Position {
+    pub x: (f32, Option<Unit>),
+    pub y: (f32, Option<Unit>),
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Position { x: (50.0, Some(Unit::Percent)), y: (50.0, Some(Unit::Percent)) }
+    }
+}
+
+impl ToString for Position {
+    fn to_string(&self) -> String {
+        let component = |(value, unit): (f32, Option<Unit>)| format!("{}{}", value, unit.map_or(String::new(), |u| u.to_string()));
+        format!("{} {}", component(self.x), component(self.y))
+    }
+}
+
+impl FromStr for Position {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || CssParseError { css: s.to_string(), message: "invalid or unsupported position".to_string() };
+        // (value, may be horizontal, may be vertical)
+        let component = |token: &str| match token {
+            "left" => Some(((0.0, Some(Unit::Percent)), true, false)),
+            "right" => Some(((100.0, Some(Unit::Percent)), true, false)),
+            "top" => Some(((0.0, Some(Unit::Percent)), false, true)),
+            "bottom" => Some(((100.0, Some(Unit::Percent)), false, true)),
+            "center" => Some(((50.0, Some(Unit::Percent)), true, true)),
+            _ => parse_dimension(token).map(|dimension| (dimension, true, true)),
+        };
+        let center = ((50.0, Some(Unit::Percent)), true, true);
+        let tokens: Vec<&str> = s.split_whitespace().collect();
+        let (first, second) = match tokens.as_slice() {
+            [single] => {
+                let first = component(single).ok_or_else(err)?;
+                // a single vertical keyword positions vertically and centers horizontally
+                if first.1 { (first, center) } else { (center, first) }
+            },
+            [first, second] => {
+                let (first, second) = (component(first).ok_or_else(err)?, component(second).ok_or_else(err)?);
+                // keywords may be given in either order
+                if !first.1 || !second.2 { (second, first) } else { (first, second) }
+            },
+            _ => return Err(err()),
+        };
+        if !first.1 || !second.2 {
+            return Err(err());
+        }
+        Ok(Position { x: first.0, y: second.0 })
+    }
+}
+
+/// Size of a background image. Sizes of None are `auto`.
+///
+/// ```
+/// use yargl::css::{BackgroundSize, Unit};
+/// assert_eq!("cover".parse::<BackgroundSize>().unwrap(), BackgroundSize::Cover);
+/// assert_eq!("auto".parse::<BackgroundSize>().unwrap(), BackgroundSize::Size(None, None));
+/// assert_eq!("50% auto".parse::<BackgroundSize>().unwrap(), BackgroundSize::Size(Some((50.0, Some(Unit::Percent))), None));
+/// assert_eq!("20px".parse::<BackgroundSize>().unwrap(), BackgroundSize::Size(Some((20.0, Some(Unit::Px))), None));
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BackgroundSize {
+    Cover,
+    Contain,
+    Size(Option<(f32, Option<Unit>)>, Option<(f32, Option<Unit>)>),
+}
+
+impl ToString for BackgroundSize {
+    fn to_string(&self) -> String {
+        let component = |size: &Option<(f32, Option<Unit>)>| match size {
+            Some((value, unit)) => format!("{}{}", value, unit.map_or(String::new(), |u| u.to_string())),
+            None => "auto".to_string(),
+        };
+        match self {
+            BackgroundSize::Cover => "cover".to_string(),
+            BackgroundSize::Contain => "contain".to_string(),
+            BackgroundSize::Size(width, height) => format!("{} {}", component(width), component(height)),
+        }
+    }
+}
+
+impl FromStr for BackgroundSize {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || CssParseError { css: s.to_string(), message: "invalid or unsupported background size".to_string() };
+        let component = |token: &str| match token {
+            "auto" => Ok(None),
+            _ => parse_dimension(token).map(Some).ok_or_else(err),
+        };
+        match s.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            ["cover"] => Ok(BackgroundSize::Cover),
+            ["contain"] => Ok(BackgroundSize::Contain),
+            [width] => Ok(BackgroundSize::Size(component(width)?, None)),
+            [width, height] => Ok(BackgroundSize::Size(component(width)?, component(height)?)),
+            _ => Err(err()),
+        }
+    }
+}
+
+/// Whether a background image is tiled horizontally and/or vertically.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BackgroundRepeat {
+    Repeat,
+    RepeatX,
+    RepeatY,
+    NoRepeat,
+}
+
+impl BackgroundRepeat {
+    /// Whether the image is tiled horizontally and vertically.
+    pub fn axes(&self) -> (bool, bool) {
+        match self {
+            BackgroundRepeat::Repeat => (true, true),
+            BackgroundRepeat::RepeatX => (true, false),
+            BackgroundRepeat::RepeatY => (false, true),
+            BackgroundRepeat::NoRepeat => (false, false),
+        }
+    }
+}
+
+impl ToString for BackgroundRepeat {
+    fn to_string(&self) -> String {
+        match self {
+            BackgroundRepeat::Repeat => "repeat".to_string(),
+            BackgroundRepeat::RepeatX => "repeat-x".to_string(),
+            BackgroundRepeat::RepeatY => "repeat-y".to_string(),
+            BackgroundRepeat::NoRepeat => "no-repeat".to_string(),
+        }
+    }
+}
+
+impl FromStr for BackgroundRepeat {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            ["repeat"] | ["repeat", "repeat"] => Ok(BackgroundRepeat::Repeat),
+            ["repeat-x"] | ["repeat", "no-repeat"] => Ok(BackgroundRepeat::RepeatX),
+            ["repeat-y"] | ["no-repeat", "repeat"] => Ok(BackgroundRepeat::RepeatY),
+            ["no-repeat"] | ["no-repeat", "no-repeat"] => Ok(BackgroundRepeat::NoRepeat),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+/// Splits a property value at separators that are not inside of parentheses (i.e. the commas between multiple backgrounds,
+/// but not those inside of `rgb()`). Parts are trimmed, empty parts are skipped.
+///
+/// ```
+/// use yargl::css::split_top_level;
+/// assert_eq!(split_top_level("url(a.png), linear-gradient(#ff0000, rgb(0, 0, 255))", |c| c == ','), vec!["url(a.png)", "linear-gradient(#ff0000, rgb(0, 0, 255))"]);
+/// assert_eq!(split_top_level("rgb(0 0 0)  10px", char::is_whitespace), vec!["rgb(0 0 0)", "10px"]);
+/// ```
+pub fn split_top_level(value: &str, is_separator: impl Fn(char) -> bool) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if depth == 0 && is_separator(c) => {
+                parts.push(value[start..i].trim());
+                start = i + c.len_utf8();
+            },
+            _ => {}
+        }
+    }
+    parts.push(value[start..].trim());
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// Parses an angle (deg, grad, rad or turn) into degrees.
+///
+/// ```
+/// use yargl::css::parse_angle;
+/// assert_eq!(parse_angle("90deg"), Some(90.0));
+/// assert_eq!(parse_angle("0.5turn"), Some(180.0));
+/// assert_eq!(parse_angle("100grad"), Some(90.0));
+/// assert_eq!(parse_angle("0"), Some(0.0));
+/// assert_eq!(parse_angle("90px"), None);
+/// ```
+pub fn parse_angle(value: &str) -> Option<f32> {
+    let units = [("deg", 1.0), ("grad", 0.9), ("rad", 180.0 / std::f32::consts::PI), ("turn", 360.0)];
+    for (unit, degrees) in units {
+        if let Some(number) = value.strip_suffix(unit) {
+            return number.parse::<f32>().ok().map(|n| n * degrees);
+        }
+    }
+    // unitless zero is allowed for angles
+    value.parse::<f32>().ok().filter(|n| *n == 0.0)
+}
+
+/// Direction of a linear gradient.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GradientDirection {
+    /// Angle in degrees, clockwise from the top.
+    Angle(f32),
+    /// Towards a side or corner, as horizontal and vertical sign (i.e. `to top right` is (1, -1)).
+    To(i8, i8),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RadialShape {
+    Circle,
+    Ellipse,
+}
+
+/// Size of a radial gradient's ending shape.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RadialSize {
+    ClosestSide,
+    ClosestCorner,
+    FarthestSide,
+    FarthestCorner,
+    /// Radius of a circle, or horizontal and vertical radius of an ellipse.
+    Radii((f32, Option<Unit>), Option<(f32, Option<Unit>)>),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GradientKind {
+    Linear(GradientDirection),
+    Radial { shape: RadialShape, size: RadialSize, position: Position },
+    /// Starting angle in degrees, clockwise from the top.
+    Conic { from: f32, position: Position },
+}
+
+/// A color stop, or a color hint if it has no color. Angles of conic gradients are stored as percentages of a full turn.
+#[derive(Debug, PartialEq, Clone)]
+pub struct GradientStop {
+    pub color: Option<CssColor>,
+    pub position: Option<(f32, Option<Unit>)>,
+}
+
+/// A linear-gradient(), radial-gradient() or conic-gradient() or one of their repeating- variants.
+///
+/// ```
+/// use yargl::css::{Gradient, GradientKind, GradientDirection, Unit};
+/// let gradient: Gradient = "repeating-linear-gradient(to right, #ff0000, 30%, #0000ff 10px 20px)".parse().unwrap();
+/// assert!(gradient.repeating);
+/// assert_eq!(gradient.kind, GradientKind::Linear(GradientDirection::To(1, 0)));
+/// assert_eq!(gradient.stops.len(), 4);
+/// assert!(gradient.stops[1].color.is_none());
+/// assert_eq!(gradient.stops[3].position, Some((20.0, Some(Unit::Px))));
+/// let gradient: Gradient = "conic-gradient(from 90deg at left, #ff0000, #0000ff 0.5turn)".parse().unwrap();
+/// assert!(matches!(gradient.kind, GradientKind::Conic { from, .. } if from == 90.0));
+/// assert_eq!(gradient.stops[1].position, Some((50.0, Some(Unit::Percent))));
+/// assert!("radial-gradient(circle closest-side at 10px 20px, #ff0000, #0000ff)".parse::<Gradient>().is_ok());
+/// assert!("linear-gradient(#ff0000)".parse::<Gradient>().is_err());
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Gradient {
+    pub kind: GradientKind,
+    pub repeating: bool,
+    pub stops: Vec<GradientStop>,
+}
+
+impl Gradient {
+    /// Parses the optional first argument of a gradient, which is not a color stop. None if the argument is a color stop.
+    fn parse_configuration(name: &str, argument: &str) -> Option<GradientKind> {
+        let tokens = split_top_level(argument, char::is_whitespace);
+        // the position follows "at" in radial and conic gradients
+        let (tokens, position) = match tokens.iter().position(|t| *t == "at") {
+            Some(at) => (&tokens[..at], Some(tokens[at + 1..].join(" ").parse::<Position>().ok()?)),
+            None => (&tokens[..], None),
+        };
+        match name {
+            "linear-gradient" => match tokens {
+                ["to", sides @ ..] if !sides.is_empty() && sides.len() <= 2 => {
+                    let (mut x, mut y) = (0, 0);
+                    for side in sides {
+                        match *side {
+                            "left" if x == 0 => x = -1,
+                            "right" if x == 0 => x = 1,
+                            "top" if y == 0 => y = -1,
+                            "bottom" if y == 0 => y = 1,
+                            _ => return None,
+                        }
+                    }
+                    Some(GradientKind::Linear(GradientDirection::To(x, y)))
+                },
+                [angle] => parse_angle(angle).map(|a| GradientKind::Linear(GradientDirection::Angle(a))),
+                _ => None,
+            },
+            "radial-gradient" => {
+                let mut shape = None;
+                let mut size = None;
+                let mut lengths = Vec::new();
+                for token in tokens {
+                    match *token {
+                        "circle" => shape = Some(RadialShape::Circle),
+                        "ellipse" => shape = Some(RadialShape::Ellipse),
+                        "closest-side" => size = Some(RadialSize::ClosestSide),
+                        "closest-corner" => size = Some(RadialSize::ClosestCorner),
+                        "farthest-side" => size = Some(RadialSize::FarthestSide),
+                        "farthest-corner" => size = Some(RadialSize::FarthestCorner),
+                        _ => lengths.push(parse_dimension(token)?),
+                    }
+                }
+                if tokens.is_empty() && position.is_none() {
+                    return None;
+                }
+                let size = match lengths.as_slice() {
+                    [] => size.unwrap_or(RadialSize::FarthestCorner),
+                    [radius] if size.is_none() => RadialSize::Radii(*radius, None),
+                    [x, y] if size.is_none() => RadialSize::Radii(*x, Some(*y)),
+                    _ => return None,
+                };
+                // a single radius makes a circle
+                let shape = shape.unwrap_or(if matches!(size, RadialSize::Radii(_, None)) { RadialShape::Circle } else { RadialShape::Ellipse });
+                Some(GradientKind::Radial { shape, size, position: position.unwrap_or_default() })
+            },
+            "conic-gradient" => match tokens {
+                ["from", angle] => Some(GradientKind::Conic { from: parse_angle(angle)?, position: position.unwrap_or_default() }),
+                [] if position.is_some() => Some(GradientKind::Conic { from: 0.0, position: position.unwrap_or_default() }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Parses a stop position. Conic gradients use angles, which are converted to percentages of a full turn.
+    fn parse_stop_position(conic: bool, value: &str) -> Option<(f32, Option<Unit>)> {
+        if conic {
+            if let Some(degrees) = parse_angle(value) {
+                return Some((degrees / 3.6, Some(Unit::Percent)));
+            }
+            parse_dimension(value).filter(|(_, unit)| *unit == Some(Unit::Percent))
+        } else {
+            parse_dimension(value)
         }
     }
 }
 
-impl FromStr for TextAlign {
+impl FromStr for Gradient {
     type Err = CssParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "left" => Ok(TextAlign::Left),
-            "right" => Ok(TextAlign::Right),
-            "center" => Ok(TextAlign::Center),
-            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        let err = || CssParseError { css: s.to_string(), message: "invalid or unsupported gradient".to_string() };
+        let (name, arguments) = s.split_once('(').ok_or_else(err)?;
+        let arguments = arguments.strip_suffix(')').ok_or_else(err)?;
+        let (repeating, name) = match name.trim().strip_prefix("repeating-") {
+            Some(name) => (true, name),
+            None => (false, name.trim()),
+        };
+        let conic = name == "conic-gradient";
+        let mut arguments = split_top_level(arguments, |c| c == ',').into_iter().peekable();
+        let first = arguments.peek().ok_or_else(err)?;
+        let kind = match Gradient::parse_configuration(name, first) {
+            Some(kind) => {
+                arguments.next();
+                kind
+            },
+            None => match name {
+                "linear-gradient" => GradientKind::Linear(GradientDirection::Angle(180.0)),
+                "radial-gradient" => GradientKind::Radial { shape: RadialShape::Ellipse, size: RadialSize::FarthestCorner, position: Position::default() },
+                "conic-gradient" => GradientKind::Conic { from: 0.0, position: Position::default() },
+                _ => return Err(err()),
+            },
+        };
+        let mut stops = Vec::new();
+        for argument in arguments {
+            match split_top_level(argument, char::is_whitespace).as_slice() {
+                [color] if color.parse::<CssColor>().is_ok() => stops.push(GradientStop { color: color.parse().ok(), position: None }),
+                [hint] => stops.push(GradientStop { color: None, position: Some(Gradient::parse_stop_position(conic, hint).ok_or_else(err)?) }),
+                [color, positions @ ..] if positions.len() <= 2 => {
+                    let color = color.parse::<CssColor>()?;
+                    // a stop with two positions is a band of one color
+                    for position in positions {
+                        stops.push(GradientStop { color: Some(color.clone()), position: Some(Gradient::parse_stop_position(conic, position).ok_or_else(err)?) });
+                    }
+                },
+                _ => return Err(err()),
+            }
+        }
+        if stops.iter().filter(|stop| stop.color.is_some()).count() < 2 {
+            return Err(err());
         }
+        Ok(Gradient { kind, repeating, stops })
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub enum BoxSizing {
-    ContentBox,
-    BorderBox,
+/// One layer of a background-image.
+///
+/// ```
+/// use yargl::css::BackgroundImage;
+/// let image: BackgroundImage = "image-set(\"a.png\" 1x, url(a@2x.png) 2x, \"a-print.png\" 300dpi)".parse().unwrap();
+/// assert_eq!(image.source(1.0), Some(("a.png", 1.0)));
+/// assert_eq!(image.source(1.5), Some(("a@2x.png", 2.0)));
+/// assert_eq!(image.source(4.0), Some(("a-print.png", 3.125)));
+/// let image: BackgroundImage = "url(b.png)".parse().unwrap();
+/// assert_eq!(image.source(2.0), Some(("b.png", 1.0)));
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub enum BackgroundImage {
+    Url(String),
+    /// The candidates of an image-set() with their resolution in image pixels per css pixel.
+    ImageSet(Vec<(String, f32)>),
+    Gradient(Gradient),
 }
 
-impl ToString for BoxSizing {
-    fn to_string(&self) -> String {
+impl BackgroundImage {
+    /// The url of the image and its resolution. For an image-set() this is the candidate with the lowest resolution that is at least
+    /// the device pixel ratio, or the one with the highest resolution if all are lower. None for gradients.
+    pub fn source(&self, device_pixel_ratio: f32) -> Option<(&str, f32)> {
         match self {
-            BoxSizing::ContentBox => "content-box".to_string(),
-            BoxSizing::BorderBox => "border-box".to_string(),
+            BackgroundImage::Url(url) => Some((url.as_str(), 1.0)),
+            BackgroundImage::ImageSet(candidates) => candidates.iter()
+                .filter(|(_, resolution)| *resolution >= device_pixel_ratio)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .or_else(|| candidates.iter().max_by(|a, b| a.1.total_cmp(&b.1)))
+                .map(|(url, resolution)| (url.as_str(), *resolution)),
+            BackgroundImage::Gradient(_) => None,
         }
     }
 }
 
-impl FromStr for BoxSizing {
+impl FromStr for BackgroundImage {
     type Err = CssParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "content-box" => Ok(BoxSizing::ContentBox),
-            "border-box" => Ok(BoxSizing::BorderBox),
-            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        let s = s.trim();
+        if let Some(arguments) = s.strip_prefix("image-set(").or_else(|| s.strip_prefix("-webkit-image-set(")).and_then(|rest| rest.strip_suffix(')')) {
+            let err = || CssParseError { css: s.to_string(), message: "invalid image-set candidate".to_string() };
+            let mut candidates = Vec::new();
+            for candidate in split_top_level(arguments, |c| c == ',') {
+                let mut parts = split_top_level(candidate, char::is_whitespace).into_iter();
+                let image = parts.next().ok_or_else(err)?;
+                let url = match parse_urls(image).into_iter().next() {
+                    Some(url) => url,
+                    None if image.starts_with('"') || image.starts_with('\'') => image.trim_matches(|c| c == '"' || c == '\'').to_string(),
+                    None => return Err(err()),
+                };
+                // the resolution defaults to 1x, type() hints are ignored
+                let mut resolution = 1.0;
+                for part in parts.filter(|part| !part.starts_with("type(")) {
+                    resolution = if let Some(value) = part.strip_suffix("dppx").or_else(|| part.strip_suffix('x')) {
+                        value.parse::<f32>().map_err(|_| err())?
+                    } else if let Some(value) = part.strip_suffix("dpi") {
+                        value.parse::<f32>().map_err(|_| err())? / 96.0
+                    } else if let Some(value) = part.strip_suffix("dpcm") {
+                        value.parse::<f32>().map_err(|_| err())? * 2.54 / 96.0
+                    } else {
+                        return Err(err());
+                    };
+                }
+                candidates.push((url, resolution));
+            }
+            return if candidates.is_empty() { Err(err()) } else { Ok(BackgroundImage::ImageSet(candidates)) };
+        }
+        match parse_urls(s).first() {
+            Some(url) => Ok(BackgroundImage::Url(url.clone())),
+            None => s.parse::<Gradient>().map(BackgroundImage::Gradient),
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub enum Display {
-    Block,
-    InlineBlock,
-    Inline,
-    Flex,
-    Grid,
-    None,
+/// One shadow of `box-shadow` or `text-shadow`. Text shadows have no spread and are never inset.
+/// The color is None if the shadow should use the element's color.
+///
+/// ```
+/// use yargl::css::{Shadow, Unit, parse_shadows};
+/// let shadow: Shadow = "inset 2px -3px 4px 1em rgba(0, 0, 0, 0.5)".parse().unwrap();
+/// assert!(shadow.inset);
+/// assert_eq!(shadow.offset_y, (-3.0, Some(Unit::Px)));
+/// assert_eq!(shadow.spread, (1.0, Some(Unit::Em)));
+/// assert_eq!(shadow.color.unwrap().sdl_color.a, 128);
+/// let shadow: Shadow = "#ff0000 1px 2px".parse().unwrap();
+/// assert_eq!(shadow.blur, (0.0, None));
+/// assert_eq!(parse_shadows("1px 1px #000000, 0 0 4px #ffffff").len(), 2);
+/// assert!(parse_shadows("none").is_empty());
+/// assert!("1px".parse::<Shadow>().is_err());
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Shadow {
+    pub inset: bool,
+    pub offset_x: (f32, Option<Unit>),
+    pub offset_y: (f32, Option<Unit>),
+    pub blur: (f32, Option<Unit>),
+    pub spread: (f32, Option<Unit>),
+    pub color: Option<CssColor>,
 }
 
-impl ToString for Display {
-    fn to_string(&self) -> String {
-        match self {
-            Display::Block => "block".to_string(),
-            Display::InlineBlock => "inline-block".to_string(),
-            Display::Inline => "inline".to_string(),
-            Display::Flex => "flex".to_string(),
-            Display::Grid => "grid".to_string(),
-            Display::None => "none".to_string(),
+impl FromStr for Shadow {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || CssParseError { css: s.to_string(), message: "invalid or unsupported shadow".to_string() };
+        let mut inset = false;
+        let mut color = None;
+        let mut lengths = Vec::new();
+        for token in split_top_level(s, char::is_whitespace) {
+            if token == "inset" && !inset {
+                inset = true;
+            } else if let Some(length) = parse_dimension(token) {
+                lengths.push(length);
+            } else if color.is_none() {
+                color = Some(token.parse::<CssColor>()?);
+            } else {
+                return Err(err());
+            }
         }
+        let zero = (0.0, None);
+        let (offset_x, offset_y, blur, spread) = match lengths.as_slice() {
+            [x, y] => (*x, *y, zero, zero),
+            [x, y, blur] => (*x, *y, *blur, zero),
+            [x, y, blur, spread] => (*x, *y, *blur, *spread),
+            _ => return Err(err()),
+        };
+        if blur.0 < 0.0 {
+            return Err(err());
+        }
+        Ok(Shadow { inset, offset_x, offset_y, blur, spread, color })
     }
 }
 
-impl FromStr for Display {
+/// Parses a comma-separated list of shadows. Returns no shadows for `none` or if any shadow is invalid.
+pub fn parse_shadows(value: &str) -> Vec<Shadow> {
+    if value.trim() == "none" {
+        return Vec::new();
+    }
+    split_top_level(value, |c| c == ',').into_iter().map(|shadow| shadow.parse::<Shadow>()).collect::<Result<Vec<Shadow>, CssParseError>>().unwrap_or_default()
+}
+
+/// A single function of the `transform` property. Angles are in degrees, lengths may be percentages of the border box.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TransformFunction {
+    /// `matrix(a, b, c, d, e, f)`
+    Matrix([f32; 6]),
+    Translate((f32, Option<Unit>), (f32, Option<Unit>)),
+    Scale(f32, f32),
+    Rotate(f32),
+    Skew(f32, f32),
+}
+
+impl FromStr for TransformFunction {
     type Err = CssParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "block" => Ok(Display::Block),
-            "inline-block" => Ok(Display::InlineBlock),
-            "inline" => Ok(Display::Inline),
-            "flex" => Ok(Display::Flex),
-            "grid" => Ok(Display::Grid),
-            "none" => Ok(Display::None),
-            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
-        }
+        let err = || CssParseError { css: s.to_string(), message: "invalid or unsupported transform function".to_string() };
+        let (name, arguments) = s.strip_suffix(')').and_then(|s| s.split_once('(')).ok_or_else(err)?;
+        let arguments = split_top_level(arguments, |c| c == ',' || c.is_whitespace());
+        let numbers = || arguments.iter().map(|argument| argument.parse::<f32>().ok()).collect::<Option<Vec<f32>>>();
+        let lengths = || arguments.iter().map(|argument| parse_dimension(argument)).collect::<Option<Vec<(f32, Option<Unit>)>>>();
+        let angles = || arguments.iter().map(|argument| parse_angle(argument)).collect::<Option<Vec<f32>>>();
+        let zero = (0.0, None);
+        let function = match name.trim() {
+            "matrix" => match numbers().as_deref() {
+                Some(&[a, b, c, d, e, f]) => TransformFunction::Matrix([a, b, c, d, e, f]),
+                _ => return Err(err()),
+            },
+            "translate" => match lengths().as_deref() {
+                Some(&[x]) => TransformFunction::Translate(x, zero),
+                Some(&[x, y]) => TransformFunction::Translate(x, y),
+                _ => return Err(err()),
+            },
+            "translateX" => match lengths().as_deref() {
+                Some(&[x]) => TransformFunction::Translate(x, zero),
+                _ => return Err(err()),
+            },
+            "translateY" => match lengths().as_deref() {
+                Some(&[y]) => TransformFunction::Translate(zero, y),
+                _ => return Err(err()),
+            },
+            "scale" => match numbers().as_deref() {
+                Some(&[scale]) => TransformFunction::Scale(scale, scale),
+                Some(&[x, y]) => TransformFunction::Scale(x, y),
+                _ => return Err(err()),
+            },
+            "scaleX" => match numbers().as_deref() {
+                Some(&[x]) => TransformFunction::Scale(x, 1.0),
+                _ => return Err(err()),
+            },
+            "scaleY" => match numbers().as_deref() {
+                Some(&[y]) => TransformFunction::Scale(1.0, y),
+                _ => return Err(err()),
+            },
+            "rotate" => match angles().as_deref() {
+                Some(&[angle]) => TransformFunction::Rotate(angle),
+                _ => return Err(err()),
+            },
+            "skew" => match angles().as_deref() {
+                Some(&[x]) => TransformFunction::Skew(x, 0.0),
+                Some(&[x, y]) => TransformFunction::Skew(x, y),
+                _ => return Err(err()),
+            },
+            "skewX" => match angles().as_deref() {
+                Some(&[x]) => TransformFunction::Skew(x, 0.0),
+                _ => return Err(err()),
+            },
+            "skewY" => match angles().as_deref() {
+                Some(&[y]) => TransformFunction::Skew(0.0, y),
+                _ => return Err(err()),
+            },
+            _ => return Err(err()),
+        };
+        Ok(function)
+    }
+}
+
+/// Parses the value of the `transform` property into its functions, which are applied from left to right.
+/// Returns no functions for `none` or if any function is invalid.
+///
+/// ```
+/// use yargl::css::{parse_transform, TransformFunction, Unit};
+/// assert_eq!(parse_transform("translate(10px, 50%) rotate(0.25turn)"), vec![
+///     TransformFunction::Translate((10.0, Some(Unit::Px)), (50.0, Some(Unit::Percent))),
+///     TransformFunction::Rotate(90.0),
+/// ]);
+/// assert_eq!(parse_transform("scale(2) skewY(45deg)"), vec![TransformFunction::Scale(2.0, 2.0), TransformFunction::Skew(0.0, 45.0)]);
+/// assert_eq!(parse_transform("matrix(1, 0, 0, 1, 5, 6)"), vec![TransformFunction::Matrix([1.0, 0.0, 0.0, 1.0, 5.0, 6.0])]);
+/// assert!(parse_transform("none").is_empty());
+/// assert!(parse_transform("rotate(45px)").is_empty());
+/// ```
+pub fn parse_transform(value: &str) -> Vec<TransformFunction> {
+    if value.trim() == "none" {
+        return Vec::new();
+    }
+    split_top_level(value, char::is_whitespace).into_iter().map(|function| function.parse::<TransformFunction>()).collect::<Result<Vec<TransformFunction>, CssParseError>>().unwrap_or_default()
+}
+
+/// A single function of the `filter` or `backdrop-filter` property.
+/// Amounts are numbers (percentages are divided by 100) and angles are in degrees.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Filter {
+    Blur((f32, Option<Unit>)),
+    Brightness(f32),
+    Contrast(f32),
+    Grayscale(f32),
+    HueRotate(f32),
+    Invert(f32),
+    Opacity(f32),
+    Saturate(f32),
+    Sepia(f32),
+    /// A shadow of the element's alpha, without spread or inset.
+    DropShadow(Shadow),
+}
+
+impl FromStr for Filter {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || CssParseError { css: s.to_string(), message: "invalid or unsupported filter function".to_string() };
+        let (name, argument) = s.strip_suffix(')').and_then(|s| s.split_once('(')).ok_or_else(err)?;
+        let argument = argument.trim();
+        // amounts default to the value that leaves the element unchanged, or to its maximum effect for filters that are off at 0
+        let amount = |default: f32, max: Option<f32>| -> Result<f32, CssParseError> {
+            let value = match argument {
+                "" => default,
+                _ => match parse_dimension(argument).ok_or_else(err)? {
+                    (value, Some(Unit::Percent)) => value / 100.0,
+                    (value, None) => value,
+                    _ => return Err(err()),
+                },
+            };
+            if value < 0.0 {
+                return Err(err());
+            }
+            Ok(max.map_or(value, |max| value.min(max)))
+        };
+        let filter = match name.trim() {
+            "blur" => match argument {
+                "" => Filter::Blur((0.0, None)),
+                _ => Filter::Blur(parse_dimension(argument).filter(|(value, unit)| *value >= 0.0 && *unit != Some(Unit::Percent)).ok_or_else(err)?),
+            },
+            "brightness" => Filter::Brightness(amount(1.0, None)?),
+            "contrast" => Filter::Contrast(amount(1.0, None)?),
+            "grayscale" => Filter::Grayscale(amount(1.0, Some(1.0))?),
+            "hue-rotate" => Filter::HueRotate(match argument {
+                "" => 0.0,
+                _ => parse_angle(argument).ok_or_else(err)?,
+            }),
+            "invert" => Filter::Invert(amount(1.0, Some(1.0))?),
+            "opacity" => Filter::Opacity(amount(1.0, Some(1.0))?),
+            "saturate" => Filter::Saturate(amount(1.0, None)?),
+            "sepia" => Filter::Sepia(amount(1.0, Some(1.0))?),
+            "drop-shadow" => {
+                let shadow = argument.parse::<Shadow>()?;
+                if shadow.inset || shadow.spread.0 != 0.0 {
+                    return Err(err());
+                }
+                Filter::DropShadow(shadow)
+            },
+            _ => return Err(err()),
+        };
+        Ok(filter)
     }
 }
 
+/// Parses the value of the `filter` or `backdrop-filter` property into its functions, which are applied from left to right.
+/// Returns no filters for `none` or if any function is invalid.
+///
+/// ```
+/// use yargl::css::{parse_filters, Filter, Unit};
+/// assert_eq!(parse_filters("grayscale(100%) blur(2px)"), vec![Filter::Grayscale(1.0), Filter::Blur((2.0, Some(Unit::Px)))]);
+/// assert_eq!(parse_filters("invert() brightness(0.5) hue-rotate(0.5turn)"), vec![Filter::Invert(1.0), Filter::Brightness(0.5), Filter::HueRotate(180.0)]);
+/// let filters = parse_filters("drop-shadow(2px 4px 6px rgba(0, 0, 0, 0.5))");
+/// assert!(matches!(&filters[..], [Filter::DropShadow(shadow)] if shadow.blur == (6.0, Some(Unit::Px))));
+/// assert!(parse_filters("none").is_empty());
+/// assert!(parse_filters("blur(-1px)").is_empty());
+/// ```
+pub fn parse_filters(value: &str) -> Vec<Filter> {
+    if value.trim() == "none" {
+        return Vec::new();
+    }
+    split_top_level(value, char::is_whitespace).into_iter().map(|filter| filter.parse::<Filter>()).collect::<Result<Vec<Filter>, CssParseError>>().unwrap_or_default()
+}
+
 #[derive(Debug, PartialEq)]
 pub enum FlexDirection {
     Row,
@@ -206,6 +1524,112 @@ impl FromStr for FlexDirection {
     }
 }
 
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FontStyle {
+    Normal,
+    Italic,
+    Oblique,
+}
+
+impl ToString for FontStyle {
+    fn to_string(&self) -> String {
+        match self {
+            FontStyle::Normal => "normal".to_string(),
+            FontStyle::Italic => "italic".to_string(),
+            FontStyle::Oblique => "oblique".to_string(),
+        }
+    }
+}
+
+impl FromStr for FontStyle {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(FontStyle::Normal),
+            "italic" => Ok(FontStyle::Italic),
+            "oblique" => Ok(FontStyle::Oblique),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() }),
+        }
+    }
+}
+
+/// Numeric font weight (1 to 1000). Keywords are converted to their numeric value.
+/// Since properties are not inherited, `bolder` and `lighter` are resolved relative to `normal` (400).
+///
+/// ```
+/// use yargl::css::FontWeight;
+/// use std::str::FromStr;
+/// assert_eq!(FontWeight::from_str("normal").unwrap(), FontWeight(400));
+/// assert_eq!(FontWeight::from_str("bold").unwrap(), FontWeight(700));
+/// assert_eq!(FontWeight::from_str("350").unwrap(), FontWeight(350));
+/// assert!(FontWeight::from_str("1001").is_err());
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct FontWeight(pub u16);
+
+impl ToString for FontWeight {
+    fn to_string(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl FromStr for FontWeight {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(FontWeight(400)),
+            "bold" | "bolder" => Ok(FontWeight(700)),
+            "lighter" => Ok(FontWeight(100)),
+            _ => match s.parse::<f32>() {
+                Ok(weight) if (1.0..=1000.0).contains(&weight) => Ok(FontWeight(weight as u16)),
+                _ => Err(CssParseError { css: s.to_string(), message: "invalid font weight".to_string() }),
+            }
+        }
+    }
+}
+
+/// Font stretch as a percentage of the normal width. Keywords are converted to their percentage.
+///
+/// ```
+/// use yargl::css::FontStretch;
+/// use std::str::FromStr;
+/// assert_eq!(FontStretch::from_str("normal").unwrap(), FontStretch(100.0));
+/// assert_eq!(FontStretch::from_str("semi-condensed").unwrap(), FontStretch(87.5));
+/// assert_eq!(FontStretch::from_str("150").unwrap(), FontStretch(150.0));
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct FontStretch(pub f32);
+
+impl ToString for FontStretch {
+    fn to_string(&self) -> String {
+        format!("{}%", self.0)
+    }
+}
+
+impl FromStr for FontStretch {
+    type Err = CssParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ultra-condensed" => Ok(FontStretch(50.0)),
+            "extra-condensed" => Ok(FontStretch(62.5)),
+            "condensed" => Ok(FontStretch(75.0)),
+            "semi-condensed" => Ok(FontStretch(87.5)),
+            "normal" => Ok(FontStretch(100.0)),
+            "semi-expanded" => Ok(FontStretch(112.5)),
+            "expanded" => Ok(FontStretch(125.0)),
+            "extra-expanded" => Ok(FontStretch(150.0)),
+            "ultra-expanded" => Ok(FontStretch(200.0)),
+            _ => match s.trim_end_matches('%').parse::<f32>() {
+                Ok(stretch) if stretch >= 0.0 => Ok(FontStretch(stretch)),
+                _ => Err(CssParseError { css: s.to_string(), message: "invalid font stretch".to_string() }),
+            }
+        }
+    }
+}
+
 /// CSS specificity. Used to resolve conflicts between rules.
 /// 
 /// ```
@@ -276,12 +1700,88 @@ impl Specificity {
     }
 }
 
+/// A pseudo-class that matches elements by their interaction state.
+#[derive(Debug, PartialEq, Clone)]
+pub enum PseudoClass {
+    /// The element or one of its descendants is below a pointer.
+    Hover,
+    /// The element or one of its descendants is pressed by a pointer.
+    Active,
+    Focus,
+    /// The element has focus and the focus should be indicated, i.e. because it was moved with the keyboard.
+    FocusVisible,
+    /// The element or one of its descendants has focus.
+    FocusWithin,
+    /// An unsupported pseudo-class (or pseudo-element), selectors that contain it never match.
+    Unsupported(String),
+}
+
+impl ToString for PseudoClass {
+    fn to_string(&self) -> String {
+        match self {
+            PseudoClass::Hover => ":hover".to_string(),
+            PseudoClass::Active => ":active".to_string(),
+            PseudoClass::Focus => ":focus".to_string(),
+            PseudoClass::FocusVisible => ":focus-visible".to_string(),
+            PseudoClass::FocusWithin => ":focus-within".to_string(),
+            PseudoClass::Unsupported(name) => format!(":{}", name),
+        }
+    }
+}
+
+impl FromStr for PseudoClass {
+    type Err = CssParseError;
+
+    /// Parses the name of a pseudo-class, without the colon.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hover" => Ok(PseudoClass::Hover),
+            "active" => Ok(PseudoClass::Active),
+            "focus" => Ok(PseudoClass::Focus),
+            "focus-visible" => Ok(PseudoClass::FocusVisible),
+            "focus-within" => Ok(PseudoClass::FocusWithin),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported pseudo-class".to_string() }),
+        }
+    }
+}
+
+/// A pseudo-element, which styles a part of an element instead of the element.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PseudoElement {
+    /// The selected text of the element. Only the color and background-color are used.
+    Selection,
+}
+
+impl ToString for PseudoElement {
+    fn to_string(&self) -> String {
+        match self {
+            PseudoElement::Selection => "::selection".to_string(),
+        }
+    }
+}
+
+impl FromStr for PseudoElement {
+    type Err = CssParseError;
+
+    /// Parses the name of a pseudo-element, without the colons.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "selection" => Ok(PseudoElement::Selection),
+            _ => Err(CssParseError { css: s.to_string(), message: "invalid or unsupported pseudo-element".to_string() }),
+        }
+    }
+}
+
 /// A CSS selector.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Selector {
     pub tag_name: Option<String>,
     pub class_list: Vec<String>,
     pub id: Option<String>,
+    /// Pseudo-classes that the element must match in addition to the tag name, classes and id.
+    pub pseudo_classes: Vec<PseudoClass>,
+    /// The part of the matched element that is styled, the whole element if None.
+    pub pseudo_element: Option<PseudoElement>,
 }
 
 impl Selector {
@@ -291,9 +1791,16 @@ impl Selector {
             tag_name,
             class_list,
             id,
+            pseudo_classes: Vec::new(),
+            pseudo_element: None,
         }
     }
 
+    /// This selector without its pseudo-classes and pseudo-element, which only matches by the document.
+    pub fn without_pseudo_classes(&self) -> Selector {
+        Selector::new(self.tag_name.clone(), self.class_list.clone(), self.id.clone())
+    }
+
     /// Returns a selector with the tag name, all classes, and id (if it exists) of the given node.
     pub fn complete_selector(node: &tl::Node) -> Selector {
         let mut class_list = Vec::new();
@@ -321,23 +1828,33 @@ impl Selector {
     /// Returns the specificity of this selector.
     /// 
     /// ```
-    /// use yargl::css::{Selector, Specificity};
+    /// use yargl::css::{Selector, Specificity, PseudoClass, PseudoElement};
     /// let a = Selector::new(None, vec!["a".to_string(), "b".to_string()], None);
     /// assert_eq!(a.specificity(), Specificity::new(0, 2, 0));
     /// let b = Selector::new(Some("div".to_string()), vec!["a".to_string(), "b".to_string()], None);
     /// assert_eq!(b.specificity(), Specificity::new(0, 2, 1));
     /// let c = Selector::new(Some("div".to_string()), vec!["a".to_string(), "b".to_string()], Some("c".to_string()));
     /// assert_eq!(c.specificity(), Specificity::new(1, 2, 1));
+    /// let mut d = Selector::new(Some("a".to_string()), vec![], None);
+    /// d.pseudo_classes.push(PseudoClass::Hover);
+    /// assert_eq!(d.specificity(), Specificity::new(0, 1, 1));
+    /// d.pseudo_element = Some(PseudoElement::Selection);
+    /// assert_eq!(d.specificity(), Specificity::new(0, 1, 2));
     /// ```
     pub fn specificity(&self) -> Specificity {
         let mut specificity = Specificity::new(0, 0, 0);
         if self.id.is_some() {
             specificity.add_id();
         }
-        specificity.add_classes(self.class_list.len() as u32);
+        // pseudo-classes count like classes
+        specificity.add_classes((self.class_list.len() + self.pseudo_classes.len()) as u32);
         if self.tag_name.is_some() {
             specificity.add_tag();
         }
+        // pseudo-elements count like tags
+        if self.pseudo_element.is_some() {
+            specificity.add_tag();
+        }
         specificity
     }
 }
@@ -346,7 +1863,7 @@ impl ToString for Selector {
     /// Returns a string representation of this selector (valid CSS).
     /// 
     /// ```
-    /// use yargl::css::Selector;
+    /// use yargl::css::{Selector, PseudoClass, PseudoElement};
     /// let a = Selector::new(None, vec!["a".to_string(), "b".to_string()], None);
     /// let a_str = a.to_string();
     /// assert_eq!(a_str, ".a.b");
@@ -359,6 +1876,13 @@ impl ToString for Selector {
     /// let d = Selector::new(None, vec![], None);
     /// let d_str = d.to_string();
     /// assert_eq!(d_str, "");
+    /// let mut e = Selector::new(Some("a".to_string()), vec![], None);
+    /// e.pseudo_classes.push(PseudoClass::FocusVisible);
+    /// assert_eq!(e.to_string(), "a:focus-visible");
+    /// assert_eq!(e.without_pseudo_classes().to_string(), "a");
+    /// e.pseudo_element = Some(PseudoElement::Selection);
+    /// assert_eq!(e.to_string(), "a:focus-visible::selection");
+    /// assert_eq!(e.without_pseudo_classes().to_string(), "a");
     /// ```
     fn to_string(&self) -> String {
         let mut selector = String::new();
@@ -373,6 +1897,12 @@ impl ToString for Selector {
             selector.push('#');
             selector.push_str(id);
         }
+        for pseudo_class in &self.pseudo_classes {
+            selector.push_str(&pseudo_class.to_string());
+        }
+        if let Some(pseudo_element) = &self.pseudo_element {
+            selector.push_str(&pseudo_element.to_string());
+        }
         selector
     }
 }
@@ -385,7 +1915,7 @@ pub struct CssColor {
 
 impl FromStr for CssColor {
     type Err = CssParseError;
-    /// Parses a color from a CSS color value. Only hex colors (no shorthand formats) are supported (yet).
+    /// Parses a color from a CSS color value. Hex colors (with optional alpha, no shorthand formats), rgb(), rgba() and transparent are supported.
     /// ```
     /// use yargl::css::CssColor;
     /// use std::str::FromStr;
@@ -397,39 +1927,137 @@ impl FromStr for CssColor {
     /// assert_eq!(color.sdl_color, sdl2::pixels::Color::RGB(255, 255, 255));
     /// let color = CssColor::from_str("#fa017f").unwrap();
     /// assert_eq!(color.sdl_color, sdl2::pixels::Color::RGB(250, 1, 127));
+    /// let color = CssColor::from_str("#fa017f80").unwrap();
+    /// assert_eq!(color.sdl_color, sdl2::pixels::Color::RGBA(250, 1, 127, 128));
+    /// let color = CssColor::from_str("rgba(255, 0, 100%, 0.5)").unwrap();
+    /// assert_eq!(color.sdl_color, sdl2::pixels::Color::RGBA(255, 0, 255, 128));
+    /// let color = CssColor::from_str("rgb(10 20 30 / 25%)").unwrap();
+    /// assert_eq!(color.sdl_color, sdl2::pixels::Color::RGBA(10, 20, 30, 64));
+    /// let color = CssColor::from_str("transparent").unwrap();
+    /// assert_eq!(color.sdl_color.a, 0);
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut chars = s.chars();
-        if chars.next() == Some('#') {
+        let err = || CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() };
+        if let Some(hex) = s.strip_prefix('#') {
+            if (hex.len() != 6 && hex.len() != 8) || !hex.is_ascii() {
+                return Err(err());
+            }
             let mut color: Vec<u8> = Vec::new();
-            for _ in 0..3 {
-                let mut hex: String = String::new();
-                for _ in 0..2 {
-                    match chars.next() {
-                        Some(ch) => hex.push(ch),
-                        None => return Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() })
-                    }
-                }
-                match u8::from_str_radix(&hex, 16) {
+            for i in (0..hex.len()).step_by(2) {
+                match u8::from_str_radix(&hex[i..i + 2], 16) {
                     Ok(num) => color.push(num),
-                    Err(_) => return Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() })
+                    Err(_) => return Err(err())
                 }
             }
-            Ok(CssColor { sdl_color: sdl2::pixels::Color::RGB(color[0], color[1], color[2]) })
+            Ok(CssColor { sdl_color: sdl2::pixels::Color::RGBA(color[0], color[1], color[2], color.get(3).copied().unwrap_or(0xFF)) })
+        } else if let Some(arguments) = s.strip_prefix("rgba(").or(s.strip_prefix("rgb(")).and_then(|a| a.strip_suffix(')')) {
+            // channels are numbers from 0 to 255 or percentages, alpha is a number from 0 to 1 or a percentage
+            let channel = |value: &str, max: f32| match value.strip_suffix('%') {
+                Some(percent) => percent.parse::<f32>().ok().map(|p| p / 100.0 * max),
+                None => value.parse::<f32>().ok(),
+            }.map(|c| (c.clamp(0.0, max) * 255.0 / max).round() as u8);
+            let values: Vec<&str> = arguments.split(|c: char| c == ',' || c == '/' || c.is_whitespace()).filter(|v| !v.is_empty()).collect();
+            let (r, g, b, a) = match values.as_slice() {
+                [r, g, b] => (channel(r, 255.0), channel(g, 255.0), channel(b, 255.0), Some(0xFF)),
+                [r, g, b, a] => (channel(r, 255.0), channel(g, 255.0), channel(b, 255.0), channel(a, 1.0)),
+                _ => return Err(err())
+            };
+            match (r, g, b, a) {
+                (Some(r), Some(g), Some(b), Some(a)) => Ok(CssColor { sdl_color: sdl2::pixels::Color::RGBA(r, g, b, a) }),
+                _ => Err(err())
+            }
+        } else if s == "transparent" {
+            Ok(CssColor { sdl_color: sdl2::pixels::Color::RGBA(0, 0, 0, 0) })
         } else {
-            Err(CssParseError { css: s.to_string(), message: "invalid or unsupported enum value".to_string() })
+            Err(err())
+        }
+    }
+}
+
+/// The media query list of an @media rule. Supported are the media types all and screen, and the resolution, min-resolution and max-resolution features
+/// (in dppx, x, dpi or dpcm) combined with "and". Queries with other media types or features never match.
+///
+/// ```
+/// use yargl::css::MediaQuery;
+/// let query = MediaQuery::parse("screen and (min-resolution: 2dppx), (max-resolution: 96dpi)");
+/// assert!(query.matches(1.0));
+/// assert!(!query.matches(1.5));
+/// assert!(query.matches(2.0));
+/// assert!(MediaQuery::parse("(resolution: 1.5x)").matches(1.5));
+/// assert!(!MediaQuery::parse("print").matches(1.0));
+/// assert!(!MediaQuery::parse("(min-width: 100px)").matches(1.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaQuery {
+    // range of device pixel ratios of each query of the list, None if the query is not supported
+    resolutions: Vec<Option<(f32, f32)>>,
+}
+
+impl MediaQuery {
+    /// Parses a comma-separated media query list, as written between @media and the block of the rule.
+    pub fn parse(list: &str) -> MediaQuery {
+        let list = list.to_lowercase();
+        MediaQuery { resolutions: list.split(',').map(resolution_range).collect() }
+    }
+
+    /// Whether one of the queries of the list matches a display with the given device pixel ratio.
+    pub fn matches(&self, device_pixel_ratio: f32) -> bool {
+        self.resolutions.iter().flatten().any(|(min, max)| (*min..=*max).contains(&device_pixel_ratio))
+    }
+}
+
+/// Range of device pixel ratios that a single media query matches. None if it has an unsupported media type or feature.
+fn resolution_range(query: &str) -> Option<(f32, f32)> {
+    let query = query.split_whitespace().collect::<Vec<_>>().join(" ");
+    let query = query.strip_prefix("only ").unwrap_or(&query);
+    let (mut min, mut max) = (0.0_f32, f32::INFINITY);
+    for condition in query.split(" and ") {
+        match condition {
+            "all" | "screen" => {},
+            feature => {
+                let (name, value) = feature.strip_prefix('(')?.strip_suffix(')')?.split_once(':')?;
+                let ratio = resolution(value.trim())?;
+                match name.trim() {
+                    "resolution" => (min, max) = (min.max(ratio), max.min(ratio)),
+                    "min-resolution" => min = min.max(ratio),
+                    "max-resolution" => max = max.min(ratio),
+                    _ => return None,
+                }
+            },
         }
     }
+    Some((min, max))
+}
+
+/// Device pixel ratio of a resolution value, i.e. 2dppx, 2x, 192dpi or 75.6dpcm.
+fn resolution(value: &str) -> Option<f32> {
+    let unit_start = value.find(|c: char| c.is_ascii_alphabetic())?;
+    let number = value[..unit_start].parse::<f32>().ok()?;
+    match &value[unit_start..] {
+        "dppx" | "x" => Some(number),
+        "dpi" => Some(number / 96.0),
+        "dpcm" => Some(number * 2.54 / 96.0),
+        _ => None,
+    }
 }
 
 #[derive(Debug)]
 /// Holds data of a CSS style rule.
 pub struct Style {
     pub selectors: Vec<Selector>,
+    /// Name of the at-rule this style belongs to (i.e. "font-face"), None for normal style rules.
+    pub at_rule: Option<String>,
+    /// Media queries of the @media rules around this style, which all have to match for the style to apply.
+    pub media: Vec<MediaQuery>,
     properties: HashMap<String, String>,
 }
 
 impl Style {
+    /// Whether the media queries of the @media rules around this style match a display with the given device pixel ratio.
+    pub fn matches_media(&self, device_pixel_ratio: f32) -> bool {
+        self.media.iter().all(|query| query.matches(device_pixel_ratio))
+    }
+
     /// Returns the value of the given property, with a unit if it has one.
     /// For supported units, see [Unit].
     /// ```
@@ -475,6 +2103,10 @@ impl Style {
             (None, None)
         }
     }
+    /// Returns the value of the given property as written, without splitting off a unit. Use this for shorthand properties.
+    pub fn get_raw_value(&self, property: &str) -> Option<String> {
+        self.properties.get(property).cloned()
+    }
     /// Sets the value of the given property, optionally with a unit.
     pub fn set_value<T>(&mut self, property: &str, value: &T, unit: Option<Unit>) where T: std::string::ToString {
         let mut value = value.to_string();
@@ -497,6 +2129,10 @@ impl SelectedStyle {
     pub fn get_value<T>(&self, property: &str) -> (Option<T>, Option<Unit>) where T: std::str::FromStr {
         self.style.get_value(property)
     }
+    /// Returns the value of the given property as written, without splitting off a unit.
+    pub fn get_raw_value(&self, property: &str) -> Option<String> {
+        self.style.get_raw_value(property)
+    }
     /// Returns the specificity based on which the style was selected. This should be the specificity of one
     /// of the matching selectors of the underlying style.
     pub fn specificity(&self) -> &Specificity {
@@ -530,7 +2166,12 @@ impl ComputedStyle {
         }
     }
 
-    /// Applies the given style to this computed style, if it has a higher specificity.
+    /// Returns the value of the given property as written, without splitting off a unit. Use this for shorthand properties.
+    pub fn get_raw_value(&self, property: &str) -> Option<String> {
+        self.properties.get(property).and_then(|property_style| property_style.get_raw_value(property))
+    }
+
+    /// Applies the given style to this computed style, if it has a higher or the same specificity (of the styles with the same specificity, the one applied last wins).
     /// 
     /// ```
     /// use yargl::css::{Style, parse_css, Selector, ComputedStyle, Unit, Specificity};
@@ -539,7 +2180,7 @@ impl ComputedStyle {
     /// let mut styles = parse_css("div.a, #b, .c { width: 100px; font-size: 20px; height: 100%; color: #ff0000; } div.a#d { width: 200px; }").unwrap();
     /// let mut style1 = styles.remove(0);
     /// let mut style2 = styles.remove(0);
-    /// let selector = Selector { tag_name: Some("div".to_string()), class_list: vec!["a".to_string()], id: Some("d".to_string()) };
+    /// let selector = Selector { tag_name: Some("div".to_string()), class_list: vec!["a".to_string()], id: Some("d".to_string()), pseudo_classes: vec![], pseudo_element: None };
     /// let mut computed_style = ComputedStyle::new(selector);
     /// computed_style.apply_style(style1.clone(), &style1.selectors.get(0).unwrap().specificity());
     /// assert_eq!(computed_style.get_value::<f32>("width"), (Some(100.0), Some(Unit::Px)));
@@ -547,12 +2188,19 @@ impl ComputedStyle {
     /// assert_eq!(computed_style.get_value::<f32>("height"), (Some(100.0), Some(Unit::Percent)));
     /// computed_style.apply_style(style2.clone(), &style2.selectors.get(0).unwrap().specificity());
     /// assert_eq!(computed_style.get_value::<f32>("width"), (Some(200.0), Some(Unit::Px)));
+    /// let mut styles = parse_css(".a:hover { width: 1px; } .a:active { width: 2px; }").unwrap();
+    /// let mut computed_style = ComputedStyle::new(Selector::new(None, vec!["a".to_string()], None));
+    /// for style in styles.drain(..) {
+    ///     let specificity = style.selectors[0].specificity();
+    ///     computed_style.apply_style(style, &specificity);
+    /// }
+    /// assert_eq!(computed_style.get_value::<f32>("width"), (Some(2.0), Some(Unit::Px)));
     /// ```
     pub fn apply_style(&mut self, style: Rc<Style>, specificity: &Specificity) {
         for (property, _value) in style.properties.iter() {
             match self.properties.get(property) {
                 Some(old_style) => {
-                    if specificity > old_style.specificity() {
+                    if specificity >= old_style.specificity() {
                         self.properties.insert(property.to_string(), SelectedStyle { 
                             specificity: specificity.clone(),
                             style: style.clone()
@@ -590,25 +2238,131 @@ impl ToString for ComputedStyle {
     }
 }
 
+/// Parses a number with an optional unit, i.e. a single part of a shorthand property value.
+///
+/// ```
+/// use yargl::css::{parse_dimension, Unit};
+/// assert_eq!(parse_dimension("12px"), Some((12.0, Some(Unit::Px))));
+/// assert_eq!(parse_dimension("-1.5em"), Some((-1.5, Some(Unit::Em))));
+/// assert_eq!(parse_dimension("0"), Some((0.0, None)));
+/// assert_eq!(parse_dimension("solid"), None);
+/// ```
+pub fn parse_dimension(value: &str) -> Option<(f32, Option<Unit>)> {
+    for unit in Unit::iter() {
+        let unit_str = unit.to_string();
+        if let Some(number) = value.strip_suffix(unit_str.as_str()) {
+            return number.parse::<f32>().ok().map(|n| (n, Some(unit)));
+        }
+    }
+    value.parse::<f32>().ok().map(|n| (n, None))
+}
+
+/// Returns the (unquoted) arguments of all url() functions in the given property value.
+///
+/// ```
+/// use yargl::css::parse_urls;
+/// assert_eq!(parse_urls("local(Arial), url(\"a.ttf\") format(\"truetype\"), url(b.otf)"), vec!["a.ttf".to_string(), "b.otf".to_string()]);
+/// assert!(parse_urls("none").is_empty());
+/// ```
+pub fn parse_urls(value: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("url(") {
+        rest = &rest[start + 4..];
+        match rest.find(')') {
+            Some(end) => {
+                urls.push(rest[..end].trim().trim_matches(|c| c == '"' || c == '\'').to_string());
+                rest = &rest[end + 1..];
+            },
+            None => break
+        }
+    }
+    urls
+}
+
 /// Parses style rules from css.
 /// 
 /// - css [&str]: CSS-formatted string
 /// 
 /// Returns: A vector of reference-counted style rules.
 /// 
-/// Supports: Comments, Basic selectors (tag, multiple classes, id), Multiple selectors for one style (comma-separated), Units, Hex colors (no shorthand)
-// TODO: Pseudo-classes, Pseudo-elements, Attribute selectors, Combinators, Media queries, Keyframes, Animations, Transitions, Variables, Functions, Calc, etc.
+/// Supports: Comments, Basic selectors (tag, multiple classes, id, state pseudo-classes, see [PseudoClass], the ::selection pseudo-element), Multiple selectors for one style (comma-separated), Units, Hex colors (no shorthand),
+/// Functions (stored as written), At-rules with a single block of properties (i.e. @font-face), @media rules (see [MediaQuery])
+///
+/// ```
+/// use yargl::css::{parse_css, Unit, PseudoClass, PseudoElement};
+/// let styles = parse_css("@font-face { font-family: Demo; src: url(\"fonts/demo.ttf\"); } h1 { width: 10px; background-position: right  10px; margin-top: -5px; height: 12.5%; opacity: -0.5; }").unwrap();
+/// assert_eq!(styles.len(), 2);
+/// assert_eq!(styles[0].at_rule, Some("font-face".to_string()));
+/// assert!(styles[0].selectors.is_empty());
+/// assert_eq!(styles[0].get_value::<String>("src").0, Some("url(\"fonts/demo.ttf\")".to_string()));
+/// assert_eq!(styles[1].at_rule, None);
+/// assert_eq!(styles[1].get_raw_value("background-position"), Some("right  10px".to_string()));
+/// assert_eq!(styles[1].get_value::<f32>("margin-top"), (Some(-5.0), Some(Unit::Px)));
+/// assert_eq!(styles[1].get_value::<f32>("height"), (Some(12.5), Some(Unit::Percent)));
+/// assert_eq!(styles[1].get_raw_value("opacity"), Some("-0.5".to_string()));
+/// let styles = parse_css("a.b:hover, :focus-visible, p::first-line { width: 10px; }").unwrap();
+/// assert_eq!(styles[0].selectors[0].to_string(), "a.b:hover");
+/// assert_eq!(styles[0].selectors[1].to_string(), ":focus-visible");
+/// assert_eq!(styles[0].selectors[2].pseudo_classes, vec![PseudoClass::Unsupported(":first-line".to_string())]);
+/// let styles = parse_css("::selection, p.a::selection { color: #ffffff; }").unwrap();
+/// assert_eq!(styles[0].selectors[0].pseudo_element, Some(PseudoElement::Selection));
+/// assert_eq!(styles[0].selectors[1].to_string(), "p.a::selection");
+/// let styles = parse_css("@media (min-resolution: 2dppx) { p { width: 10px; } @media (max-resolution: 3dppx) { a { width: 20px; } } } div { width: 30px; }").unwrap();
+/// assert_eq!(styles.len(), 3);
+/// assert_eq!(styles[0].at_rule, None);
+/// assert!(!styles[0].matches_media(1.0) && styles[0].matches_media(2.0));
+/// assert_eq!(styles[1].selectors[0].to_string(), "a");
+/// assert!(styles[1].matches_media(3.0) && !styles[1].matches_media(4.0));
+/// assert!(styles[2].media.is_empty());
+/// ```
+// TODO: Functional pseudo-classes, Pseudo-elements other than ::selection, Attribute selectors, Combinators, Media features other than resolution, Keyframes, Animations, Transitions, Variables, Functions, Calc, etc.
 pub fn parse_css(css: &str) -> Result<Vec<Rc<Style>>, CssParseError> {
     let mut parser_input = cssparser::ParserInput::new(css);
     let mut parser = cssparser::Parser::new(&mut parser_input);
+    parse_rules(&mut parser, &[])
+}
+
+/// Parses the style rules of a style sheet, or of the block of an @media rule with the media queries of the @media rules around them.
+fn parse_rules(parser: &mut cssparser::Parser, media: &[MediaQuery]) -> Result<Vec<Rc<Style>>, CssParseError> {
     let mut sheet: Vec<Rc<Style>> = Vec::new();
     let mut selectors: Vec<Selector> = Vec::new();
     let mut current_selector = Selector::new(None, Vec::new(), None);
     let mut expect_class = false;
+    // number of colons before a pseudo-class (one) or pseudo-element (two)
+    let mut pseudo_colons = 0;
+    let mut at_rule: Option<String> = None;
+    let mut prelude_start = parser.position();
     loop {
         match parser.next() {
             Ok(token) => {
                 match token {
+                    cssparser::Token::AtKeyword(name) => {
+                        at_rule = Some(name.to_string());
+                        prelude_start = parser.position();
+                    },
+                    cssparser::Token::Semicolon => {
+                        // end of an at-rule without block (i.e. @import)
+                        at_rule = None;
+                    },
+                    // the prelude of an at-rule is not a selector
+                    _ if at_rule.is_some() && *token != cssparser::Token::CurlyBracketBlock => {},
+                    cssparser::Token::Colon => {
+                        pseudo_colons += 1;
+                    },
+                    cssparser::Token::Ident(ident) if pseudo_colons == 2 && ident.parse::<PseudoElement>().is_ok() => {
+                        current_selector.pseudo_element = ident.parse().ok();
+                        pseudo_colons = 0;
+                    },
+                    cssparser::Token::Ident(ident) if pseudo_colons > 0 => {
+                        let name = format!("{}{}", ":".repeat(pseudo_colons - 1), ident);
+                        current_selector.pseudo_classes.push(name.parse().unwrap_or(PseudoClass::Unsupported(name)));
+                        pseudo_colons = 0;
+                    },
+                    cssparser::Token::Function(name) if pseudo_colons > 0 => {
+                        current_selector.pseudo_classes.push(PseudoClass::Unsupported(format!("{}{}", ":".repeat(pseudo_colons - 1), name)));
+                        pseudo_colons = 0;
+                    },
                     cssparser::Token::Ident(ident) => {
                         if expect_class {
                             current_selector.class_list.push(ident.to_string());
@@ -630,14 +2384,31 @@ pub fn parse_css(css: &str) -> Result<Vec<Rc<Style>>, CssParseError> {
                     cssparser::Token::Comma => {
                         selectors.push(current_selector.clone());
                         current_selector = Selector::new(None, Vec::new(), None);
+                        pseudo_colons = 0;
+                    },
+                    // the block of an @media rule holds style rules, which only apply if the media query matches
+                    cssparser::Token::CurlyBracketBlock if at_rule.as_deref() == Some("media") => {
+                        let query = MediaQuery::parse(parser.slice_from(prelude_start).trim_end_matches('{'));
+                        let media = media.iter().cloned().chain(std::iter::once(query)).collect::<Vec<_>>();
+                        at_rule = None;
+                        let rules = parser.parse_nested_block(|block_parser| {
+                            Ok::<_, cssparser::ParseError<'_, BasicParseError>>(parse_rules(block_parser, &media))
+                        }).unwrap_or_else(|_| Ok(Vec::new()))?;
+                        sheet.extend(rules);
                     },
                     cssparser::Token::CurlyBracketBlock => {
-                        selectors.push(current_selector.clone());
+                        if at_rule.is_none() {
+                            selectors.push(current_selector.clone());
+                        }
                         current_selector = Selector::new(None, Vec::new(), None);
+                        pseudo_colons = 0;
+                        let style_at_rule = at_rule.take();
                         // Parse Style block
                         let style_result = parser.parse_nested_block(|block_parser| {
                             let mut style = Style {
                                 selectors: Vec::new(),
+                                at_rule: style_at_rule,
+                                media: media.to_vec(),
                                 properties: HashMap::new(),
                             };
                             style.selectors.append(selectors.as_mut());
@@ -645,7 +2416,7 @@ pub fn parse_css(css: &str) -> Result<Vec<Rc<Style>>, CssParseError> {
                             let mut property_value = String::new();
                             let mut in_property_value = false;
                             loop {
-                                match block_parser.next() {
+                                match block_parser.next_including_whitespace() {
                                     Ok(token) => {
                                         match token {
                                             cssparser::Token::Ident(ident) => {
@@ -663,31 +2434,24 @@ pub fn parse_css(css: &str) -> Result<Vec<Rc<Style>>, CssParseError> {
                                                     property_value.push(',');
                                                 }
                                             },
-                                            cssparser::Token::Dimension { has_sign, value, int_value, unit } => {
-                                                if *has_sign {
-                                                    property_value.push('-');
-                                                }
+                                            cssparser::Token::Dimension { value, int_value, unit, .. } => {
+                                                // the value includes its sign
                                                 match int_value {
                                                     Some(int_value) => property_value.push_str(&int_value.to_string()),
                                                     None => property_value.push_str(&value.to_string())
                                                 }
                                                 property_value.push_str(&unit.to_string());
                                             },
-                                            cssparser::Token::Percentage { has_sign, unit_value, int_value } => {
-                                                if *has_sign {
-                                                    property_value.push('-');
-                                                }
+                                            cssparser::Token::Percentage { unit_value, int_value, .. } => {
+                                                // the value includes its sign
                                                 match int_value {
                                                     Some(int_value) => property_value.push_str(&int_value.to_string()),
-                                                    None => property_value.push_str(&((*unit_value * 100.0) as i32).to_string())
+                                                    None => property_value.push_str(&(*unit_value * 100.0).to_string())
                                                 }
                                                 property_value.push('%');
                                             },
-                                            cssparser::Token::Number { has_sign, value, int_value } => {
-                                                // Parse property value
-                                                if *has_sign {
-                                                    property_value.push('-');
-                                                }
+                                            cssparser::Token::Number { value, int_value, .. } => {
+                                                // Parse property value, which includes its sign
                                                 match int_value {
                                                     Some(int_value) => property_value.push_str(&int_value.to_string()),
                                                     None => property_value.push_str(&value.to_string())
@@ -704,6 +2468,19 @@ pub fn parse_css(css: &str) -> Result<Vec<Rc<Style>>, CssParseError> {
                                             cssparser::Token::QuotedString(string) => {
                                                 property_value.push_str(string.as_ref());
                                             },
+                                            cssparser::Token::UnquotedUrl(url) => {
+                                                property_value.push_str(&format!("url({})", url));
+                                            },
+                                            cssparser::Token::Function(name) => {
+                                                // Functions are kept as written, their arguments are parsed when the value is used
+                                                let name = name.to_string();
+                                                let arguments = block_parser.parse_nested_block(|arguments_parser| {
+                                                    let start = arguments_parser.position();
+                                                    while arguments_parser.next_including_whitespace_and_comments().is_ok() {}
+                                                    Ok::<String, cssparser::ParseError<'_, BasicParseError>>(arguments_parser.slice_from(start).to_string())
+                                                }).unwrap_or_default();
+                                                property_value.push_str(&format!("{}({})", name, arguments.trim()));
+                                            },
                                             cssparser::Token::WhiteSpace(ws) => {
                                                 if in_property_value && !property_value.is_empty() {
                                                     property_value.push_str(*ws);
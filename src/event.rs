@@ -1,8 +1,10 @@
-use std::ptr::eq;
+use std::{cell::{Cell, RefCell}, collections::HashMap, path::PathBuf, rc::Rc, str::FromStr, sync::atomic::{AtomicU64, Ordering}};
 
 use crate::window;
 
 
+/// Returned by listeners. Cancel stops the propagation of the event and prevents its default action,
+/// Quit stops the propagation and is passed on to the caller of [crate::context::Context::poll_events].
 #[derive(PartialEq)]
 pub enum EventReturnCode {
     Continue,
@@ -10,20 +12,112 @@ pub enum EventReturnCode {
     Quit
 }
 
+/// The phase in which the listeners of an element are called. The window's listeners are called after the bubbling phase.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EventPhase {
+    None,
+    Capturing,
+    AtTarget,
+    Bubbling,
+}
+
+#[derive(Debug)]
+struct Propagation {
+    target: Option<tl::NodeHandle>,
+    current_target: Cell<Option<tl::NodeHandle>>,
+    phase: Cell<EventPhase>,
+    bubbles: bool,
+    stopped: Cell<bool>,
+    default_prevented: Cell<bool>,
+}
+
+/// An event with its payload, dispatched to the listeners of its target element, the element's ancestors and the window.
 #[derive(Debug)]
-pub struct Event<T>(pub T);
+pub struct Event<T>(pub T, Propagation);
+
+impl<T> Event<T> {
+    /// Creates an event for the given target element. Events without a target are only dispatched to the window's listeners.
+    pub fn new(payload: T, target: Option<tl::NodeHandle>) -> Event<T> {
+        Event(payload, Propagation {
+            target,
+            current_target: Cell::new(None),
+            phase: Cell::new(EventPhase::None),
+            bubbles: true,
+            stopped: Cell::new(false),
+            default_prevented: Cell::new(false),
+        })
+    }
+    /// Creates an event that doesn't bubble: only the capturing listeners of the target's ancestors and the listeners of the target are called.
+    pub fn new_non_bubbling(payload: T, target: Option<tl::NodeHandle>) -> Event<T> {
+        let mut event = Event::new(payload, target);
+        event.1.bubbles = false;
+        event
+    }
+    /// The element the event is dispatched to.
+    pub fn target(&self) -> Option<tl::NodeHandle> {
+        self.1.target
+    }
+    /// The element whose listener is currently called, None while the window's listeners are called.
+    pub fn current_target(&self) -> Option<tl::NodeHandle> {
+        self.1.current_target.get()
+    }
+    pub fn phase(&self) -> EventPhase {
+        self.1.phase.get()
+    }
+    /// Whether the event is dispatched to the listeners of the target's ancestors and the window after the target.
+    pub fn bubbles(&self) -> bool {
+        self.1.bubbles
+    }
+    /// Stops the event from reaching further elements and the window. The remaining listeners of the current element are still called.
+    pub fn stop_propagation(&self) {
+        self.1.stopped.set(true);
+    }
+    pub fn propagation_stopped(&self) -> bool {
+        self.1.stopped.get()
+    }
+    /// Prevents the default action of the event, i.e. moving the focus.
+    pub fn prevent_default(&self) {
+        self.1.default_prevented.set(true);
+    }
+    pub fn default_prevented(&self) -> bool {
+        self.1.default_prevented.get()
+    }
+}
+
+/// A listener can capture state and change the window, i.e. its document or its other listeners.
+pub type Listener<T> = dyn FnMut(&Event<T>, &mut window::Window) -> EventReturnCode;
+
+type SharedListener<T> = Rc<RefCell<Listener<T>>>;
+
+/// Identifies a listener for removing it. Ids are unique across all receivers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ListenerId(u64);
+
+impl ListenerId {
+    fn next() -> ListenerId {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        ListenerId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
 
 pub trait EventReceiver<T> {
-    fn add_listener(&mut self, listener: fn(&Event<T>, &window::Window) -> EventReturnCode);
-    fn remove_listener(&mut self, listener: fn(&Event<T>, &window::Window) -> EventReturnCode);
-    fn trigger(&self, event: &Event<T>, window: &window::Window) -> EventReturnCode;
+    /// Adds a listener for the events of the whole window.
+    fn add_listener(&mut self, listener: impl FnMut(&Event<T>, &mut window::Window) -> EventReturnCode + 'static) -> ListenerId;
+    /// Adds a listener for events that target the element or one of its descendants.
+    /// Capturing listeners are called before the listeners of the descendants, others after them.
+    fn add_element_listener(&mut self, node_handle: tl::NodeHandle, listener: impl FnMut(&Event<T>, &mut window::Window) -> EventReturnCode + 'static, capture: bool) -> ListenerId;
+    /// Removes a window or element listener. Returns false if there is no listener with the id.
+    fn remove_listener(&mut self, id: ListenerId) -> bool;
 }
-#[derive(Debug)]
+/// Pointer id of the mouse. Fingers get their own ids while they touch the screen.
+pub const MOUSE_POINTER_ID: i64 = 1;
+
+#[derive(Debug, Clone)]
 pub struct MouseButtonEventData {
     pub button: sdl2::mouse::MouseButton,
     pub clicks: u8,
 }
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FingerEventData {
     pub norm_x: f32,
     pub norm_y: f32,
@@ -33,10 +127,12 @@ pub struct FingerEventData {
     pub finger_id: i64,
     pub norm_pressure: f32,
 }
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PointerEventData {
     pub x: i32,
     pub y: i32,
+    /// [MOUSE_POINTER_ID] for the mouse, or a unique id of a finger from the moment it touches the screen until it is lifted.
+    pub pointer_id: i64,
     pub mouse_data: Option<MouseButtonEventData>,
     pub finger_data: Option<FingerEventData>,
     pub timestamp: u32,
@@ -49,6 +145,45 @@ pub struct PointerDownEvent {
 pub struct PointerUpEvent {
     pub data: PointerEventData,
 }
+/// A click of the primary button (or a tap), dispatched when the pointer goes up on the element on which it went down.
+#[derive(Debug)]
+pub struct ClickEvent {
+    pub data: PointerEventData,
+}
+/// Dispatched after the second click of a double click.
+#[derive(Debug)]
+pub struct DoubleClickEvent {
+    pub data: PointerEventData,
+}
+#[derive(Debug)]
+pub struct PointerBoundaryEventData {
+    pub x: i32,
+    pub y: i32,
+    pub pointer_id: i64,
+    /// The element that the pointer left (for over and enter events) or entered (for out and leave events).
+    pub related_target: Option<tl::NodeHandle>,
+    pub timestamp: u32,
+}
+/// The pointer moved onto the target. Bubbles.
+#[derive(Debug)]
+pub struct PointerOverEvent {
+    pub data: PointerBoundaryEventData,
+}
+/// The pointer moved off the target. Bubbles.
+#[derive(Debug)]
+pub struct PointerOutEvent {
+    pub data: PointerBoundaryEventData,
+}
+/// The pointer moved onto the target or one of its descendants from outside of the target. Doesn't bubble, dispatched to each element that was entered.
+#[derive(Debug)]
+pub struct PointerEnterEvent {
+    pub data: PointerBoundaryEventData,
+}
+/// The pointer moved off the target and its descendants. Doesn't bubble, dispatched to each element that was left.
+#[derive(Debug)]
+pub struct PointerLeaveEvent {
+    pub data: PointerBoundaryEventData,
+}
 #[derive(Debug)]
 pub struct MouseMoveData {
     pub state: sdl2::mouse::MouseState
@@ -59,6 +194,8 @@ pub struct PointerMoveEvent {
     pub y: i32,
     pub x_rel: i32,
     pub y_rel: i32,
+    /// [MOUSE_POINTER_ID] for the mouse, or a unique id of a finger from the moment it touches the screen until it is lifted.
+    pub pointer_id: i64,
     pub mouse_data: Option<MouseMoveData>,
     pub finger_data: Option<FingerEventData>,
     pub timestamp: u32,
@@ -70,35 +207,510 @@ pub struct ScrollEvent {
     pub timestamp: u32,
 }
 
+/// A pinch or rotation with two or more fingers.
+#[derive(Debug)]
+pub struct GestureEvent {
+    /// Center of the fingers in window coordinates.
+    pub x: i32,
+    pub y: i32,
+    /// Rotation of the fingers since the last event in radians.
+    pub rotation: f32,
+    /// Change of the distance between the fingers since the last event, relative to the size of the touch device. Positive when the fingers move apart.
+    pub pinch: f32,
+    pub num_fingers: u16,
+    pub touch_id: i64,
+    pub timestamp: u32,
+}
+#[derive(Debug)]
+pub struct KeyboardEventData {
+    pub keycode: Option<sdl2::keyboard::Keycode>,
+    pub scancode: Option<sdl2::keyboard::Scancode>,
+    pub modifiers: sdl2::keyboard::Mod,
+    /// Whether the event was generated by holding the key down.
+    pub repeat: bool,
+    pub timestamp: u32,
+}
+#[derive(Debug)]
+pub struct KeyDownEvent {
+    pub data: KeyboardEventData,
+}
+#[derive(Debug)]
+pub struct KeyUpEvent {
+    pub data: KeyboardEventData,
+}
+/// The modifier of shortcuts like copy and paste: Cmd on macOS, Ctrl elsewhere. Contains both the left and the right key.
+pub fn primary_modifier() -> sdl2::keyboard::Mod {
+    if cfg!(target_os = "macos") {
+        sdl2::keyboard::Mod::LGUIMOD | sdl2::keyboard::Mod::RGUIMOD
+    } else {
+        sdl2::keyboard::Mod::LCTRLMOD | sdl2::keyboard::Mod::RCTRLMOD
+    }
+}
+/// The target received the keyboard focus. Doesn't bubble.
+#[derive(Debug)]
+pub struct FocusEvent {
+    /// The element that lost the focus.
+    pub related_target: Option<tl::NodeHandle>,
+}
+/// The target lost the keyboard focus. Doesn't bubble.
+#[derive(Debug)]
+pub struct BlurEvent {
+    /// The element that receives the focus.
+    pub related_target: Option<tl::NodeHandle>,
+}
+#[derive(Debug)]
+pub struct TextInputEvent {
+    pub text: String,
+    /// Cursor position and selection length within the text while an input method is still composing it, None for committed text.
+    pub composition: Option<(i32, i32)>,
+    pub timestamp: u32,
+}
+
+/// The window's size changed, i.e. because the user resized it. The document is laid out again before the event is dispatched.
+#[derive(Debug)]
+pub struct ResizeEvent {
+    /// The new viewport size in css pixels.
+    pub width: u32,
+    pub height: u32,
+    pub timestamp: u32,
+}
+/// The window was moved on the screen.
+#[derive(Debug)]
+pub struct MoveEvent {
+    /// The new position of the window in screen coordinates.
+    pub x: i32,
+    pub y: i32,
+    pub timestamp: u32,
+}
+/// The window gained or lost the keyboard focus.
+#[derive(Debug)]
+pub struct WindowFocusEvent {
+    pub focused: bool,
+    pub timestamp: u32,
+}
+/// The user asked to close the window, i.e. with its close button. Unless a listener cancels the event, the window is closed.
+#[derive(Debug)]
+pub struct CloseRequestedEvent {
+    pub timestamp: u32,
+}
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WindowState {
+    Minimized,
+    Maximized,
+    /// The window was restored from being minimized or maximized.
+    Restored,
+}
+/// The window was minimized, maximized or restored.
+#[derive(Debug)]
+pub struct WindowStateEvent {
+    pub state: WindowState,
+    pub timestamp: u32,
+}
+/// The window moved to another display, or the scale of its display changed. The document is laid out again before the event is dispatched.
+#[derive(Debug)]
+pub struct DisplayChangeEvent {
+    pub display_index: i32,
+    /// See [window::Window::device_pixel_ratio].
+    pub device_pixel_ratio: f32,
+    pub timestamp: u32,
+}
+
+/// The data of a drag and drop operation, shared by all of its events. Listeners of the drag start event set the data that a drop target receives.
+#[derive(Debug, Default)]
+pub struct DataTransfer {
+    // data by format, in the order it was set
+    data: RefCell<Vec<(String, String)>>,
+    files: RefCell<Vec<PathBuf>>,
+}
+
+impl DataTransfer {
+    pub fn new() -> DataTransfer {
+        DataTransfer::default()
+    }
+    /// Sets the data of a format (i.e. "text/plain"), replacing the previous data of the format.
+    pub fn set_data(&self, format: &str, data: &str) {
+        let mut formats = self.data.borrow_mut();
+        match formats.iter_mut().find(|(f, _)| f == format) {
+            Some((_, value)) => *value = data.to_string(),
+            None => formats.push((format.to_string(), data.to_string())),
+        }
+    }
+    pub fn get_data(&self, format: &str) -> Option<String> {
+        self.data.borrow().iter().find(|(f, _)| f == format).map(|(_, data)| data.clone())
+    }
+    pub fn clear_data(&self) {
+        self.data.borrow_mut().clear();
+    }
+    /// The formats of the data, in the order they were set.
+    pub fn types(&self) -> Vec<String> {
+        self.data.borrow().iter().map(|(format, _)| format.clone()).collect()
+    }
+    /// Files that were dropped onto the window from another application.
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.files.borrow().clone()
+    }
+    pub fn add_file(&self, path: PathBuf) {
+        self.files.borrow_mut().push(path);
+    }
+    /// Whether neither data nor files were set.
+    pub fn is_empty(&self) -> bool {
+        self.data.borrow().is_empty() && self.files.borrow().is_empty()
+    }
+}
+#[derive(Debug)]
+pub struct DragEventData {
+    pub x: i32,
+    pub y: i32,
+    pub data_transfer: Rc<DataTransfer>,
+    pub timestamp: u32,
+}
+/// A drag of a draggable element starts, dispatched to the dragged element. Cancel it to prevent the drag.
+#[derive(Debug)]
+pub struct DragStartEvent {
+    pub data: DragEventData,
+}
+/// The dragged element is moved over the target. Cancel it to allow a drop onto the target.
+#[derive(Debug)]
+pub struct DragOverEvent {
+    pub data: DragEventData,
+}
+/// An element was dropped onto the target after its drag over event was cancelled, or files or text were dropped onto the window from another application.
+#[derive(Debug)]
+pub struct DropEvent {
+    pub data: DragEventData,
+}
+/// A drag ended, dispatched to the dragged element.
+#[derive(Debug)]
+pub struct DragEndEvent {
+    pub data: DragEventData,
+    /// Whether the element was dropped onto a target.
+    pub dropped: bool,
+}
+
 pub struct GenericEventReceiver<T> {
-    listeners: Vec<fn(&Event<T>, &window::Window) -> EventReturnCode>,
+    listeners: Vec<(ListenerId, SharedListener<T>)>,
+    // listeners of elements, with whether they are capturing
+    element_listeners: HashMap<tl::NodeHandle, Vec<(ListenerId, SharedListener<T>, bool)>>,
 }
 
 impl<T> GenericEventReceiver<T> {
     pub fn new() -> GenericEventReceiver<T> {
         GenericEventReceiver {
             listeners: Vec::new(),
+            element_listeners: HashMap::new(),
         }
     }   
+
+    /// The listeners of one element (or of the window if node_handle is None) that are called in the given phase.
+    fn phase_listeners(&self, node_handle: Option<tl::NodeHandle>, phase: EventPhase) -> Vec<(ListenerId, SharedListener<T>)> {
+        match node_handle {
+            Some(node_handle) => self.element_listeners.get(&node_handle).map(|listeners| listeners.iter()
+                .filter(|(_, _, capture)| match phase {
+                    EventPhase::Capturing => *capture,
+                    EventPhase::Bubbling => !*capture,
+                    _ => true,
+                })
+                .map(|(id, listener, _)| (*id, listener.clone()))
+                .collect()).unwrap_or_default(),
+            None => self.listeners.clone(),
+        }
+    }
+
+    fn contains(&self, id: ListenerId) -> bool {
+        self.listeners.iter().any(|(listener_id, _)| *listener_id == id)
+            || self.element_listeners.values().any(|listeners| listeners.iter().any(|(listener_id, _, _)| *listener_id == id))
+    }
 }
 
 impl<T> EventReceiver<T> for GenericEventReceiver<T> {
-    fn add_listener(&mut self, listener: fn(&Event<T>, &window::Window) -> EventReturnCode) {
-        self.listeners.push(listener);
+    fn add_listener(&mut self, listener: impl FnMut(&Event<T>, &mut window::Window) -> EventReturnCode + 'static) -> ListenerId {
+        let id = ListenerId::next();
+        self.listeners.push((id, Rc::new(RefCell::new(listener))));
+        id
+    }
+
+    fn add_element_listener(&mut self, node_handle: tl::NodeHandle, listener: impl FnMut(&Event<T>, &mut window::Window) -> EventReturnCode + 'static, capture: bool) -> ListenerId {
+        let id = ListenerId::next();
+        self.element_listeners.entry(node_handle).or_default().push((id, Rc::new(RefCell::new(listener)), capture));
+        id
     }
 
-    fn remove_listener(&mut self, listener: fn(&Event<T>, &window::Window) -> EventReturnCode) {
-        self.listeners.retain(|l| !eq(l, &listener));
+    fn remove_listener(&mut self, id: ListenerId) -> bool {
+        let count = self.listeners.len() + self.element_listeners.values().map(Vec::len).sum::<usize>();
+        self.listeners.retain(|(listener_id, _)| *listener_id != id);
+        for listeners in self.element_listeners.values_mut() {
+            listeners.retain(|(listener_id, _, _)| *listener_id != id);
+        }
+        count != self.listeners.len() + self.element_listeners.values().map(Vec::len).sum::<usize>()
     }
+}
 
-    fn trigger(&self, event: &Event<T>, window: &window::Window) -> EventReturnCode {
-        let mut return_code = EventReturnCode::Continue;
-        for listener in &self.listeners {
-            return_code = listener(event, window);
-            if return_code != EventReturnCode::Continue {
-                break;
+/// Dispatches an event to the listeners of one of the window's receivers, selected by `receiver`: in the capturing, target and bubbling phase
+/// along the target's ancestors, and then to the window's listeners.
+/// Listeners that are removed during the dispatch are not called anymore, listeners that are added are called for the next event.
+///
+/// Returns Quit if a listener quits, Cancel if the default action was prevented and Continue otherwise.
+pub fn dispatch<'a, 's, T>(window: &mut window::Window<'a, 's>, receiver: for<'w> fn(&'w window::Window<'a, 's>) -> &'w GenericEventReceiver<T>, event: &Event<T>) -> EventReturnCode {
+    let path = event.target().map(|target| window.event_path(target)).unwrap_or_default();
+    let ancestors = &path[..path.len().saturating_sub(1)];
+    let phases = ancestors.iter().map(|node_handle| (Some(*node_handle), EventPhase::Capturing))
+        .chain(path.last().map(|node_handle| (Some(*node_handle), EventPhase::AtTarget)))
+        .chain(ancestors.iter().rev().map(|node_handle| (Some(*node_handle), EventPhase::Bubbling)).filter(|_| event.bubbles()))
+        .chain(std::iter::once((None, EventPhase::Bubbling)).filter(|_| event.bubbles()));
+    let mut return_code = EventReturnCode::Continue;
+    'phases: for (node_handle, phase) in phases {
+        if event.propagation_stopped() {
+            break;
+        }
+        event.1.current_target.set(node_handle);
+        event.1.phase.set(phase);
+        for (id, listener) in receiver(window).phase_listeners(node_handle, phase) {
+            if !receiver(window).contains(id) {
+                continue;
+            }
+            // a listener that dispatches an event to itself is not called again
+            let code = match listener.try_borrow_mut() {
+                Ok(mut listener) => (*listener)(event, window),
+                Err(_) => continue,
+            };
+            match code {
+                EventReturnCode::Continue => {},
+                EventReturnCode::Cancel => {
+                    event.prevent_default();
+                    event.stop_propagation();
+                    break;
+                },
+                EventReturnCode::Quit => {
+                    event.stop_propagation();
+                    return_code = EventReturnCode::Quit;
+                    break 'phases;
+                }
             }
         }
+    }
+    event.1.current_target.set(None);
+    event.1.phase.set(EventPhase::None);
+    if return_code == EventReturnCode::Quit {
         return_code
+    } else if event.default_prevented() {
+        EventReturnCode::Cancel
+    } else {
+        EventReturnCode::Continue
     }
-}
\ No newline at end of file
+}
+
+/// Error which occured while parsing an accelerator.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AcceleratorParseError {
+    pub accelerator: String,
+    pub message: String,
+}
+
+/// A key with modifiers that triggers a keyboard shortcut, i.e. Ctrl+S, Ctrl+Shift+P or F5.
+/// Left and right modifier keys are not distinguished, and Num Lock and Caps Lock are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Accelerator {
+    pub keycode: sdl2::keyboard::Keycode,
+    // only the left variants of the modifiers
+    modifiers: sdl2::keyboard::Mod,
+}
+
+impl Accelerator {
+    /// Creates an accelerator for the key with the given modifiers, where either the left or the right key of a modifier may be given.
+    pub fn new(keycode: sdl2::keyboard::Keycode, modifiers: sdl2::keyboard::Mod) -> Accelerator {
+        use sdl2::keyboard::Mod;
+        let mut normalized = Mod::NOMOD;
+        for (either, left) in [
+            (Mod::LCTRLMOD | Mod::RCTRLMOD, Mod::LCTRLMOD),
+            (Mod::LSHIFTMOD | Mod::RSHIFTMOD, Mod::LSHIFTMOD),
+            (Mod::LALTMOD | Mod::RALTMOD, Mod::LALTMOD),
+            (Mod::LGUIMOD | Mod::RGUIMOD, Mod::LGUIMOD),
+        ] {
+            if modifiers.intersects(either) {
+                normalized |= left;
+            }
+        }
+        Accelerator { keycode, modifiers: normalized }
+    }
+
+    /// The modifiers of the accelerator, as their left keys.
+    pub fn modifiers(&self) -> sdl2::keyboard::Mod {
+        self.modifiers
+    }
+
+    /// Whether a key that is pressed with the given modifiers triggers the accelerator. All of its modifiers and no others must be held.
+    pub fn matches(&self, keycode: sdl2::keyboard::Keycode, modifiers: sdl2::keyboard::Mod) -> bool {
+        *self == Accelerator::new(keycode, modifiers)
+    }
+}
+
+impl FromStr for Accelerator {
+    type Err = AcceleratorParseError;
+
+    /// Parses an accelerator from modifiers and a key name, separated by "+" (case-insensitive).
+    /// Modifiers are Ctrl (Control), Shift, Alt (Option), Cmd (Command, Super, Win, Meta) and Primary (CmdOrCtrl), which is Cmd on macOS and Ctrl elsewhere.
+    /// Keys are named like sdl names them, i.e. "S", "F5", "Delete", "Left" or "+".
+    ///
+    /// ```
+    /// use yargl::event::{Accelerator, primary_modifier};
+    /// use sdl2::keyboard::{Keycode, Mod};
+    /// let accelerator: Accelerator = "Ctrl+Shift+P".parse().unwrap();
+    /// assert_eq!(accelerator, Accelerator::new(Keycode::P, Mod::LCTRLMOD | Mod::RSHIFTMOD));
+    /// assert!(accelerator.matches(Keycode::P, Mod::RCTRLMOD | Mod::LSHIFTMOD | Mod::NUMMOD));
+    /// assert!(!accelerator.matches(Keycode::P, Mod::LCTRLMOD));
+    /// assert_eq!("f5".parse::<Accelerator>().unwrap(), Accelerator::new(Keycode::F5, Mod::NOMOD));
+    /// assert_eq!("Primary+S".parse::<Accelerator>().unwrap(), Accelerator::new(Keycode::S, primary_modifier()));
+    /// assert_eq!("Ctrl++".parse::<Accelerator>().unwrap(), Accelerator::new(Keycode::Plus, Mod::LCTRLMOD));
+    /// assert!("Ctrl+Shift".parse::<Accelerator>().is_err());
+    /// assert!("Ctrl+S+T".parse::<Accelerator>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use sdl2::keyboard::Mod;
+        let error = |message: &str| AcceleratorParseError { accelerator: s.to_string(), message: message.to_string() };
+        // a trailing "+" is the plus key
+        let (modifier_names, key_name) = match s.trim().strip_suffix("++") {
+            Some(modifier_names) => (modifier_names, "+"),
+            None => match s.trim().rsplit_once('+') {
+                Some((modifier_names, key_name)) => (modifier_names, key_name),
+                None => ("", s.trim()),
+            },
+        };
+        let mut modifiers = Mod::NOMOD;
+        for name in modifier_names.split('+').map(str::trim).filter(|name| !name.is_empty()) {
+            modifiers |= match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => Mod::LCTRLMOD,
+                "shift" => Mod::LSHIFTMOD,
+                "alt" | "option" | "opt" => Mod::LALTMOD,
+                "cmd" | "command" | "super" | "win" | "meta" | "gui" => Mod::LGUIMOD,
+                "primary" | "cmdorctrl" | "commandorcontrol" => primary_modifier(),
+                _ => return Err(error("invalid or unsupported modifier")),
+            };
+        }
+        let keycode = sdl2::keyboard::Keycode::from_name(key_name.trim()).ok_or_else(|| error("invalid key name"))?;
+        if is_modifier_key(keycode) {
+            return Err(error("an accelerator needs a key that is not a modifier"));
+        }
+        Ok(Accelerator::new(keycode, modifiers))
+    }
+}
+
+// whether the key is one of the modifier keys
+fn is_modifier_key(keycode: sdl2::keyboard::Keycode) -> bool {
+    use sdl2::keyboard::Keycode;
+    matches!(keycode, Keycode::LCtrl | Keycode::RCtrl | Keycode::LShift | Keycode::RShift | Keycode::LAlt | Keycode::RAlt | Keycode::LGui | Keycode::RGui)
+}
+
+impl std::fmt::Display for Accelerator {
+    /// Formats the accelerator as it is shown to the user on the current platform, i.e. in menus:
+    /// "⌃⌥⇧⌘S" on macOS, "Ctrl+Alt+Shift+Win+S" on Windows and "Ctrl+Alt+Shift+Super+S" elsewhere.
+    ///
+    /// ```
+    /// use yargl::event::Accelerator;
+    /// let accelerator: Accelerator = "shift+primary+p".parse().unwrap();
+    /// let expected = if cfg!(target_os = "macos") { "⇧⌘P" } else { "Ctrl+Shift+P" };
+    /// assert_eq!(accelerator.to_string(), expected);
+    /// assert_eq!("F5".parse::<Accelerator>().unwrap().to_string(), "F5");
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use sdl2::keyboard::Mod;
+        let names: [(Mod, &str, &str); 4] = [
+            (Mod::LCTRLMOD, "⌃", "Ctrl"),
+            (Mod::LALTMOD, "⌥", "Alt"),
+            (Mod::LSHIFTMOD, "⇧", "Shift"),
+            (Mod::LGUIMOD, "⌘", if cfg!(target_os = "windows") { "Win" } else { "Super" }),
+        ];
+        let modifiers = names.iter().filter(|(modifier, ..)| self.modifiers.contains(*modifier));
+        for (_, symbol, name) in modifiers {
+            if cfg!(target_os = "macos") {
+                f.write_str(symbol)?;
+            } else {
+                write!(f, "{}+", name)?;
+            }
+        }
+        f.write_str(&self.keycode.name())
+    }
+}
+
+/// Where an accelerator applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcceleratorScope {
+    /// Anywhere in the window.
+    Global,
+    /// While the element or one of its descendants has focus. Takes precedence over accelerators of its ancestors and global ones.
+    FocusWithin(tl::NodeHandle),
+}
+
+/// Returned when an accelerator is added with the same key, modifiers and scope as one that was added before.
+#[derive(Debug)]
+pub struct AcceleratorConflictError {
+    /// The accelerator that was added before.
+    pub existing: ListenerId,
+    pub message: String,
+}
+
+/// A callback of an accelerator. Like a listener it can change the window, its return code is handled like the one of a key down listener.
+pub type AcceleratorCallback = dyn FnMut(&mut window::Window) -> EventReturnCode;
+
+/// Keyboard shortcuts of a window. When a key is pressed, the accelerator that matches it is called before the key down listeners,
+/// see [dispatch_accelerator].
+#[derive(Default)]
+pub struct AcceleratorRegistry {
+    accelerators: Vec<(ListenerId, Accelerator, AcceleratorScope, Rc<RefCell<AcceleratorCallback>>)>,
+}
+
+impl AcceleratorRegistry {
+    pub fn new() -> AcceleratorRegistry {
+        AcceleratorRegistry { accelerators: Vec::new() }
+    }
+
+    /// Adds an accelerator with a callback. Fails if there is an accelerator with the same key, modifiers and scope already.
+    /// The id removes the accelerator again.
+    pub fn add(&mut self, accelerator: Accelerator, scope: AcceleratorScope, callback: impl FnMut(&mut window::Window) -> EventReturnCode + 'static) -> Result<ListenerId, AcceleratorConflictError> {
+        if let Some(existing) = self.find(accelerator, scope) {
+            return Err(AcceleratorConflictError { existing, message: format!("{} is already used in this scope", accelerator) });
+        }
+        let id = ListenerId::next();
+        self.accelerators.push((id, accelerator, scope, Rc::new(RefCell::new(callback))));
+        Ok(id)
+    }
+
+    /// The accelerator with the key, modifiers and scope, None if there is none.
+    pub fn find(&self, accelerator: Accelerator, scope: AcceleratorScope) -> Option<ListenerId> {
+        self.accelerators.iter().find(|(_, other, other_scope, _)| *other == accelerator && *other_scope == scope).map(|(id, ..)| *id)
+    }
+
+    /// Removes an accelerator. Returns false if there is no accelerator with the id.
+    pub fn remove(&mut self, id: ListenerId) -> bool {
+        let count = self.accelerators.len();
+        self.accelerators.retain(|(accelerator_id, ..)| *accelerator_id != id);
+        count != self.accelerators.len()
+    }
+
+    /// The accelerator that a key triggers with the focus on the last element of the path: the one of the innermost element of the path, or a global one.
+    fn triggered(&self, keycode: sdl2::keyboard::Keycode, modifiers: sdl2::keyboard::Mod, focus_path: &[tl::NodeHandle]) -> Option<Rc<RefCell<AcceleratorCallback>>> {
+        self.accelerators.iter()
+            .filter(|(_, accelerator, ..)| accelerator.matches(keycode, modifiers))
+            .filter_map(|(_, _, scope, callback)| match scope {
+                AcceleratorScope::Global => Some((0, callback)),
+                AcceleratorScope::FocusWithin(node_handle) => focus_path.iter().position(|focused| focused == node_handle).map(|depth| (depth + 1, callback)),
+            })
+            .max_by_key(|(depth, _)| *depth)
+            .map(|(_, callback)| callback.clone())
+    }
+}
+
+/// Calls the accelerator that a key press triggers, if any, before the key down event is dispatched.
+///
+/// Returns Continue if no accelerator matched or its callback returned Continue, the key down event is then dispatched as usual.
+/// Cancel means that the accelerator handled the key, the key down event is skipped.
+pub fn dispatch_accelerator(window: &mut window::Window, keycode: sdl2::keyboard::Keycode, modifiers: sdl2::keyboard::Mod) -> EventReturnCode {
+    let focus_path = window.focused().map(|focused| window.event_path(focused)).unwrap_or_default();
+    match window.accelerators.triggered(keycode, modifiers, &focus_path) {
+        // an accelerator that dispatches its key again is not called again
+        Some(callback) => match callback.try_borrow_mut() {
+            Ok(mut callback) => (*callback)(window),
+            Err(_) => EventReturnCode::Continue,
+        },
+        None => EventReturnCode::Continue,
+    }
+}
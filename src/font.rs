@@ -1,224 +1,879 @@
-use std::collections::{HashMap, HashSet};
+use std::{collections::{HashMap, HashSet}, path::{Path, PathBuf}, sync::Arc};
 use sdl2::render::Canvas;
+use unicode_bidi::{BidiInfo, Level};
 
-use crate::layout;
+use crate::{css, layout, util::{self, PixmapTarget}};
 
-// TODO use rasterized chars instead of outlines
+/// Error which occured while loading a font.
+#[derive(Debug)]
+pub struct FontLoadError {
+    pub message: String,
+}
 
-struct TextCanvasBuilder<'a, Target: sdl2::render::RenderTarget> {
-    canvas: &'a mut Canvas<Target>,
-    x: f32,
-    y: f32,
+/// Collects glyph outlines into a path. Scales from font units to pixels and places the glyph at the pen position on the baseline.
+struct GlyphPathBuilder<'a> {
+    path: &'a mut util::Path,
     x0: f32,
     y0: f32,
     scale: f32,
+    // horizontal shift per unit of height, used for synthetic oblique
+    skew: f32,
 }
 
-impl<Target : sdl2::render::RenderTarget> TextCanvasBuilder<'_, Target> {
-    fn new<'a>(x0: f32, y0: f32, scale: f32, canvas: &'a mut Canvas<Target>) -> TextCanvasBuilder<'a, Target> {
-        TextCanvasBuilder {
-            canvas,
-            x: 0.0,
-            y: 0.0,
+impl GlyphPathBuilder<'_> {
+    fn new(x0: f32, y0: f32, scale: f32, path: &mut util::Path) -> GlyphPathBuilder<'_> {
+        GlyphPathBuilder {
+            path,
             x0,
             y0,
-            scale
+            scale,
+            skew: 0.0,
         }
     }
+
+    /// Converts a point from font units (y up) to canvas pixels (y down).
+    fn point(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.x0 + (x + y * self.skew) * self.scale, self.y0 - y * self.scale)
+    }
 }
 
-impl<Target : sdl2::render::RenderTarget> ttf_parser::OutlineBuilder for TextCanvasBuilder<'_, Target> {
+impl ttf_parser::OutlineBuilder for GlyphPathBuilder<'_> {
     fn move_to(&mut self, x: f32, y: f32) {
-        self.x = x * self.scale;
-        self.y = y * self.scale;
+        let (x, y) = self.point(x, y);
+        self.path.move_to(x, y);
     }
 
     fn line_to(&mut self, x: f32, y: f32) {
-        let scaled_x = x * self.scale;
-        let scaled_y = y * self.scale;
-        match self.canvas.draw_line(
-            ((self.x0 + self.x) as i32, (self.y0 - self.y) as i32), 
-            ((self.x0 + scaled_x) as i32, (self.y0 - scaled_y) as i32)
-        ) {_ => {}}
-        self.x = scaled_x;
-        self.y = scaled_y;
+        let (x, y) = self.point(x, y);
+        self.path.line_to(x, y);
     }
 
     fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
-        let scaled_x1 = x1 * self.scale;
-        let scaled_y1 = y1 * self.scale;
-        let scaled_x = x * self.scale;
-        let scaled_y = y * self.scale;
-        // Draw quadratic bezier curve
-        let x_span = (scaled_x - self.x).abs();
-        let y_span = (scaled_y - self.y).abs();
-        let max_span = x_span.max(y_span).ceil() as i32;
-        for i in 0..max_span {
-            let t = i as f32 / max_span as f32;
-            let t_x = (1.0 - t) * (1.0 - t) * self.x + 2.0 * (1.0 - t) * t * scaled_x1 + t * t * scaled_x;
-            let t_y = (1.0 - t) * (1.0 - t) * self.y + 2.0 * (1.0 - t) * t * scaled_y1 + t * t * scaled_y;
-            match self.canvas.draw_point(((self.x0 + t_x) as i32, (self.y0 - t_y) as i32)) {_ => {}}
-        }
-        self.x = scaled_x;
-        self.y = scaled_y;
+        let (x1, y1) = self.point(x1, y1);
+        let (x, y) = self.point(x, y);
+        self.path.quad_to(x1, y1, x, y);
     }
 
     fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
-        let scaled_x1 = x1 * self.scale;
-        let scaled_y1 = y1 * self.scale;
-        let scaled_x2 = x2 * self.scale;
-        let scaled_y2 = y2 * self.scale;
-        let scaled_x = x * self.scale;
-        let scaled_y = y * self.scale;
-        // Draw cubic bezier curve
-        let x_span = (scaled_x - self.x).abs();
-        let y_span = (scaled_y - self.y).abs();
-        let max_span = x_span.max(y_span).ceil() as i32;
-        for i in 0..max_span {
-            let t = i as f32 / max_span as f32;
-            let t_x = (1.0 - t) * (1.0 - t) * (1.0 - t) * self.x + 3.0 * (1.0 - t) * (1.0 - t) * t * scaled_x1 + 3.0 * (1.0 - t) * t * t * scaled_x2 + t * t * t * scaled_x;
-            let t_y = (1.0 - t) * (1.0 - t) * (1.0 - t) * self.y + 3.0 * (1.0 - t) * (1.0 - t) * t * scaled_y1 + 3.0 * (1.0 - t) * t * t * scaled_y2 + t * t * t * scaled_y;
-            match self.canvas.draw_point(((self.x0 + t_x) as i32, (self.y0 - t_y) as i32)) {_ => {}}
-        }
-        self.x = scaled_x;
-        self.y = scaled_y;
+        let (x1, y1) = self.point(x1, y1);
+        let (x2, y2) = self.point(x2, y2);
+        let (x, y) = self.point(x, y);
+        self.path.cubic_to(x1, y1, x2, y2, x, y);
     }
 
     fn close(&mut self) {
-        self.canvas.present();
+        self.path.close();
+    }
+}
+
+/// Describes the face that should be used to render text. Built from an element's font-weight, font-style and font-stretch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontQuery {
+    pub weight: u16,
+    pub style: css::FontStyle,
+    /// Width in percent of the normal width.
+    pub stretch: f32,
+}
+
+impl Default for FontQuery {
+    fn default() -> Self {
+        FontQuery {
+            weight: 400,
+            style: css::FontStyle::Normal,
+            stretch: 100.0,
+        }
+    }
+}
+
+impl FontQuery {
+    /// Reads font-weight, font-style and font-stretch of the given style. Unset properties use their initial value.
+    pub fn from_style(style: &css::ComputedStyle) -> FontQuery {
+        let mut query = FontQuery::default();
+        if let Some(weight) = style.get_value::<css::FontWeight>("font-weight").0 {
+            query.weight = weight.0;
+        }
+        if let Some(font_style) = style.get_value::<css::FontStyle>("font-style").0 {
+            query.style = font_style;
+        }
+        if let Some(stretch) = style.get_value::<css::FontStretch>("font-stretch").0 {
+            query.stretch = stretch.0;
+        }
+        query
     }
 }
 
+/// A face of a font family, together with the weight, style and stretch it covers.
+/// For variable fonts, weight and stretch are ranges spanned by the `wght` and `wdth` axes.
+/// The font data is shared between all faces of the same file.
 #[derive(Clone)]
-pub struct Font<'a> {
-    faces: HashMap<u32, ttf_parser::Face<'a>>,
-    pub used_index: u32,
+struct FamilyFace {
+    data: Arc<[u8]>,
+    index: u32,
+    weight: (f32, f32),
+    style: css::FontStyle,
+    stretch: (f32, f32),
 }
 
-impl<'a> Font<'a> {
-    pub fn new<'b: 'a>(data: &'b Vec<u8>, min: u32, max: u32) -> Font<'a> {
-        let mut font = Font {
-            faces: HashMap::new(),
-            used_index: 0,
+impl FamilyFace {
+    /// Parses the face with the given index. Returns None if the data does not contain a valid face at that index.
+    fn new(data: Arc<[u8]>, index: u32) -> Option<FamilyFace> {
+        let face = ttf_parser::Face::parse(&data, index).ok()?;
+        let weight = face.weight().to_number() as f32;
+        let stretch = match face.width() {
+            ttf_parser::Width::UltraCondensed => 50.0,
+            ttf_parser::Width::ExtraCondensed => 62.5,
+            ttf_parser::Width::Condensed => 75.0,
+            ttf_parser::Width::SemiCondensed => 87.5,
+            ttf_parser::Width::Normal => 100.0,
+            ttf_parser::Width::SemiExpanded => 112.5,
+            ttf_parser::Width::Expanded => 125.0,
+            ttf_parser::Width::ExtraExpanded => 150.0,
+            ttf_parser::Width::UltraExpanded => 200.0,
         };
-        for index in min..max {
-            if let Ok(face) = ttf_parser::Face::parse(data, index) {
-                font.faces.insert(index, face);
+        let style = match face.style() {
+            ttf_parser::Style::Normal => css::FontStyle::Normal,
+            ttf_parser::Style::Italic => css::FontStyle::Italic,
+            ttf_parser::Style::Oblique => css::FontStyle::Oblique,
+        };
+        let mut weight_range = (weight, weight);
+        let mut stretch_range = (stretch, stretch);
+        for axis in face.variation_axes() {
+            if axis.tag == WEIGHT_AXIS {
+                weight_range = (axis.min_value, axis.max_value);
+            } else if axis.tag == WIDTH_AXIS {
+                stretch_range = (axis.min_value, axis.max_value);
             }
         }
+        Some(FamilyFace {
+            data,
+            index,
+            weight: weight_range,
+            style,
+            stretch: stretch_range,
+        })
+    }
+
+    /// Parses the face from the shared font data. Parsing only reads the table directory, the tables are read when they are used.
+    fn face(&self) -> Option<ttf_parser::Face<'_>> {
+        ttf_parser::Face::parse(&self.data, self.index).ok()
+    }
+
+    /// Sort key for the font-stretch step of the font matching algorithm. Lower is better.
+    fn stretch_key(&self, desired: f32) -> (u8, f32) {
+        let (min, max) = self.stretch;
+        if desired >= min && desired <= max {
+            (0, 0.0)
+        } else if desired <= 100.0 {
+            // narrower widths are checked first, then wider ones
+            if max < desired { (1, desired - max) } else { (2, min - desired) }
+        } else {
+            // wider widths are checked first, then narrower ones
+            if min > desired { (1, min - desired) } else { (2, desired - max) }
+        }
+    }
+
+    /// Sort key for the font-style step of the font matching algorithm. Lower is better.
+    fn style_key(&self, desired: css::FontStyle) -> u8 {
+        let order = match desired {
+            css::FontStyle::Italic => [css::FontStyle::Italic, css::FontStyle::Oblique, css::FontStyle::Normal],
+            css::FontStyle::Oblique => [css::FontStyle::Oblique, css::FontStyle::Italic, css::FontStyle::Normal],
+            css::FontStyle::Normal => [css::FontStyle::Normal, css::FontStyle::Oblique, css::FontStyle::Italic],
+        };
+        order.iter().position(|s| *s == self.style).unwrap_or(order.len()) as u8
+    }
+
+    /// Sort key for the font-weight step of the font matching algorithm. Lower is better.
+    fn weight_key(&self, desired: f32) -> (u8, f32) {
+        let (min, max) = self.weight;
+        if desired >= min && desired <= max {
+            (0, 0.0)
+        } else if (400.0..=500.0).contains(&desired) {
+            // weights up to 500 first, then lighter ones, then heavier ones
+            if min > desired && min <= 500.0 { (1, min - desired) }
+            else if max < desired { (2, desired - max) }
+            else { (3, min - desired) }
+        } else if desired < 400.0 {
+            // lighter weights first, then heavier ones
+            if max < desired { (1, desired - max) } else { (2, min - desired) }
+        } else {
+            // heavier weights first, then lighter ones
+            if min > desired { (1, min - desired) } else { (2, desired - max) }
+        }
+    }
+}
+
+const WEIGHT_AXIS: ttf_parser::Tag = ttf_parser::Tag::from_bytes(b"wght");
+const WIDTH_AXIS: ttf_parser::Tag = ttf_parser::Tag::from_bytes(b"wdth");
+/// Horizontal skew used for synthetic oblique text (tan(14deg)).
+const SYNTHETIC_OBLIQUE_SKEW: f32 = 0.25;
+
+/// The face selected for a [FontQuery], with variation axes applied.
+/// Synthetic bold and oblique are set if the family has no face that matches the query closely enough.
+#[derive(Clone)]
+pub struct MatchedFace<'a> {
+    pub face: ttf_parser::Face<'a>,
+    pub synthetic_bold: bool,
+    pub synthetic_oblique: bool,
+}
+
+/// A font family. Holds all faces (regular, bold, italic, etc.) that were added to it.
+/// The font data is reference-counted, so a family can be cloned and stored without borrowing the data.
+#[derive(Clone, Default)]
+pub struct Font {
+    faces: Vec<FamilyFace>,
+}
+
+impl Font {
+    /// Creates a font family from the faces with index min..max of the given font (collection) data.
+    pub fn new(data: impl Into<Arc<[u8]>>, min: u32, max: u32) -> Font {
+        let mut font = Font::default();
+        font.add_faces(data, min, max);
         font
     }
 
-    pub fn face(&self) -> Option<&ttf_parser::Face<'a>> {
-        self.faces.get(&self.used_index)
+    /// Adds the faces with index min..max of the given font (collection) data to this family.
+    /// Use this to add i.e. the bold and italic files of a family. Returns the number of faces that were added.
+    pub fn add_faces(&mut self, data: impl Into<Arc<[u8]>>, min: u32, max: u32) -> usize {
+        let data = data.into();
+        let num_faces = self.faces.len();
+        for index in min..max {
+            if let Some(face) = FamilyFace::new(data.clone(), index) {
+                self.faces.push(face);
+            }
+        }
+        self.faces.len() - num_faces
+    }
+
+    /// Adds all faces of the given font (collection) data to this family. Returns the number of faces that were added.
+    pub fn add_all_faces(&mut self, data: impl Into<Arc<[u8]>>) -> usize {
+        let data = data.into();
+        let num_faces = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+        self.add_faces(data, 0, num_faces)
+    }
+
+    /// The face used for normal text.
+    pub fn face(&self) -> Option<ttf_parser::Face<'_>> {
+        self.best_face(&FontQuery::default()).and_then(|f| f.face())
+    }
+
+    /// Returns the face that matches the given query best, by comparing stretch, then style, then weight.
+    fn best_face(&self, query: &FontQuery) -> Option<&FamilyFace> {
+        let weight = query.weight as f32;
+        self.faces.iter().min_by(|a, b| {
+            let key_a = (a.stretch_key(query.stretch), a.style_key(query.style), a.weight_key(weight));
+            let key_b = (b.stretch_key(query.stretch), b.style_key(query.style), b.weight_key(weight));
+            key_a.partial_cmp(&key_b).unwrap_or(std::cmp::Ordering::Equal)
+        })
+    }
+
+    /// Selects the face which matches the given query best, using the css font matching algorithm (font-stretch, then font-style, then font-weight).
+    /// Sets the `wght` and `wdth` axes of variable faces. Returns None if this family has no faces.
+    pub fn select(&self, query: &FontQuery) -> Option<MatchedFace<'_>> {
+        let weight = query.weight as f32;
+        let best = self.best_face(query)?;
+        let mut face = best.face()?;
+        let used_weight = weight.clamp(best.weight.0, best.weight.1);
+        let used_stretch = query.stretch.clamp(best.stretch.0, best.stretch.1);
+        if face.is_variable() {
+            face.set_variation(WEIGHT_AXIS, used_weight);
+            face.set_variation(WIDTH_AXIS, used_stretch);
+        }
+        Some(MatchedFace {
+            face,
+            synthetic_bold: weight >= 600.0 && used_weight < 600.0,
+            synthetic_oblique: query.style != css::FontStyle::Normal && best.style == css::FontStyle::Normal,
+        })
     }
 
     /// Updates content flow of the given parent layout with the dimensions of the given text. Can break lines at any character.
-    pub fn text_layout(&self, text: &str, line_height: i32, parent_layout_mut: &mut layout::NodeLayoutInfo) {
-        if let Some(face) = self.face() {
-            let units_per_em = face.units_per_em() as f32;
-            for c in text.chars() {
-                if let Some(glyph) = face.glyph_index(c) {
-                    let hor_advance_f = face.glyph_hor_advance(glyph).unwrap_or(0) as f32;
-                    let hor_advance = (hor_advance_f / units_per_em * (line_height as f32)) as i32;
-                    parent_layout_mut.reverse_flow_inline(hor_advance, line_height);
+    pub fn text_layout(&self, text: &str, line_height: i32, text_style: &TextStyle, parent_layout_mut: &mut layout::NodeLayoutInfo) {
+        if let Some(MatchedFace { face, .. }) = self.select(&text_style.query) {
+            let scale = line_height as f32 / face.units_per_em() as f32;
+            if text_style.white_space == css::WhiteSpace::NoWrap || text_style.line_clamp.is_some() {
+                // Lines are flowed as a whole, so that unwrapped text is not broken by the parent's width and clamped lines are left out.
+                let max_width = parent_layout_mut.get::<{layout::LayoutValue::Width as usize}>();
+                let lines = self.text_lines(&face, text, scale, text_style, max_width, &HashSet::from([' ', '\n', '\t', '\r', '\u{00A0}']));
+                for (i, line) in lines.iter().enumerate() {
+                    if i > 0 {
+                        parent_layout_mut.reverse_break_line();
+                    }
+                    let indent = if i == 0 { text_style.text_indent } else { 0 };
+                    parent_layout_mut.reverse_flow_inline(indent + line.width(), line_height);
+                }
+                return;
+            }
+            let mut indent = text_style.text_indent;
+            for c in text_style.text_transform.apply(text).chars() {
+                if let Some(advance) = char_advance(&face, c, scale, text_style) {
+                    parent_layout_mut.reverse_flow_inline(advance + indent, line_height);
+                    indent = 0;
                 }
             }
         }
     }
 
-    // TODO fill shapes (currently only draws outlines)
     /// Dimensions of the given text with this font, using the given line height and max width.
     /// Optionally draws the text on the given canvas.
     /// - text [&str] - Text to measure.
-    /// - line_height [i32] - Height of one line of text.
-    /// - x0 [i32] - X position of the text.
+    /// - line_height [i32] - Height of one line of text, which is also the font size the glyphs are scaled to.
+    /// - _font_size [i32] - Unused, the glyphs are scaled to the line height.
+    /// - x0 [i32] - X position of the first line.
+    /// - y0 [i32] - Y position of the top of the first line.
+    /// - text_style [&TextStyle] - Face selection, direction, alignment, spacing, transformation and decoration of the text.
     /// - max_width [Option<i32>] - Maximum width of the text. Breaks the line if the width is exceeded, but only on characters in the break_on set.
-    /// That means the returned width can exceed max_width, if breaking it would result in an empty line.
-    /// - break_on [&HashSet<char>] - Characters on which a line break can be insterted due to max_width. If max_width is none, this is ignored.
-    /// - draw_on [Option<&mut sdl2::render::Canvas<Target>>] - If given, draws the text on the canvas.
+    /// That means the returned width can exceed max_width, if breaking it would result in an empty line. Lines are aligned within max_width.
+    /// - break_on [&HashSet<char>] - Characters on which a line break can be inserted due to max_width. If max_width is none, this is ignored.
+    /// - draw_on [Option<&mut sdl2::render::Canvas<Target>>] - If given, fills the glyphs on the canvas in its draw color, with the shadows and decorations of the text style.
     /// 
-    /// Returns a tuple of (width, height, x1, y1). Height is 1 line short.
-    pub fn render_text<Target: sdl2::render::RenderTarget>(&self, text: &str, line_height: i32, _font_size: i32, x0: i32, y0: i32, max_width: Option<i32>, break_on: &HashSet<char>, draw_on: Option<&mut sdl2::render::Canvas<Target>>) -> (i32, i32, i32, i32) {
-        let mut x = x0;
+    /// Returns a tuple of (width, height, x1, y1): the width of the widest line, the height without the last line,
+    /// and the end of the last line (its left edge in right-to-left text) with the top of the last line.
+    pub fn render_text<Target: sdl2::render::RenderTarget>(&self, text: &str, line_height: i32, _font_size: i32, x0: i32, y0: i32, text_style: &TextStyle, max_width: Option<i32>, break_on: &HashSet<char>, mut draw_on: Option<&mut sdl2::render::Canvas<Target>>) -> (i32, i32, i32, i32) where Canvas<Target>: util::PixmapTarget {
+        let matched_face = match self.select(&text_style.query) {
+            Some(matched_face) => matched_face,
+            None => return (0, 0, x0, y0)
+        };
+        let scale = line_height as f32 / matched_face.face.units_per_em() as f32;
+        let params = TextLayoutParams { text, line_height, x0, y0, text_style, max_width, break_on };
+        let lines = self.place_lines(&matched_face.face, scale, &params);
+        let mut max_line_width = 0;
+        let mut x1 = x0;
         let mut y = y0;
-        let mut break_x = 0;
-        let mut max_x = 0;
-        let mut i = 0;
-        let mut break_i0 = 0;
-        let mut break_i = 0;
-        let mut lines: Vec<String> = Vec::new();
-        let mut cur_line = String::new();
-        if let Some(face) = self.face() {
-            let ver_advance_f = face.units_per_em() as f32;
-            for c in text.chars() {
-                if let Some(glyph_id) = face.glyph_index(c) {
-                    if break_on.contains(&c) {
-                        break_x = x;
-                        break_i = i;
-                    }
-                    let hor_advance_f = face.glyph_hor_advance(glyph_id).unwrap_or(0) as f32;
-                    let hor_advance = (hor_advance_f / ver_advance_f * line_height as f32) as i32;
-                    if max_width.is_some() && (x + hor_advance > max_width.unwrap()) {
-                        // line break because max width is exceeded
-                        if x > max_x {
-                            max_x = x;
-                        }
-                        x = x - break_x;
-                        // dont break line if it would result in an empty line (break_x == 0)
-                        if break_x > 0 {
-                            y += line_height;
-                            break_x = 0;
-                            let old_line = cur_line.drain(0..(break_i-break_i0)).as_str().to_string();
-                            lines.push(old_line);
-                            break_i = i;
-                            break_i0 = i;
-                        }
-                    } else if c == '\n' {
-                        // line break in text
-                        if x > max_x {
-                            max_x = x;
-                        }
-                        x = 0;
-                        break_x = 0;
-                        break_i0 = i;
-                        break_i = i;
-                        y += line_height; 
-                        lines.push(cur_line.clone());
-                        cur_line.clear();
-                    } else {
-                        x += hor_advance;
-                        cur_line.push(c);
-                    }
-                    i += 1;
-                }
+        for placed_line in lines.iter() {
+            y = placed_line.y;
+            if let Some(canvas) = draw_on.as_deref_mut() {
+                draw_text_line(&matched_face, placed_line, scale, text_style, None, canvas);
             }
-            lines.push(cur_line.clone());
-            cur_line.clear();
-            if x > max_x {
-                max_x = x;
+            max_line_width = max_line_width.max(placed_line.width);
+            x1 = if placed_line.line.paragraph_level.is_rtl() { placed_line.x } else { placed_line.x + placed_line.line.width() };
+        }
+        (max_line_width, y - y0, x1, y)
+    }
+
+    /// Draws only the glyphs of the characters in the given range, where [Font::render_text] draws them with the same parameters.
+    /// The range indexes the characters after the text-transform, like [GlyphPosition::index]. Shadows and decorations are not drawn,
+    /// so that a range of the text can be drawn again over the whole text in another color.
+    ///
+    /// ```
+    /// use std::collections::HashSet;
+    /// use yargl::font::{FontDatabase, TextLayoutParams, TextStyle};
+    /// let mut db = FontDatabase::new();
+    /// db.load_font_file("arial", "res/font/arial.ttf").unwrap();
+    /// let font = db.get("arial").unwrap();
+    /// let surface = sdl2::surface::Surface::new(40, 20, sdl2::pixels::PixelFormatEnum::RGBA32).unwrap();
+    /// let mut canvas = surface.into_canvas().unwrap();
+    /// canvas.set_draw_color(sdl2::pixels::Color::RGB(0, 0, 0));
+    /// let (text_style, break_on) = (TextStyle::default(), HashSet::new());
+    /// let params = TextLayoutParams { text: "HH", line_height: 20, x0: 0, y0: 0, text_style: &text_style, max_width: None, break_on: &break_on };
+    /// let glyphs = font.glyph_positions(&params);
+    /// font.render_text_range(&params, 1..2, &mut canvas);
+    /// let pixels = canvas.read_pixels(None, sdl2::pixels::PixelFormatEnum::RGBA32).unwrap();
+    /// let inked = |glyph: &yargl::font::GlyphPosition| (glyph.x..glyph.x + glyph.advance).any(|x| (0..20).any(|y| pixels[(y * 40 + x) as usize * 4 + 3] > 0));
+    /// assert!(!inked(&glyphs[0]));
+    /// assert!(inked(&glyphs[1]));
+    /// ```
+    pub fn render_text_range<Target: sdl2::render::RenderTarget>(&self, params: &TextLayoutParams, range: std::ops::Range<usize>, canvas: &mut sdl2::render::Canvas<Target>) where Canvas<Target>: util::PixmapTarget {
+        let matched_face = match self.select(&params.text_style.query) {
+            Some(matched_face) => matched_face,
+            None => return
+        };
+        let scale = params.line_height as f32 / matched_face.face.units_per_em() as f32;
+        for placed_line in self.place_lines(&matched_face.face, scale, params).iter() {
+            draw_text_line(&matched_face, placed_line, scale, params.text_style, Some(&range), canvas);
+        }
+    }
+
+    /// Positions of the characters of the text as [Font::render_text] draws them with the same parameters, line by line in visual order.
+    /// Trailing whitespace of a line and characters without a glyph have no position, an ellipsis has the position of no character.
+    ///
+    /// ```
+    /// use std::collections::HashSet;
+    /// use yargl::font::{FontDatabase, TextLayoutParams, TextStyle};
+    /// let mut db = FontDatabase::new();
+    /// db.load_font_file("arial", "res/font/arial.ttf").unwrap();
+    /// let font = db.get("arial").unwrap();
+    /// let (text_style, break_on) = (TextStyle::default(), HashSet::from([' ']));
+    /// let params = TextLayoutParams { text: "ab cd", line_height: 20, x0: 10, y0: 5, text_style: &text_style, max_width: None, break_on: &break_on };
+    /// let glyphs = font.glyph_positions(&params);
+    /// assert_eq!(glyphs.iter().map(|g| g.index).collect::<Vec<_>>(), vec![Some(0), Some(1), Some(2), Some(3), Some(4)]);
+    /// assert_eq!(glyphs[0].x, 10);
+    /// assert!(glyphs.windows(2).all(|pair| pair[1].x == pair[0].x + pair[0].advance));
+    /// assert!(glyphs.iter().all(|g| g.y == 5 && g.height == 20 && g.line == 0));
+    /// let wrapped = font.glyph_positions(&TextLayoutParams { max_width: Some(30), ..params });
+    /// assert_eq!(wrapped.iter().filter(|g| g.line == 1).map(|g| (g.index, g.x, g.y)).next(), Some((Some(3), 10, 25)));
+    /// ```
+    pub fn glyph_positions(&self, params: &TextLayoutParams) -> Vec<GlyphPosition> {
+        let matched_face = match self.select(&params.text_style.query) {
+            Some(matched_face) => matched_face,
+            None => return Vec::new()
+        };
+        let scale = params.line_height as f32 / matched_face.face.units_per_em() as f32;
+        let mut positions = Vec::new();
+        for (line_index, placed_line) in self.place_lines(&matched_face.face, scale, params).iter().enumerate() {
+            // the same pen movement as draw_text_line
+            let mut pen_x = placed_line.x as f32;
+            for line_char in placed_line.line.visual_chars() {
+                let advance = line_char.advance as f32 + if line_char.c == ' ' { placed_line.space_extra } else { 0.0 };
+                positions.push(GlyphPosition {
+                    index: line_char.index,
+                    x: pen_x.round() as i32,
+                    y: placed_line.y,
+                    advance: (pen_x + advance).round() as i32 - pen_x.round() as i32,
+                    height: params.line_height,
+                    line: line_index,
+                });
+                pen_x += advance;
             }
         }
-        if let Some(canvas) = draw_on {
-            if let Some(face) = self.face() {
-                let scale = line_height as f32 / face.units_per_em() as f32;
-                let mut text_builder = TextCanvasBuilder::new(x0 as f32, (y0 + line_height) as f32, scale, canvas);
-                for line in lines {
-                    text_builder.x0 = x0 as f32;
-                    for c in line.chars() {
-                        if let Some(glyph_id) = face.glyph_index(c) {
-                            // TODO calculate baseline and y bearing
-                            let bearing_x = face.glyph_hor_side_bearing(glyph_id).unwrap_or(0) as f32 * scale;
-                            text_builder.x0 += bearing_x;
-                            let _bbox = face.outline_glyph(glyph_id, &mut text_builder);
-                            text_builder.x0 -= bearing_x;
-                            text_builder.x0 += scale * (face.glyph_hor_advance(glyph_id).unwrap_or(0) as f32);
-                        }
-                    }
-                    text_builder.y0 += line_height as f32;
+        positions
+    }
+
+    /// Breaks the text into lines and aligns them, as they are drawn.
+    fn place_lines(&self, face: &ttf_parser::Face, scale: f32, params: &TextLayoutParams) -> Vec<PlacedLine> {
+        let TextLayoutParams { text, line_height, x0, y0, text_style, max_width, break_on } = *params;
+        let lines = self.text_lines(face, text, scale, text_style, max_width, break_on);
+        let num_lines = lines.len();
+        lines.into_iter().enumerate().map(|(i, line)| {
+            let y = y0 + i as i32 * line_height;
+            let indent = if i == 0 { text_style.text_indent } else { 0 };
+            let line_width = indent + line.trimmed_width();
+            let free_width = max_width.map_or(0, |w| (w - line_width).max(0));
+            let last_in_paragraph = line.hard_break || i == num_lines - 1;
+            let num_spaces = line.chars.iter().take(line.trimmed_len()).filter(|line_char| line_char.c == ' ').count();
+            let rtl = line.paragraph_level.is_rtl();
+            let text_align = match (text_style.text_align, rtl) {
+                (css::TextAlign::Start, false) | (css::TextAlign::End, true) => css::TextAlign::Left,
+                (css::TextAlign::Start, true) | (css::TextAlign::End, false) => css::TextAlign::Right,
+                (text_align, _) => text_align,
+            };
+            let (offset, space_extra) = match text_align {
+                css::TextAlign::Right => (free_width, 0.0),
+                css::TextAlign::Center => (free_width / 2, 0.0),
+                css::TextAlign::Justify if !last_in_paragraph && num_spaces > 0 => (0, free_width as f32 / num_spaces as f32),
+                // justified right-to-left text that is not stretched starts at the right
+                css::TextAlign::Justify if rtl => (free_width, 0.0),
+                _ => (0, 0.0),
+            };
+            let drawn_width = line.trimmed_width() + (space_extra * num_spaces as f32) as i32;
+            // the first line is indented at its start edge, which is the right edge in right-to-left text
+            let x = if rtl { x0 + offset } else { x0 + indent + offset };
+            PlacedLine { line, x, y, space_extra, drawn_width, width: indent + offset + drawn_width }
+        }).collect()
+    }
+
+    /// Transforms the text, resolves its bidirectional embedding levels and breaks it into lines. Unless white-space is nowrap, lines are broken to fit max_width.
+    /// Clamps the number of lines to the text style's line clamp, and truncates overflowing lines with an ellipsis if text-overflow is ellipsis.
+    /// Lines are kept in logical order, see TextLine::visual_chars.
+    fn text_lines(&self, face: &ttf_parser::Face, text: &str, scale: f32, text_style: &TextStyle, max_width: Option<i32>, break_on: &HashSet<char>) -> Vec<TextLine> {
+        let text = text_style.text_transform.apply(text);
+        let wrap_width = if text_style.white_space == css::WhiteSpace::NoWrap { None } else { max_width };
+        let mut lines = break_lines(face, &bidi_levels(text.as_str(), text_style), scale, text_style, wrap_width, break_on);
+        // the ellipsis character, or three dots if the face has no glyph for it
+        let ellipsis: Vec<(char, i32)> = match char_advance(face, '\u{2026}', scale, text_style) {
+            Some(advance) => vec![('\u{2026}', advance)],
+            None => vec![('.', char_advance(face, '.', scale, text_style).unwrap_or(0)); 3],
+        };
+        if let Some(line_clamp) = text_style.line_clamp {
+            if lines.len() > line_clamp.max(1) {
+                lines.truncate(line_clamp.max(1));
+                if let Some(last_line) = lines.last_mut() {
+                    let available_width = max_width.unwrap_or_else(|| last_line.width() + ellipsis.iter().map(|(_, a)| a).sum::<i32>());
+                    last_line.truncate_with_ellipsis(available_width, &ellipsis);
                 }
             }
         }
-        (max_x, y - y0, x, y)
+        if let (css::TextOverflow::Ellipsis, Some(max_width)) = (text_style.text_overflow, max_width) {
+            for (i, line) in lines.iter_mut().enumerate() {
+                let available_width = if i == 0 { max_width - text_style.text_indent } else { max_width };
+                if line.trimmed_width() > available_width {
+                    line.truncate_with_ellipsis(available_width, &ellipsis);
+                }
+            }
+        }
+        lines
+    }
+}
+
+/// The text and how it is laid out, for the functions of [Font] that have to agree on the position of each character.
+#[derive(Debug, Clone, Copy)]
+pub struct TextLayoutParams<'t> {
+    pub text: &'t str,
+    /// Height of one line of text, which is also the font size.
+    pub line_height: i32,
+    /// Top left corner of the first line.
+    pub x0: i32,
+    pub y0: i32,
+    pub text_style: &'t TextStyle,
+    /// Lines are broken to fit the max width, and aligned within it.
+    pub max_width: Option<i32>,
+    /// Characters after which a line can be broken to fit the max width.
+    pub break_on: &'t HashSet<char>,
+}
+
+/// Text styling that is applied while laying out and drawing text, in addition to the selected face.
+#[derive(Debug, Clone)]
+pub struct TextStyle {
+    pub query: FontQuery,
+    /// Base direction of the text, used as paragraph direction by the bidirectional algorithm.
+    pub direction: css::Direction,
+    pub unicode_bidi: css::UnicodeBidi,
+    pub text_align: css::TextAlign,
+    pub text_transform: css::TextTransform,
+    /// Extra space after each character, in pixels.
+    pub letter_spacing: i32,
+    /// Extra space after each space character, in pixels.
+    pub word_spacing: i32,
+    /// Indentation of the first line, in pixels.
+    pub text_indent: i32,
+    pub decoration_line: css::TextDecorationLine,
+    /// Color of the decoration lines. The text color is used if None.
+    pub decoration_color: Option<sdl2::pixels::Color>,
+    /// Thickness of the decoration lines in pixels. The face's underline thickness is used if None.
+    pub decoration_thickness: Option<i32>,
+    /// Lines are only broken at line breaks in the text if white-space is nowrap.
+    pub white_space: css::WhiteSpace,
+    /// How lines that are wider than the max width are truncated. Should only be ellipsis if the element's overflow is not visible.
+    pub text_overflow: css::TextOverflow,
+    /// Maximum number of lines. The last line ends with an ellipsis if lines were left out.
+    pub line_clamp: Option<usize>,
+    /// Shadows of the glyphs, the first one is drawn on top. Shadows have no spread.
+    pub shadows: Vec<util::Shadow>,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        TextStyle {
+            query: FontQuery::default(),
+            direction: css::Direction::Ltr,
+            unicode_bidi: css::UnicodeBidi::Normal,
+            text_align: css::TextAlign::Start,
+            text_transform: css::TextTransform::None,
+            letter_spacing: 0,
+            word_spacing: 0,
+            text_indent: 0,
+            decoration_line: css::TextDecorationLine::default(),
+            decoration_color: None,
+            decoration_thickness: None,
+            white_space: css::WhiteSpace::Normal,
+            text_overflow: css::TextOverflow::Clip,
+            line_clamp: None,
+            shadows: Vec::new(),
+        }
+    }
+}
+
+/// A character of a line of text with its horizontal advance and its embedding level. Odd levels are right-to-left.
+#[derive(Debug, Clone, Copy)]
+struct LineChar {
+    c: char,
+    advance: i32,
+    level: Level,
+    // index of the character in the transformed text, None for an ellipsis
+    index: Option<usize>,
+}
+
+/// Position of a character of drawn text, see [Font::glyph_positions].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphPosition {
+    /// Index of the character in the text (in chars, after the text transform). None for the characters of an ellipsis.
+    pub index: Option<usize>,
+    /// Left edge of the character and top of its line.
+    pub x: i32,
+    pub y: i32,
+    /// Horizontal advance including letter, word and justification spacing.
+    pub advance: i32,
+    /// Height of the line.
+    pub height: i32,
+    /// Index of the line.
+    pub line: usize,
+}
+
+/// A line of text with its position after alignment.
+struct PlacedLine {
+    line: TextLine,
+    // start of the drawn characters and top of the line
+    x: i32,
+    y: i32,
+    // extra space per space character for justified text
+    space_extra: f32,
+    // width of the drawn characters
+    drawn_width: i32,
+    // width including the indent and the alignment offset
+    width: i32,
+}
+
+/// A line of text, as created by line breaking. Holds each character in logical order.
+struct TextLine {
+    chars: Vec<LineChar>,
+    /// True if the line ends with a line break in the text, false if it was broken because of the max width.
+    hard_break: bool,
+    /// Embedding level of the paragraph the line belongs to. Determines the start edge of the line.
+    paragraph_level: Level,
+}
+
+impl TextLine {
+    fn new(paragraph_level: Level) -> TextLine {
+        TextLine {
+            chars: Vec::new(),
+            hard_break: false,
+            paragraph_level,
+        }
+    }
+
+    fn width(&self) -> i32 {
+        self.chars.iter().map(|line_char| line_char.advance).sum()
+    }
+
+    /// Number of characters without trailing whitespace.
+    fn trimmed_len(&self) -> usize {
+        self.chars.iter().rposition(|line_char| !line_char.c.is_whitespace()).map_or(0, |i| i + 1)
+    }
+
+    /// Width without trailing whitespace. Used for alignment.
+    fn trimmed_width(&self) -> i32 {
+        self.chars.iter().take(self.trimmed_len()).map(|line_char| line_char.advance).sum()
+    }
+
+    /// Removes characters from the end of the line until the ellipsis fits into the available width, then appends the ellipsis.
+    /// The ellipsis takes the paragraph's direction.
+    fn truncate_with_ellipsis(&mut self, available_width: i32, ellipsis: &[(char, i32)]) {
+        let ellipsis_width: i32 = ellipsis.iter().map(|(_, advance)| advance).sum();
+        self.chars.truncate(self.trimmed_len());
+        while !self.chars.is_empty() && self.width() + ellipsis_width > available_width {
+            self.chars.pop();
+            self.chars.truncate(self.trimmed_len());
+        }
+        let level = self.paragraph_level;
+        self.chars.extend(ellipsis.iter().map(|(c, advance)| LineChar { c: *c, advance: *advance, level, index: None }));
+    }
+
+    /// Characters without trailing whitespace in visual order from left to right, reordered by their embedding levels.
+    /// Characters in right-to-left runs are replaced by their mirrored glyph, i.e. brackets.
+    fn visual_chars(&self) -> Vec<LineChar> {
+        let chars = &self.chars[..self.trimmed_len()];
+        let levels: Vec<Level> = chars.iter().map(|line_char| line_char.level).collect();
+        BidiInfo::reorder_visual(&levels).into_iter().map(|i| {
+            let mut line_char = chars[i];
+            if line_char.level.is_rtl() {
+                line_char.c = unicode_bidi_mirroring::get_mirrored(line_char.c).unwrap_or(line_char.c);
+            }
+            line_char
+        }).collect()
+    }
+}
+
+/// Resolves the embedding level of each character with the Unicode Bidirectional Algorithm.
+/// Returns each character with its level and the level of its paragraph. Paragraphs are separated by line breaks.
+/// Since the text of an element is resolved on its own, embed and isolate behave like normal.
+fn bidi_levels(text: &str, text_style: &TextStyle) -> Vec<(char, Level, Level)> {
+    let base_level = match text_style.direction {
+        css::Direction::Ltr => Level::ltr(),
+        css::Direction::Rtl => Level::rtl(),
+    };
+    match text_style.unicode_bidi {
+        // overrides ignore the directionality of the characters
+        css::UnicodeBidi::BidiOverride | css::UnicodeBidi::IsolateOverride => text.chars().map(|c| (c, base_level, base_level)).collect(),
+        unicode_bidi => {
+            // plaintext determines the direction of each paragraph from its first strong character
+            let default_level = if unicode_bidi == css::UnicodeBidi::Plaintext { None } else { Some(base_level) };
+            let bidi_info = BidiInfo::new(text, default_level);
+            text.char_indices().map(|(i, c)| {
+                let paragraph_level = bidi_info.paragraphs.iter()
+                    .find(|paragraph| paragraph.range.contains(&i))
+                    .map_or(base_level, |paragraph| paragraph.level);
+                (c, bidi_info.levels[i], paragraph_level)
+            }).collect()
+        }
+    }
+}
+
+/// Horizontal advance of a character in pixels, including letter and word spacing. None if the face has no glyph for the character.
+fn char_advance(face: &ttf_parser::Face, c: char, scale: f32, text_style: &TextStyle) -> Option<i32> {
+    let glyph_id = face.glyph_index(c)?;
+    let mut advance = (face.glyph_hor_advance(glyph_id).unwrap_or(0) as f32 * scale) as i32 + text_style.letter_spacing;
+    if c == ' ' {
+        advance += text_style.word_spacing;
+    }
+    Some(advance)
+}
+
+/// Breaks text into lines. Lines are broken at line breaks in the text, and after characters in break_on if the max width would be exceeded.
+/// The first line is shortened by the text indent.
+/// - chars [&[(char, Level, Level)]] - Characters with their embedding level and paragraph level, as returned by bidi_levels.
+fn break_lines(face: &ttf_parser::Face, chars: &[(char, Level, Level)], scale: f32, text_style: &TextStyle, max_width: Option<i32>, break_on: &HashSet<char>) -> Vec<TextLine> {
+    let mut lines = Vec::new();
+    let mut line = TextLine::new(chars.first().map_or(Level::ltr(), |(_, _, paragraph_level)| *paragraph_level));
+    let mut line_width = text_style.text_indent;
+    // index of the last character after which the line can be broken
+    let mut break_after: Option<usize> = None;
+    for (index, &(c, level, paragraph_level)) in chars.iter().enumerate() {
+        if c == '\n' {
+            line.hard_break = true;
+            lines.push(std::mem::replace(&mut line, TextLine::new(paragraph_level)));
+            line_width = 0;
+            break_after = None;
+            continue;
+        }
+        let advance = match char_advance(face, c, scale, text_style) {
+            Some(advance) => advance,
+            None => continue
+        };
+        if let (Some(max_width), Some(break_index)) = (max_width, break_after) {
+            // whitespace may overflow the line, it is not considered for alignment
+            if line_width + advance > max_width && !break_on.contains(&c) {
+                let rest = line.chars.split_off(break_index + 1);
+                lines.push(std::mem::replace(&mut line, TextLine { chars: rest, hard_break: false, paragraph_level }));
+                line_width = line.width();
+                break_after = None;
+            }
+        }
+        line.paragraph_level = paragraph_level;
+        line.chars.push(LineChar { c, advance, level, index: Some(index) });
+        line_width += advance;
+        if break_on.contains(&c) {
+            break_after = Some(line.chars.len() - 1);
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+/// Draws one placed line of text and its decorations.
+/// If a range of character indices is given, only the glyphs of these characters are drawn, without shadows and decorations.
+fn draw_text_line<Target: sdl2::render::RenderTarget>(matched_face: &MatchedFace, placed_line: &PlacedLine, scale: f32, text_style: &TextStyle, range: Option<&std::ops::Range<usize>>, canvas: &mut Canvas<Target>) where Canvas<Target>: util::PixmapTarget {
+    let PlacedLine { x: line_x, y, space_extra, drawn_width, .. } = *placed_line;
+    let face = &matched_face.face;
+    let baseline = y + (face.ascender() as f32 * scale) as i32;
+    // synthetic bold draws each glyph multiple times, shifted by one pixel each time
+    let bold_offset = if matched_face.synthetic_bold { ((face.units_per_em() as f32 * scale) as i32 / 24).max(1) } else { 0 };
+    let mut glyphs = util::Path::new();
+    let mut glyph_builder = GlyphPathBuilder::new(line_x as f32, baseline as f32, scale, &mut glyphs);
+    if matched_face.synthetic_oblique {
+        glyph_builder.skew = SYNTHETIC_OBLIQUE_SKEW;
+    }
+    let mut pen_x = line_x as f32;
+    for LineChar { c, advance, index, .. } in placed_line.line.visual_chars().iter() {
+        let drawn = range.is_none_or(|range| index.is_some_and(|index| range.contains(&index)));
+        if let Some(glyph_id) = face.glyph_index(*c).filter(|_| drawn) {
+            let bearing_x = face.glyph_hor_side_bearing(glyph_id).unwrap_or(0) as f32 * scale;
+            for offset in 0..=bold_offset {
+                glyph_builder.x0 = pen_x + bearing_x + offset as f32;
+                let _bbox = face.outline_glyph(glyph_id, &mut glyph_builder);
+            }
+        }
+        pen_x += *advance as f32;
+        if *c == ' ' {
+            pen_x += space_extra;
+        }
     }
-}
\ No newline at end of file
+    // glyphs are filled with anti-aliasing and drawn at once
+    if let Some(bounds) = glyphs.bounds() {
+        for shadow in text_style.shadows.iter().rev().filter(|_| range.is_none()) {
+            let margin = shadow.blur.ceil() as i32 + 1;
+            let region = sdl2::rect::Rect::new(
+                bounds.x() + shadow.offset_x.floor() as i32 - margin, bounds.y() + shadow.offset_y.floor() as i32 - margin,
+                bounds.width() + 2 * margin as u32 + 1, bounds.height() + 2 * margin as u32 + 1,
+            );
+            let mut shadow_mask = glyphs.translate(shadow.offset_x, shadow.offset_y).fill(util::FillRule::NonZero, region);
+            shadow_mask.blur(shadow.blur);
+            let mut pixmap = util::Pixmap::new(region);
+            pixmap.fill_mask(&shadow_mask, shadow.color);
+            let _ = canvas.draw_pixmap(&pixmap);
+        }
+        let mut pixmap = util::Pixmap::new(bounds);
+        pixmap.fill_mask(&glyphs.fill(util::FillRule::NonZero, bounds), canvas.draw_color());
+        let _ = canvas.draw_pixmap(&pixmap);
+    }
+
+    let decoration = text_style.decoration_line;
+    if !(decoration.underline || decoration.overline || decoration.line_through) || drawn_width <= 0 || range.is_some() {
+        return;
+    }
+    let text_color = canvas.draw_color();
+    canvas.set_draw_color(text_style.decoration_color.unwrap_or(text_color));
+    let underline = face.underline_metrics();
+    let thickness = text_style.decoration_thickness
+        .unwrap_or_else(|| underline.map_or(1, |m| (m.thickness as f32 * scale).round() as i32))
+        .max(1);
+    let mut line_ys = Vec::new();
+    if decoration.underline {
+        line_ys.push(baseline - underline.map_or(-(thickness * 2), |m| (m.position as f32 * scale) as i32));
+    }
+    if decoration.overline {
+        line_ys.push(y);
+    }
+    if decoration.line_through {
+        let strikeout_position = face.strikeout_metrics().map_or(face.x_height().unwrap_or(0) as f32 / 2.0, |m| m.position as f32);
+        line_ys.push(baseline - (strikeout_position * scale) as i32);
+    }
+    for line_y in line_ys {
+        let _ = canvas.fill_rect(sdl2::rect::Rect::new(line_x, line_y, drawn_width as u32, thickness as u32));
+    }
+    canvas.set_draw_color(text_color);
+}
+
+/// Owns all loaded font families, keyed by their lowercase family name.
+#[derive(Default)]
+pub struct FontDatabase {
+    families: HashMap<String, Font>,
+    /// Files loaded with [FontDatabase::load_font_file], keyed by lowercase family name and path, with their number of faces.
+    files: HashMap<(String, PathBuf), usize>,
+}
+
+impl FontDatabase {
+    pub fn new() -> FontDatabase {
+        FontDatabase::default()
+    }
+
+    /// Adds all faces of the given font (collection) data to a family. Creates the family if it does not exist yet.
+    /// Returns the number of faces that were added. If the family already holds faces of the same data, nothing is added
+    /// and the number of those faces is returned.
+    pub fn load_font_bytes(&mut self, family: &str, data: impl Into<Arc<[u8]>>) -> Result<usize, FontLoadError> {
+        let data = data.into();
+        if let Some(font) = self.get(family) {
+            let loaded = font.faces.iter().filter(|f| Arc::ptr_eq(&f.data, &data) || *f.data == *data).count();
+            if loaded > 0 {
+                return Ok(loaded);
+            }
+        }
+        let mut font = self.families.remove(&family.to_lowercase()).unwrap_or_default();
+        let num_faces = font.add_all_faces(data);
+        if !font.faces.is_empty() {
+            self.families.insert(family.to_lowercase(), font);
+        }
+        if num_faces == 0 {
+            return Err(FontLoadError { message: format!("No valid font face found for family {}", family) });
+        }
+        Ok(num_faces)
+    }
+
+    /// Reads a font (collection) file and adds all of its faces to a family. Creates the family if it does not exist yet.
+    /// Returns the number of faces that were added. A file that was already loaded into the family is not read again.
+    pub fn load_font_file(&mut self, family: &str, path: impl AsRef<Path>) -> Result<usize, FontLoadError> {
+        let key = (family.to_lowercase(), path.as_ref().to_path_buf());
+        if let Some(&num_faces) = self.files.get(&key) {
+            return Ok(num_faces);
+        }
+        let data = std::fs::read(path.as_ref())
+            .map_err(|e| FontLoadError { message: format!("Failed to read font file {}: {}", path.as_ref().display(), e) })?;
+        let num_faces = self.load_font_bytes(family, data)?;
+        self.files.insert(key, num_faces);
+        Ok(num_faces)
+    }
+
+    /// Inserts a font family, replacing a family with the same name.
+    pub fn insert(&mut self, family: &str, font: Font) {
+        let family = family.to_lowercase();
+        self.files.retain(|(name, _), _| *name != family);
+        self.families.insert(family, font);
+    }
+
+    /// Returns the family with the given name (case-insensitive).
+    pub fn get(&self, family: &str) -> Option<&Font> {
+        self.families.get(&family.to_lowercase())
+    }
+
+    /// Returns the first loaded family of a comma-separated font-family value.
+    pub fn find(&self, font_family: &str) -> Option<&Font> {
+        font_family.split(',').find_map(|name| self.get(name.trim().trim_matches(|c| c == '"' || c == '\'')))
+    }
+}
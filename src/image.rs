@@ -0,0 +1,135 @@
+use std::{collections::HashMap, path::{Path, PathBuf}};
+
+use crate::{css, svg, util};
+
+#[derive(Debug)]
+pub struct ImageLoadError {
+    pub message: String,
+}
+
+/// Largest width or height in pixels that SVG images are rendered at.
+const MAX_RENDER_SIZE: u32 = 8192;
+
+/// Decodes a PNG, JPEG, GIF, WebP, BMP or SVG file into a pixmap at the origin. Animated images are decoded as their first frame,
+/// SVG images are rendered at their intrinsic size, scaled down to at most [MAX_RENDER_SIZE] pixels wide and high.
+pub fn decode_file(path: impl AsRef<Path>) -> Result<util::Pixmap, ImageLoadError> {
+    let data = std::fs::read(path.as_ref())
+        .map_err(|e| ImageLoadError { message: format!("Failed to read image file {}: {}", path.as_ref().display(), e) })?;
+    decode_bytes(&data)
+}
+
+/// Decodes PNG, JPEG, GIF, WebP, BMP or SVG data into a pixmap at the origin. The format is guessed from the data.
+pub fn decode_bytes(data: &[u8]) -> Result<util::Pixmap, ImageLoadError> {
+    if let Some(document) = parse_svg(data) {
+        let (width, height) = document.size();
+        let scale = (MAX_RENDER_SIZE as f32 / width.max(height)).min(1.0);
+        let (width, height) = ((width * scale).ceil() as u32, (height * scale).ceil() as u32);
+        let region = sdl2::rect::Rect::new(0, 0, width.clamp(1, MAX_RENDER_SIZE), height.clamp(1, MAX_RENDER_SIZE));
+        return Ok(document.render(region, sdl2::pixels::Color::RGB(0, 0, 0), 1.0));
+    }
+    let decoded = ::image::load_from_memory(data)
+        .map_err(|e| ImageLoadError { message: format!("Failed to decode image: {}", e) })?
+        .into_rgba8();
+    let (width, height) = decoded.dimensions();
+    util::Pixmap::from_rgba(width, height, decoded.into_raw())
+        .ok_or(ImageLoadError { message: "Decoded image has an unexpected size".to_string() })
+}
+
+/// Parses the data as an SVG document if it is text that contains an `<svg>` element.
+fn parse_svg(data: &[u8]) -> Option<svg::Document> {
+    let source = std::str::from_utf8(data).ok()?;
+    if !source.trim_start().starts_with('<') || !source.contains("<svg") {
+        return None;
+    }
+    svg::Document::parse(source).ok()
+}
+
+/// Decoded images used by a window, keyed by their resolved path. Only the pixels are cached, not textures: a texture can't outlive
+/// the texture creator of the window's canvas, so an image is uploaded into a new texture each time it is drawn.
+/// Images that could not be loaded are remembered as well, so they are not decoded again on every layout and draw.
+/// SVG images are also kept as documents, so they can be rendered crisply at the size they are drawn with.
+/// The last rendering of each SVG image is kept with the device pixel ratio it was rendered for, so it is not rendered again while its size stays the same.
+#[derive(Default)]
+pub struct ImageCache {
+    images: HashMap<PathBuf, Option<util::Pixmap>>,
+    documents: HashMap<PathBuf, Option<svg::Document>>,
+    renderings: HashMap<PathBuf, (f32, util::Pixmap)>,
+}
+
+impl ImageCache {
+    pub fn new() -> ImageCache {
+        ImageCache::default()
+    }
+
+    /// Returns the decoded image file, decoding it on first use. None if the image could not be loaded.
+    pub fn load(&mut self, path: &Path) -> Option<&util::Pixmap> {
+        if !self.images.contains_key(path) {
+            self.images.insert(path.to_path_buf(), decode_file(path).ok());
+        }
+        self.images.get(path).and_then(|image| image.as_ref())
+    }
+
+    /// Returns the document of an SVG image file (with an .svg extension), parsing it on first use. None if the file is not an SVG image.
+    pub fn document(&mut self, path: &Path) -> Option<&svg::Document> {
+        if !self.documents.contains_key(path) {
+            let is_svg = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("svg"));
+            let document = if is_svg { std::fs::read(path).ok().and_then(|data| parse_svg(&data)) } else { None };
+            self.documents.insert(path.to_path_buf(), document);
+        }
+        self.documents.get(path).and_then(|document| document.as_ref())
+    }
+
+    /// Returns the SVG image file rendered at the given size, with its top left corner at the origin.
+    /// None if the file is not an SVG image or the size is larger than [MAX_RENDER_SIZE], then the decoded image is scaled instead.
+    pub fn render(&mut self, path: &Path, width: u32, height: u32, device_pixel_ratio: f32) -> Option<&util::Pixmap> {
+        if width > MAX_RENDER_SIZE || height > MAX_RENDER_SIZE {
+            return None;
+        }
+        let rendered = self.renderings.get(path)
+            .is_some_and(|(ratio, pixmap)| *ratio == device_pixel_ratio && pixmap.width == width && pixmap.height == height);
+        if !rendered {
+            let pixmap = self.document(path)?.render(sdl2::rect::Rect::new(0, 0, width, height), sdl2::pixels::Color::RGB(0, 0, 0), device_pixel_ratio);
+            self.renderings.insert(path.to_path_buf(), (device_pixel_ratio, pixmap));
+        }
+        self.renderings.get(path).map(|(_, pixmap)| pixmap)
+    }
+
+    /// Returns the size of the image file, loading it if neccessary. The size of SVG images is read from their document without rendering them.
+    pub fn size(&mut self, path: &Path) -> Option<(u32, u32)> {
+        if let Some(document) = self.document(path) {
+            let (width, height) = document.size();
+            return Some((width.ceil() as u32, height.ceil() as u32));
+        }
+        self.load(path).map(|image| (image.width, image.height))
+    }
+}
+
+/// Size of a replaced object (i.e. an image) inside a box of the given size, according to the object-fit property.
+///
+/// ```
+/// use yargl::{css::ObjectFit, image::object_fit_size};
+/// assert_eq!(object_fit_size(ObjectFit::Fill, (100.0, 50.0), (40.0, 40.0)), (40.0, 40.0));
+/// assert_eq!(object_fit_size(ObjectFit::Contain, (100.0, 50.0), (40.0, 40.0)), (40.0, 20.0));
+/// assert_eq!(object_fit_size(ObjectFit::Cover, (100.0, 50.0), (40.0, 40.0)), (80.0, 40.0));
+/// assert_eq!(object_fit_size(ObjectFit::None, (100.0, 50.0), (40.0, 40.0)), (100.0, 50.0));
+/// assert_eq!(object_fit_size(ObjectFit::ScaleDown, (10.0, 5.0), (40.0, 40.0)), (10.0, 5.0));
+/// ```
+pub fn object_fit_size(fit: css::ObjectFit, object: (f32, f32), container: (f32, f32)) -> (f32, f32) {
+    let (object_width, object_height) = object;
+    let (container_width, container_height) = container;
+    if object_width <= 0.0 || object_height <= 0.0 {
+        return container;
+    }
+    let contain_scale = (container_width / object_width).min(container_height / object_height);
+    let cover_scale = (container_width / object_width).max(container_height / object_height);
+    match fit {
+        css::ObjectFit::Fill => container,
+        css::ObjectFit::Contain => (object_width * contain_scale, object_height * contain_scale),
+        css::ObjectFit::Cover => (object_width * cover_scale, object_height * cover_scale),
+        css::ObjectFit::None => object,
+        css::ObjectFit::ScaleDown => {
+            let scale = contain_scale.min(1.0);
+            (object_width * scale, object_height * scale)
+        },
+    }
+}
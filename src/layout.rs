@@ -34,6 +34,13 @@ pub enum LayoutValue {
     BorderTopRightRadius,
     BorderBottomLeftRadius,
     BorderBottomRightRadius,
+    /// Vertical radius of an elliptical corner. The values without Y suffix are the horizontal radii.
+    BorderTopLeftRadiusY,
+    BorderTopRightRadiusY,
+    BorderBottomLeftRadiusY,
+    BorderBottomRightRadiusY,
+    /// Index of the line the element was flowed on in its parent, counted from the last line.
+    ReverseLineIndex,
     MaxValue
 }
 
@@ -71,7 +78,12 @@ impl Into<usize> for LayoutValue {
             LayoutValue::BorderTopRightRadius => 28,
             LayoutValue::BorderBottomLeftRadius => 29,
             LayoutValue::BorderBottomRightRadius => 30,
-            LayoutValue::MaxValue => 31
+            LayoutValue::BorderTopLeftRadiusY => 31,
+            LayoutValue::BorderTopRightRadiusY => 32,
+            LayoutValue::BorderBottomLeftRadiusY => 33,
+            LayoutValue::BorderBottomRightRadiusY => 34,
+            LayoutValue::ReverseLineIndex => 35,
+            LayoutValue::MaxValue => 36
         }
     }
 }
@@ -110,7 +122,12 @@ impl From<usize> for LayoutValue {
             28 => LayoutValue::BorderTopRightRadius,
             29 => LayoutValue::BorderBottomLeftRadius,
             30 => LayoutValue::BorderBottomRightRadius,
-            31 => LayoutValue::MaxValue,
+            31 => LayoutValue::BorderTopLeftRadiusY,
+            32 => LayoutValue::BorderTopRightRadiusY,
+            33 => LayoutValue::BorderBottomLeftRadiusY,
+            34 => LayoutValue::BorderBottomRightRadiusY,
+            35 => LayoutValue::ReverseLineIndex,
+            36 => LayoutValue::MaxValue,
             _ => panic!("Invalid layout value"),
         }
     }
@@ -175,12 +192,15 @@ impl ToString for LayoutValue {
 #[derive(Debug)]
 pub struct  NodeLayoutInfo {
     values: Vec<Option<i32>>,
+    /// Width of each broken line, starting with the last line.
+    reverse_line_widths: Vec<i32>,
 }
 
 impl NodeLayoutInfo {
     pub fn new() -> NodeLayoutInfo {
         NodeLayoutInfo {
             values: vec![None; LayoutValue::MaxValue as usize],
+            reverse_line_widths: Vec::new(),
         }
     }
 
@@ -193,6 +213,13 @@ impl NodeLayoutInfo {
     // TODO somehow this ends up in correct layout, but reversed. figure out why (current fix is to flip it afterwards)
 
     /// Updates content flow for an inline child. Returns the child's position. Call in reverse order of children.
+    /// ```
+    /// use yargl::layout::NodeLayoutInfo;
+    /// let mut layout = NodeLayoutInfo::new();
+    /// assert_eq!(layout.reverse_flow_inline(10, 5), (0, 0));
+    /// assert_eq!(layout.reverse_flow_inline(20, 5), (10, 0));
+    /// assert_eq!(layout.reverse_flow_inline(30, 5), (30, 0));
+    /// ```
     pub fn reverse_flow_inline(&mut self, child_width: i32, child_height: i32) -> (i32, i32) {
         let mut cur_content_x = -self.values[LayoutValue::ContentX as usize].unwrap_or(0);
         let mut child_x = cur_content_x;
@@ -211,7 +238,7 @@ impl NodeLayoutInfo {
         let cur_content_line_width = self.values[LayoutValue::ContentLineWidth as usize].unwrap_or(0);
         let cur_content_line_height = self.values[LayoutValue::ContentLineHeight as usize].unwrap_or(0);
 
-        self.set::<{LayoutValue::ContentX as usize}>(Some(cur_content_x - child_width));
+        self.set::<{LayoutValue::ContentX as usize}>(Some(-(cur_content_x + child_width)));
         self.set::<{LayoutValue::ContentLineWidth as usize}>(Some(cur_content_line_width + child_width));
         self.set::<{LayoutValue::ContentLineHeight as usize}>(Some(cur_content_line_height.max(child_height)));
 
@@ -236,6 +263,7 @@ impl NodeLayoutInfo {
         let content_width = self.values[LayoutValue::ContentWidth as usize].unwrap_or(0);
         let content_height = self.values[LayoutValue::ContentHeight as usize].unwrap_or(0);
 
+        self.reverse_line_widths.push(line_width);
         self.set::<{LayoutValue::ContentWidth as usize}>(Some(content_width.max(line_width)));
         self.set::<{LayoutValue::ContentHeight as usize}>(Some(content_height + line_height));
 
@@ -245,6 +273,28 @@ impl NodeLayoutInfo {
         self.set::<{LayoutValue::ContentLineHeight as usize}>(Some(0));
     }
 
+    /// Index of the line that is currently being flowed, counted from the last line.
+    pub fn reverse_line_index(&self) -> usize {
+        self.reverse_line_widths.len()
+    }
+
+    /// Width of a broken line, as indexed by reverse_line_index.
+    pub fn reverse_line_width(&self, index: usize) -> Option<i32> {
+        self.reverse_line_widths.get(index).copied()
+    }
+
+    /// Horizontal position of an inline child within its line box, given the position returned by reverse_flow_inline.
+    /// Children are placed from left to right, or from right to left within the given width if rtl is true.
+    pub fn resolve_inline_x(&self, reverse_x: i32, child_width: i32, reverse_line_index: usize, rtl: bool, width: i32) -> i32 {
+        let line_width = self.reverse_line_width(reverse_line_index).unwrap_or(0);
+        // reverse_x is the width of all children after this one on the same line
+        if rtl {
+            width - line_width + reverse_x
+        } else {
+            line_width - reverse_x - child_width
+        }
+    }
+
     pub fn is_complete(&self) -> bool {
         self.values.iter().all(|v| v.is_some())
     }
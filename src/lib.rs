@@ -6,3 +6,5 @@ pub mod window;
 pub mod context;
 pub mod css;
 pub mod layout;
+pub mod image;
+pub mod svg;
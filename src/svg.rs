@@ -0,0 +1,693 @@
+use std::str::FromStr;
+
+use crate::{css, util};
+
+#[derive(Debug)]
+pub struct SvgParseError {
+    pub message: String,
+}
+
+/// Paint of a fill or a stroke.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Paint {
+    None,
+    Color(sdl2::pixels::Color),
+    /// The color of the element that uses the SVG (the `color` property of an inline `<svg>`), black for images.
+    CurrentColor,
+}
+
+impl FromStr for Paint {
+    type Err = SvgParseError;
+
+    /// Parses a fill or stroke value. Besides the CSS color formats, shorthand hex colors and basic color keywords are supported.
+    ///
+    /// ```
+    /// use yargl::svg::Paint;
+    /// assert_eq!("#f00".parse::<Paint>().unwrap(), Paint::Color(sdl2::pixels::Color::RGB(255, 0, 0)));
+    /// assert_eq!("white".parse::<Paint>().unwrap(), Paint::Color(sdl2::pixels::Color::RGB(255, 255, 255)));
+    /// assert_eq!("currentColor".parse::<Paint>().unwrap(), Paint::CurrentColor);
+    /// assert_eq!("none".parse::<Paint>().unwrap(), Paint::None);
+    /// assert!("url(#gradient)".parse::<Paint>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let keyword_color = match s.to_ascii_lowercase().as_str() {
+            "none" => return Ok(Paint::None),
+            "currentcolor" => return Ok(Paint::CurrentColor),
+            "black" => Some((0, 0, 0)),
+            "white" => Some((255, 255, 255)),
+            "red" => Some((255, 0, 0)),
+            "green" => Some((0, 128, 0)),
+            "lime" => Some((0, 255, 0)),
+            "blue" => Some((0, 0, 255)),
+            "yellow" => Some((255, 255, 0)),
+            "orange" => Some((255, 165, 0)),
+            "gray" | "grey" => Some((128, 128, 128)),
+            _ => None,
+        };
+        if let Some((r, g, b)) = keyword_color {
+            return Ok(Paint::Color(sdl2::pixels::Color::RGB(r, g, b)));
+        }
+        let expanded = match s.strip_prefix('#') {
+            Some(hex) if hex.len() == 3 || hex.len() == 4 => format!("#{}", hex.chars().flat_map(|c| [c, c]).collect::<String>()),
+            _ => s.to_string(),
+        };
+        expanded.parse::<css::CssColor>()
+            .map(|color| Paint::Color(color.sdl_color))
+            .map_err(|_| SvgParseError { message: format!("invalid or unsupported paint: {}", s) })
+    }
+}
+
+/// How the viewBox is fitted into the viewport, from the preserveAspectRatio attribute.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AspectRatio {
+    /// Horizontal and vertical alignment (0 for min, 0.5 for mid, 1 for max). None if the viewBox is stretched.
+    align: Option<(f32, f32)>,
+    /// Whether the viewBox covers the viewport instead of being contained in it.
+    slice: bool,
+}
+
+impl Default for AspectRatio {
+    fn default() -> Self {
+        AspectRatio { align: Some((0.5, 0.5)), slice: false }
+    }
+}
+
+impl AspectRatio {
+    fn parse(value: &str) -> Option<AspectRatio> {
+        let mut tokens = value.split_whitespace();
+        let align = match tokens.next()? {
+            "none" => None,
+            align => {
+                let component = |name: &str| match name {
+                    "Min" => Some(0.0),
+                    "Mid" => Some(0.5),
+                    "Max" => Some(1.0),
+                    _ => None,
+                };
+                let (x, y) = (align.strip_prefix('x')?.get(0..3)?, align.get(4..)?);
+                Some((component(x)?, component(y.strip_prefix('Y').unwrap_or(y))?))
+            },
+        };
+        let slice = tokens.next() == Some("slice");
+        Some(AspectRatio { align, slice })
+    }
+}
+
+/// Fill and stroke properties, which are inherited by the children of an SVG element.
+#[derive(Debug, Clone, Copy)]
+struct Presentation {
+    fill: Paint,
+    fill_opacity: f32,
+    fill_rule: util::FillRule,
+    stroke: Paint,
+    stroke_opacity: f32,
+    stroke_style: util::StrokeStyle,
+    /// Value of the `color` attribute, which replaces currentColor.
+    color: Option<sdl2::pixels::Color>,
+}
+
+impl Default for Presentation {
+    fn default() -> Self {
+        Presentation {
+            fill: Paint::Color(sdl2::pixels::Color::RGB(0, 0, 0)),
+            fill_opacity: 1.0,
+            fill_rule: util::FillRule::NonZero,
+            stroke: Paint::None,
+            stroke_opacity: 1.0,
+            stroke_style: util::StrokeStyle::default(),
+            color: None,
+        }
+    }
+}
+
+impl Presentation {
+    /// The presentation of an element: the inherited properties, overridden by the element's presentation attributes and style attribute.
+    fn with_element(&self, tag: &tl::HTMLTag) -> Presentation {
+        let mut presentation = *self;
+        for (name, value) in declarations(tag) {
+            let opacity = || number(&value).map(|opacity| opacity.clamp(0.0, 1.0));
+            match name.as_str() {
+                "fill" => presentation.fill = value.parse().unwrap_or(presentation.fill),
+                "fill-opacity" => presentation.fill_opacity = opacity().unwrap_or(presentation.fill_opacity),
+                "fill-rule" => presentation.fill_rule = if value.trim() == "evenodd" { util::FillRule::EvenOdd } else { util::FillRule::NonZero },
+                "stroke" => presentation.stroke = value.parse().unwrap_or(presentation.stroke),
+                "stroke-opacity" => presentation.stroke_opacity = opacity().unwrap_or(presentation.stroke_opacity),
+                "stroke-width" => presentation.stroke_style.width = number(&value).unwrap_or(presentation.stroke_style.width),
+                "stroke-miterlimit" => presentation.stroke_style.miter_limit = number(&value).unwrap_or(presentation.stroke_style.miter_limit),
+                "stroke-linecap" => match value.trim() {
+                    "butt" => presentation.stroke_style.cap = util::LineCap::Butt,
+                    "round" => presentation.stroke_style.cap = util::LineCap::Round,
+                    "square" => presentation.stroke_style.cap = util::LineCap::Square,
+                    _ => {}
+                },
+                "stroke-linejoin" => match value.trim() {
+                    "miter" => presentation.stroke_style.join = util::LineJoin::Miter,
+                    "round" => presentation.stroke_style.join = util::LineJoin::Round,
+                    "bevel" => presentation.stroke_style.join = util::LineJoin::Bevel,
+                    _ => {}
+                },
+                "color" => presentation.color = match value.parse::<Paint>() {
+                    Ok(Paint::Color(color)) => Some(color),
+                    _ => presentation.color,
+                },
+                _ => {}
+            }
+        }
+        // currentColor refers to the color of the element that declares it
+        if let Some(color) = presentation.color {
+            if presentation.fill == Paint::CurrentColor {
+                presentation.fill = Paint::Color(color);
+            }
+            if presentation.stroke == Paint::CurrentColor {
+                presentation.stroke = Paint::Color(color);
+            }
+        }
+        presentation
+    }
+}
+
+/// A filled and/or stroked shape of an SVG document, in the coordinates of the viewBox.
+#[derive(Debug, Clone)]
+struct Shape {
+    path: util::Path,
+    transform: util::Transform,
+    presentation: Presentation,
+    /// Product of the opacities of the shape and its ancestors.
+    opacity: f32,
+}
+
+/// An SVG document, reduced to the shapes it paints. Gradients, patterns, clipping, masks, text and `<use>` are not supported,
+/// group opacity is applied to each shape of the group separately.
+///
+/// ```
+/// use yargl::svg::Document;
+/// let document = Document::parse(r##"<svg xmlns="http://www.w3.org/2000/svg" width="24" viewBox="0 0 12 6">
+///     <rect x="0" y="0" width="6" height="6" fill="currentColor"/>
+///     <path d="M6 0h6v6z" fill="#00f"/>
+/// </svg>"##).unwrap();
+/// assert_eq!(document.size(), (24.0, 12.0));
+/// let pixmap = document.render(sdl2::rect::Rect::new(0, 0, 24, 12), sdl2::pixels::Color::RGB(255, 0, 0), 1.0);
+/// assert_eq!(pixmap.pixel(2, 2), sdl2::pixels::Color::RGBA(255, 0, 0, 255));
+/// assert_eq!(pixmap.pixel(22, 4), sdl2::pixels::Color::RGBA(0, 0, 255, 255));
+/// assert_eq!(pixmap.pixel(14, 10).a, 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Document {
+    width: Option<f32>,
+    height: Option<f32>,
+    view_box: Option<[f32; 4]>,
+    aspect_ratio: AspectRatio,
+    shapes: Vec<Shape>,
+}
+
+impl Document {
+    /// Parses an SVG file's content. The first `<svg>` element is used.
+    pub fn parse(source: &str) -> Result<Document, SvgParseError> {
+        let vdom = tl::parse(source, tl::ParserOptions::default())
+            .map_err(|e| SvgParseError { message: format!("Failed to parse svg: {}", e) })?;
+        vdom.nodes().iter()
+            .find_map(|node| Document::from_node(node, vdom.parser()))
+            .ok_or(SvgParseError { message: "No svg element found".to_string() })
+    }
+
+    /// Creates a document from an `<svg>` element, i.e. one that is part of an html document. None if the node is not an `<svg>` element.
+    pub fn from_node(node: &tl::Node, parser: &tl::Parser) -> Option<Document> {
+        let tag = node.as_tag().filter(|tag| is_svg_element(tag))?;
+        let view_box = attribute(tag, "viewBox").and_then(|value| {
+            let numbers = numbers(&value);
+            match numbers.as_slice() {
+                &[x, y, width, height] if width > 0.0 && height > 0.0 => Some([x, y, width, height]),
+                _ => None,
+            }
+        });
+        let mut document = Document {
+            width: attribute(tag, "width").and_then(|value| number(&value)),
+            height: attribute(tag, "height").and_then(|value| number(&value)),
+            view_box,
+            aspect_ratio: attribute(tag, "preserveAspectRatio").and_then(|value| AspectRatio::parse(&value)).unwrap_or_default(),
+            shapes: Vec::new(),
+        };
+        let presentation = Presentation::default().with_element(tag);
+        let opacity = attribute(tag, "opacity").and_then(|value| number(&value)).unwrap_or(1.0).clamp(0.0, 1.0);
+        let transform = attribute(tag, "transform").and_then(|value| parse_transform(&value)).unwrap_or_default();
+        document.collect_shapes(tag, parser, &presentation, transform, opacity);
+        Some(document)
+    }
+
+    /// Intrinsic size from the width and height attributes, completed by the viewBox's aspect ratio.
+    /// Without width and height the size of the viewBox is used, without both the default size of 300x150.
+    pub fn size(&self) -> (f32, f32) {
+        match (self.width, self.height, self.view_box) {
+            (Some(width), Some(height), _) => (width, height),
+            (Some(width), None, Some([_, _, view_width, view_height])) => (width, width * view_height / view_width),
+            (None, Some(height), Some([_, _, view_width, view_height])) => (height * view_width / view_height, height),
+            (None, None, Some([_, _, view_width, view_height])) => (view_width, view_height),
+            (width, height, _) => (width.unwrap_or(300.0), height.unwrap_or(150.0)),
+        }
+    }
+
+    /// Renders the document into a pixmap that covers the region, scaling the viewBox to the region's size.
+    /// Without a viewBox, user units are css pixels and are scaled by the device pixel ratio.
+    pub fn render(&self, region: sdl2::rect::Rect, current_color: sdl2::pixels::Color, device_pixel_ratio: f32) -> util::Pixmap {
+        let mut pixmap = util::Pixmap::new(region);
+        let view_transform = util::Transform::translate(region.x() as f32, region.y() as f32)
+            .multiply(&self.view_transform(region.width() as f32, region.height() as f32, device_pixel_ratio));
+        for shape in self.shapes.iter() {
+            let transform = view_transform.multiply(&shape.transform);
+            let path = shape.path.transform(&transform);
+            let presentation = &shape.presentation;
+            let paint_color = |paint: Paint, opacity: f32| {
+                let color = match paint {
+                    Paint::None => return None,
+                    Paint::Color(color) => color,
+                    Paint::CurrentColor => current_color,
+                };
+                Some(sdl2::pixels::Color::RGBA(color.r, color.g, color.b, (color.a as f32 * opacity * shape.opacity).round() as u8))
+            };
+            if let Some(color) = paint_color(presentation.fill, presentation.fill_opacity) {
+                if let Some(bounds) = path.bounds().and_then(|bounds| bounds.intersection(region)) {
+                    pixmap.fill_mask(&path.fill(presentation.fill_rule, bounds), color);
+                }
+            }
+            if let Some(color) = paint_color(presentation.stroke, presentation.stroke_opacity) {
+                // strokes are outlined after the transformation, so that curves are flattened finely enough. Their width is scaled by the average scale.
+                let scale = (transform.a * transform.d - transform.b * transform.c).abs().sqrt();
+                let stroke_style = util::StrokeStyle { width: presentation.stroke_style.width * scale, ..presentation.stroke_style };
+                let outline = path.stroke(&stroke_style);
+                if let Some(bounds) = outline.bounds().and_then(|bounds| bounds.intersection(region)) {
+                    pixmap.fill_mask(&outline.fill(util::FillRule::NonZero, bounds), color);
+                }
+            }
+        }
+        pixmap
+    }
+
+    /// Maps viewBox coordinates to a viewport of the given size at the origin.
+    fn view_transform(&self, width: f32, height: f32, device_pixel_ratio: f32) -> util::Transform {
+        let [view_x, view_y, view_width, view_height] = match self.view_box {
+            Some(view_box) => view_box,
+            None => return util::Transform::scale(device_pixel_ratio, device_pixel_ratio),
+        };
+        let (scale_x, scale_y) = (width / view_width, height / view_height);
+        match self.aspect_ratio.align {
+            Some((align_x, align_y)) => {
+                let scale = if self.aspect_ratio.slice { scale_x.max(scale_y) } else { scale_x.min(scale_y) };
+                util::Transform::translate((width - view_width * scale) * align_x - view_x * scale, (height - view_height * scale) * align_y - view_y * scale)
+                    .multiply(&util::Transform::scale(scale, scale))
+            },
+            None => util::Transform::translate(-view_x * scale_x, -view_y * scale_y).multiply(&util::Transform::scale(scale_x, scale_y)),
+        }
+    }
+
+    /// Collects the shapes of all descendants of an element.
+    fn collect_shapes(&mut self, tag: &tl::HTMLTag, parser: &tl::Parser, presentation: &Presentation, transform: util::Transform, opacity: f32) {
+        for child in tag.children().top().iter() {
+            let child = match child.get(parser).and_then(|node| node.as_tag()) {
+                Some(child) => child,
+                None => continue,
+            };
+            let declarations = declarations(child);
+            let declaration = |name: &str| declarations.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str());
+            if declaration("display") == Some("none") {
+                continue;
+            }
+            let presentation = presentation.with_element(child);
+            let transform = transform.multiply(&attribute(child, "transform").and_then(|value| parse_transform(&value)).unwrap_or_default());
+            let opacity = opacity * declaration("opacity").and_then(number).unwrap_or(1.0).clamp(0.0, 1.0);
+            let name = child.name().as_utf8_str().to_ascii_lowercase();
+            match name.as_str() {
+                "g" | "a" | "svg" | "switch" => self.collect_shapes(child, parser, &presentation, transform, opacity),
+                _ => {
+                    if let Some(path) = self.shape_path(name.as_str(), child) {
+                        self.shapes.push(Shape { path, transform, presentation, opacity });
+                    }
+                }
+            }
+        }
+    }
+
+    /// The path of a basic shape or `<path>` element. None for other elements.
+    fn shape_path(&self, name: &str, tag: &tl::HTMLTag) -> Option<util::Path> {
+        let (view_width, view_height) = self.view_box.map_or(self.size(), |[_, _, width, height]| (width, height));
+        let diagonal = ((view_width * view_width + view_height * view_height) / 2.0).sqrt();
+        let length = |name: &str, reference: f32| attribute(tag, name).and_then(|value| match value.trim().strip_suffix('%') {
+            Some(percent) => percent.parse::<f32>().ok().map(|p| p / 100.0 * reference),
+            None => number(&value),
+        });
+        let mut path = util::Path::new();
+        match name {
+            "rect" => {
+                let (x, y) = (length("x", view_width).unwrap_or(0.0), length("y", view_height).unwrap_or(0.0));
+                let (width, height) = (length("width", view_width)?, length("height", view_height)?);
+                if width <= 0.0 || height <= 0.0 {
+                    return None;
+                }
+                let (rx, ry) = (length("rx", view_width), length("ry", view_height));
+                let (rx, ry) = (rx.or(ry).unwrap_or(0.0).min(width / 2.0), ry.or(rx).unwrap_or(0.0).min(height / 2.0));
+                let radius = util::CornerRadius { x: rx, y: ry };
+                path = util::Path::rounded_rect(&util::RoundedRect::new(x, y, width, height, [radius; 4]));
+            },
+            "circle" => {
+                let r = length("r", diagonal)?;
+                if r <= 0.0 {
+                    return None;
+                }
+                path = util::Path::ellipse(length("cx", view_width).unwrap_or(0.0), length("cy", view_height).unwrap_or(0.0), r, r);
+            },
+            "ellipse" => {
+                let (rx, ry) = (length("rx", view_width)?, length("ry", view_height)?);
+                if rx <= 0.0 || ry <= 0.0 {
+                    return None;
+                }
+                path = util::Path::ellipse(length("cx", view_width).unwrap_or(0.0), length("cy", view_height).unwrap_or(0.0), rx, ry);
+            },
+            "line" => {
+                path.move_to(length("x1", view_width).unwrap_or(0.0), length("y1", view_height).unwrap_or(0.0));
+                path.line_to(length("x2", view_width).unwrap_or(0.0), length("y2", view_height).unwrap_or(0.0));
+            },
+            "polyline" | "polygon" => {
+                let points = numbers(&attribute(tag, "points")?);
+                let mut pairs = points.chunks_exact(2);
+                let first = pairs.next()?;
+                path.move_to(first[0], first[1]);
+                for point in pairs {
+                    path.line_to(point[0], point[1]);
+                }
+                if name == "polygon" {
+                    path.close();
+                }
+            },
+            "path" => path = parse_path_data(&attribute(tag, "d")?),
+            _ => return None,
+        }
+        Some(path)
+    }
+}
+
+fn is_svg_element(tag: &tl::HTMLTag) -> bool {
+    tag.name().as_utf8_str().eq_ignore_ascii_case("svg")
+}
+
+/// Value of an attribute. Attribute names of SVG are case-sensitive, but html documents may have been written in lowercase.
+fn attribute(tag: &tl::HTMLTag, name: &str) -> Option<String> {
+    let attributes = tag.attributes();
+    let value = |name: &str| attributes.get(name).flatten().map(|value| value.as_utf8_str().to_string());
+    value(name).or_else(|| value(name.to_ascii_lowercase().as_str()))
+}
+
+/// Presentation attributes of an element, followed by the declarations of its style attribute (which take precedence).
+fn declarations(tag: &tl::HTMLTag) -> Vec<(String, String)> {
+    let names = ["fill", "fill-opacity", "fill-rule", "stroke", "stroke-opacity", "stroke-width", "stroke-miterlimit", "stroke-linecap", "stroke-linejoin", "color", "opacity", "display"];
+    let mut declarations: Vec<(String, String)> = names.iter()
+        .filter_map(|name| attribute(tag, name).map(|value| (name.to_string(), value)))
+        .collect();
+    if let Some(style) = attribute(tag, "style") {
+        declarations.extend(style.split(';').filter_map(|declaration| {
+            let (name, value) = declaration.split_once(':')?;
+            Some((name.trim().to_string(), value.trim().to_string()))
+        }));
+    }
+    declarations
+}
+
+/// Parses a number, optionally with a px unit.
+fn number(value: &str) -> Option<f32> {
+    let value = value.trim();
+    value.strip_suffix("px").unwrap_or(value).trim().parse::<f32>().ok()
+}
+
+/// Parses a list of numbers separated by whitespace and/or commas, as in viewBox and points. Stops at the first invalid number.
+fn numbers(value: &str) -> Vec<f32> {
+    let mut scanner = PathDataScanner::new(value);
+    std::iter::from_fn(|| scanner.number()).collect()
+}
+
+/// Parses the value of an SVG transform attribute (unitless numbers, angles in degrees, rotate() with an optional center).
+///
+/// ```
+/// use yargl::svg::parse_transform;
+/// let transform = parse_transform("translate(10, 5) rotate(90 1 1)").unwrap();
+/// let (x, y) = transform.apply(2.0, 1.0);
+/// assert!((x - 11.0).abs() < 1e-5 && (y - 7.0).abs() < 1e-5);
+/// assert!(parse_transform("rotate(45deg)").is_none());
+/// ```
+pub fn parse_transform(value: &str) -> Option<util::Transform> {
+    let mut transform = util::Transform::IDENTITY;
+    let mut rest = value.trim();
+    while !rest.is_empty() {
+        let (name, after_name) = rest.split_once('(')?;
+        let (arguments, after_arguments) = after_name.split_once(')')?;
+        let arguments = arguments.split(|c: char| c == ',' || c.is_whitespace()).filter(|a| !a.is_empty())
+            .map(|a| a.parse::<f32>().ok()).collect::<Option<Vec<f32>>>()?;
+        let function = match (name.trim(), arguments.as_slice()) {
+            ("matrix", &[a, b, c, d, e, f]) => util::Transform { a, b, c, d, e, f },
+            ("translate", &[x]) => util::Transform::translate(x, 0.0),
+            ("translate", &[x, y]) => util::Transform::translate(x, y),
+            ("scale", &[scale]) => util::Transform::scale(scale, scale),
+            ("scale", &[x, y]) => util::Transform::scale(x, y),
+            ("rotate", &[angle]) => util::Transform::rotate(angle),
+            ("rotate", &[angle, x, y]) => util::Transform::translate(x, y).multiply(&util::Transform::rotate(angle)).multiply(&util::Transform::translate(-x, -y)),
+            ("skewX", &[angle]) => util::Transform::skew(angle, 0.0),
+            ("skewY", &[angle]) => util::Transform::skew(0.0, angle),
+            _ => return None,
+        };
+        transform = transform.multiply(&function);
+        rest = after_arguments.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+    }
+    Some(transform)
+}
+
+/// Reads numbers, flags and commands of path data.
+struct PathDataScanner<'s> {
+    data: &'s [u8],
+    position: usize,
+}
+
+impl<'s> PathDataScanner<'s> {
+    fn new(data: &'s str) -> PathDataScanner<'s> {
+        PathDataScanner { data: data.as_bytes(), position: 0 }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.position < self.data.len() && (self.data[self.position].is_ascii_whitespace() || self.data[self.position] == b',') {
+            self.position += 1;
+        }
+    }
+
+    /// The next command letter, if the next token is a command.
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        let command = *self.data.get(self.position).filter(|c| c.is_ascii_alphabetic() && !matches!(c, b'e' | b'E'))?;
+        self.position += 1;
+        Some(command)
+    }
+
+    fn has_number(&mut self) -> bool {
+        self.skip_separators();
+        self.data.get(self.position).is_some_and(|c| c.is_ascii_digit() || matches!(c, b'-' | b'+' | b'.'))
+    }
+
+    /// Reads a number. Numbers may follow each other without separators, i.e. `1-2` or `.5.5`.
+    fn number(&mut self) -> Option<f32> {
+        if !self.has_number() {
+            return None;
+        }
+        let start = self.position;
+        let mut end = start;
+        if matches!(self.data[end], b'-' | b'+') {
+            end += 1;
+        }
+        let mut seen_point = false;
+        while end < self.data.len() && (self.data[end].is_ascii_digit() || (self.data[end] == b'.' && !seen_point)) {
+            seen_point |= self.data[end] == b'.';
+            end += 1;
+        }
+        if end < self.data.len() && matches!(self.data[end], b'e' | b'E') {
+            let mut exponent_end = end + 1;
+            if exponent_end < self.data.len() && matches!(self.data[exponent_end], b'-' | b'+') {
+                exponent_end += 1;
+            }
+            if exponent_end < self.data.len() && self.data[exponent_end].is_ascii_digit() {
+                end = exponent_end;
+                while end < self.data.len() && self.data[end].is_ascii_digit() {
+                    end += 1;
+                }
+            }
+        }
+        let number = std::str::from_utf8(&self.data[start..end]).ok()?.parse::<f32>().ok()?;
+        self.position = end;
+        Some(number)
+    }
+
+    /// Reads an arc flag, which is a single 0 or 1 that may be followed by a number without separator.
+    fn flag(&mut self) -> Option<bool> {
+        self.skip_separators();
+        let flag = match self.data.get(self.position)? {
+            b'0' => false,
+            b'1' => true,
+            _ => return None,
+        };
+        self.position += 1;
+        Some(flag)
+    }
+}
+
+/// Parses SVG path data into a path. Parsing stops at the first error, the path up to it is kept (as required by SVG).
+///
+/// ```
+/// use yargl::svg::parse_path_data;
+/// use yargl::util::PathSegment;
+/// let path = parse_path_data("M1 2l3-4H0v.5z");
+/// assert_eq!(path.segments(), &[
+///     PathSegment::MoveTo(1.0, 2.0), PathSegment::LineTo(4.0, -2.0), PathSegment::LineTo(0.0, -2.0),
+///     PathSegment::LineTo(0.0, -1.5), PathSegment::Close,
+/// ]);
+/// // an arc is converted to cubic curves that end at the arc's end point
+/// let arc = parse_path_data("M0 0A5 5 0 0 1 10 0");
+/// assert_eq!(arc.segments().len(), 3);
+/// assert!(matches!(arc.segments().last(), Some(PathSegment::CubicTo(.., x, y)) if (x - 10.0).abs() < 1e-4 && y.abs() < 1e-4));
+/// ```
+pub fn parse_path_data(data: &str) -> util::Path {
+    let mut path = util::Path::new();
+    let mut scanner = PathDataScanner::new(data);
+    let (mut x, mut y) = (0.0, 0.0);
+    let (mut start_x, mut start_y) = (0.0, 0.0);
+    // reflected control point of the previous curve, for the smooth curve commands
+    let mut last_cubic_control: Option<(f32, f32)> = None;
+    let mut last_quad_control: Option<(f32, f32)> = None;
+    let mut command = match scanner.command() {
+        Some(command @ (b'M' | b'm')) => command,
+        _ => return path,
+    };
+    loop {
+        let relative = command.is_ascii_lowercase();
+        let (offset_x, offset_y) = if relative { (x, y) } else { (0.0, 0.0) };
+        let mut cubic_control = None;
+        let mut quad_control = None;
+        let parsed = (|| -> Option<()> {
+            match command.to_ascii_uppercase() {
+                b'M' => {
+                    let (nx, ny) = (scanner.number()? + offset_x, scanner.number()? + offset_y);
+                    path.move_to(nx, ny);
+                    (x, y, start_x, start_y) = (nx, ny, nx, ny);
+                    // further coordinate pairs are implicit line commands
+                    command = if relative { b'l' } else { b'L' };
+                },
+                b'L' => {
+                    (x, y) = (scanner.number()? + offset_x, scanner.number()? + offset_y);
+                    path.line_to(x, y);
+                },
+                b'H' => {
+                    x = scanner.number()? + offset_x;
+                    path.line_to(x, y);
+                },
+                b'V' => {
+                    y = scanner.number()? + offset_y;
+                    path.line_to(x, y);
+                },
+                b'C' => {
+                    let (x1, y1) = (scanner.number()? + offset_x, scanner.number()? + offset_y);
+                    let (x2, y2) = (scanner.number()? + offset_x, scanner.number()? + offset_y);
+                    (x, y) = (scanner.number()? + offset_x, scanner.number()? + offset_y);
+                    path.cubic_to(x1, y1, x2, y2, x, y);
+                    cubic_control = Some((x2, y2));
+                },
+                b'S' => {
+                    let (x1, y1) = last_cubic_control.map_or((x, y), |(cx, cy)| (2.0 * x - cx, 2.0 * y - cy));
+                    let (x2, y2) = (scanner.number()? + offset_x, scanner.number()? + offset_y);
+                    (x, y) = (scanner.number()? + offset_x, scanner.number()? + offset_y);
+                    path.cubic_to(x1, y1, x2, y2, x, y);
+                    cubic_control = Some((x2, y2));
+                },
+                b'Q' => {
+                    let (x1, y1) = (scanner.number()? + offset_x, scanner.number()? + offset_y);
+                    (x, y) = (scanner.number()? + offset_x, scanner.number()? + offset_y);
+                    path.quad_to(x1, y1, x, y);
+                    quad_control = Some((x1, y1));
+                },
+                b'T' => {
+                    let (x1, y1) = last_quad_control.map_or((x, y), |(cx, cy)| (2.0 * x - cx, 2.0 * y - cy));
+                    (x, y) = (scanner.number()? + offset_x, scanner.number()? + offset_y);
+                    path.quad_to(x1, y1, x, y);
+                    quad_control = Some((x1, y1));
+                },
+                b'A' => {
+                    let (rx, ry, rotation) = (scanner.number()?, scanner.number()?, scanner.number()?);
+                    let (large_arc, sweep) = (scanner.flag()?, scanner.flag()?);
+                    let (nx, ny) = (scanner.number()? + offset_x, scanner.number()? + offset_y);
+                    arc_to(&mut path, (x, y), (rx, ry), rotation, large_arc, sweep, (nx, ny));
+                    (x, y) = (nx, ny);
+                },
+                b'Z' => {
+                    path.close();
+                    (x, y) = (start_x, start_y);
+                },
+                _ => return None,
+            }
+            Some(())
+        })();
+        if parsed.is_none() {
+            break;
+        }
+        last_cubic_control = cubic_control;
+        last_quad_control = quad_control;
+        // commands repeat while more numbers follow, except for closepath
+        if command.eq_ignore_ascii_case(&b'Z') || !scanner.has_number() {
+            command = match scanner.command() {
+                Some(command) => command,
+                None => break,
+            };
+        }
+    }
+    path
+}
+
+/// Adds an elliptical arc as cubic bezier curves of at most 90 degrees, following the endpoint to center conversion of the SVG specification.
+fn arc_to(path: &mut util::Path, from: (f32, f32), radii: (f32, f32), rotation: f32, large_arc: bool, sweep: bool, to: (f32, f32)) {
+    let ((x1, y1), (x2, y2)) = (from, to);
+    let (mut rx, mut ry) = (radii.0.abs(), radii.1.abs());
+    if rx == 0.0 || ry == 0.0 {
+        path.line_to(x2, y2);
+        return;
+    }
+    if from == to {
+        return;
+    }
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    let (dx, dy) = ((x1 - x2) / 2.0, (y1 - y2) / 2.0);
+    let (x1p, y1p) = (cos * dx + sin * dy, -sin * dx + cos * dy);
+    // radii that are too small are scaled up until the arc reaches the end point
+    let lambda = x1p * x1p / (rx * rx) + y1p * y1p / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+    let numerator = rx * rx * ry * ry - rx * rx * y1p * y1p - ry * ry * x1p * x1p;
+    let denominator = rx * rx * y1p * y1p + ry * ry * x1p * x1p;
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let coefficient = sign * (numerator / denominator).max(0.0).sqrt();
+    let (cxp, cyp) = (coefficient * rx * y1p / ry, -coefficient * ry * x1p / rx);
+    let (cx, cy) = (cos * cxp - sin * cyp + (x1 + x2) / 2.0, sin * cxp + cos * cyp + (y1 + y2) / 2.0);
+    let angle = |(ux, uy): (f32, f32), (vx, vy): (f32, f32)| (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+    let start_angle = angle((1.0, 0.0), ((x1p - cxp) / rx, (y1p - cyp) / ry));
+    let mut sweep_angle = angle(((x1p - cxp) / rx, (y1p - cyp) / ry), ((-x1p - cxp) / rx, (-y1p - cyp) / ry));
+    if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= std::f32::consts::TAU;
+    } else if sweep && sweep_angle < 0.0 {
+        sweep_angle += std::f32::consts::TAU;
+    }
+    let segments = (sweep_angle.abs() / std::f32::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+    let delta = sweep_angle / segments as f32;
+    let handle = 4.0 / 3.0 * (delta / 4.0).tan();
+    let point = |a: f32| (cx + rx * a.cos() * cos - ry * a.sin() * sin, cy + rx * a.cos() * sin + ry * a.sin() * cos);
+    let derivative = |a: f32| (-rx * a.sin() * cos - ry * a.cos() * sin, -rx * a.sin() * sin + ry * a.cos() * cos);
+    for i in 0..segments {
+        let (a1, a2) = (start_angle + delta * i as f32, start_angle + delta * (i + 1) as f32);
+        let ((p1x, p1y), (d1x, d1y)) = (point(a1), derivative(a1));
+        let ((p2x, p2y), (d2x, d2y)) = (point(a2), derivative(a2));
+        // the last segment ends exactly at the end point
+        let (p2x, p2y) = if i + 1 == segments { (x2, y2) } else { (p2x, p2y) };
+        path.cubic_to(p1x + handle * d1x, p1y + handle * d1y, p2x - handle * d2x, p2y - handle * d2y, p2x, p2y);
+    }
+}
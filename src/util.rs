@@ -1,3 +1,5 @@
+use crate::css;
+
 
 pub fn pack_id_color(id: u32) -> sdl2::pixels::Color {
     sdl2::pixels::Color::RGBA(
@@ -25,39 +27,1557 @@ pub struct DrawingSuccess;
 
 pub type DrawingResult = Result<DrawingSuccess, DrawingError>;
 
-/// Fills a quarter of a circle using the midpoint circle algorithm.
-/// 
-/// - x0, y0: Center of the circle
-/// - r: Radius of the circle
-/// - x_direction: 1 or -1, depending on which direction the circle should be drawn in the x axis
-/// - y_direction: 1 or -1, depending on which direction the circle should be drawn in the y axis
-/// - color: Color of the circle
-/// - canvas: Canvas to draw on
-/// 
-/// Returns Ok(DrawingSuccess) if the circle was drawn successfully, Err(DrawingError) otherwise.
-pub fn fill_quarter_circle<Target: sdl2::render::RenderTarget>(x0: i32, y0: i32, r: i32, x_direction: i32, y_direction: i32, color: sdl2::pixels::Color, canvas: &mut sdl2::render::Canvas<Target>) -> DrawingResult {
-    if r <= 0 {
-        return Err(DrawingError {msg: "Radius must be greater than 0".to_string()});
-    }
-    let mut x = r;
-    let mut y = 0;
-    let mut err = 0;
-    canvas.set_draw_color(color);
-    while x >= y {
-        canvas.draw_line((x0, y0), (x0 + x * x_direction, y0 + y * y_direction)).map_err(|msg| DrawingError {msg})?;
-        canvas.draw_line((x0, y0), (x0 + y * x_direction, y0 + x * y_direction)).map_err(|msg| DrawingError {msg})?;
-        canvas.draw_line((x0, y0), (x0 - y * x_direction, y0 + x * y_direction)).map_err(|msg| DrawingError {msg})?;
-        canvas.draw_line((x0, y0), (x0 - x * x_direction, y0 + y * y_direction)).map_err(|msg| DrawingError {msg})?;
-        canvas.draw_line((x0, y0), (x0 - x * x_direction, y0 - y * y_direction)).map_err(|msg| DrawingError {msg})?;
-        canvas.draw_line((x0, y0), (x0 - y * x_direction, y0 - x * y_direction)).map_err(|msg| DrawingError {msg})?;
-        canvas.draw_line((x0, y0), (x0 + y * x_direction, y0 - x * y_direction)).map_err(|msg| DrawingError {msg})?;
-        canvas.draw_line((x0, y0), (x0 + x * x_direction, y0 - y * y_direction)).map_err(|msg| DrawingError {msg})?;
-        y += 1;
-        err += 1 + 2 * y;
-        if 2 * (err - x) + 1 > 0 {
-            x -= 1;
-            err += 1 - 2 * x;
-        }
-    }
-    Ok(DrawingSuccess {})
+/// Horizontal and vertical radius of an elliptical corner.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CornerRadius {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// A rectangle with elliptical corners, i.e. the border box of an element.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoundedRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// Corner radii in the order top left, top right, bottom right, bottom left.
+    pub radii: [CornerRadius; 4],
+}
+
+impl RoundedRect {
+    /// Creates a rounded rectangle. If adjacent radii don't fit into the rectangle, all radii are scaled down by the same factor.
+    pub fn new(x: f32, y: f32, width: f32, height: f32, radii: [CornerRadius; 4]) -> RoundedRect {
+        let [top_left, top_right, bottom_right, bottom_left] = radii;
+        let mut factor: f32 = 1.0;
+        for (sum, length) in [
+            (top_left.x + top_right.x, width), (bottom_left.x + bottom_right.x, width),
+            (top_left.y + bottom_left.y, height), (top_right.y + bottom_right.y, height)
+        ] {
+            if sum > length {
+                factor = factor.min(length / sum);
+            }
+        }
+        let radii = radii.map(|r| CornerRadius { x: r.x * factor, y: r.y * factor });
+        RoundedRect { x, y, width, height, radii }
+    }
+
+    /// Shrinks the rectangle by the given widths (top, right, bottom, left). The corner radii shrink by the adjacent widths,
+    /// so that the inner edge of a border is concentric to its outer edge.
+    pub fn inset(&self, widths: [f32; 4]) -> RoundedRect {
+        let [top, right, bottom, left] = widths;
+        let [top_left, top_right, bottom_right, bottom_left] = self.radii;
+        let shrink = |r: CornerRadius, x: f32, y: f32| CornerRadius { x: (r.x - x).max(0.0), y: (r.y - y).max(0.0) };
+        RoundedRect {
+            x: self.x + left,
+            y: self.y + top,
+            width: (self.width - left - right).max(0.0),
+            height: (self.height - top - bottom).max(0.0),
+            radii: [shrink(top_left, left, top), shrink(top_right, right, top), shrink(bottom_right, right, bottom), shrink(bottom_left, left, bottom)],
+        }
+    }
+
+    /// Moves the rectangle and grows it by the spread on every side (shrinks it for a negative spread), like the shape of a box shadow.
+    /// Rounded corners grow and shrink with the spread, sharp corners stay sharp.
+    pub fn shadow_shape(&self, offset_x: f32, offset_y: f32, spread: f32) -> RoundedRect {
+        let spread_radius = |r: CornerRadius| if r.x > 0.0 && r.y > 0.0 {
+            CornerRadius { x: (r.x + spread).max(0.0), y: (r.y + spread).max(0.0) }
+        } else {
+            r
+        };
+        RoundedRect::new(
+            self.x + offset_x - spread,
+            self.y + offset_y - spread,
+            (self.width + spread * 2.0).max(0.0),
+            (self.height + spread * 2.0).max(0.0),
+            self.radii.map(spread_radius),
+        )
+    }
+}
+
+/// Approximate length in pixels of the lines that curves are flattened to.
+const FLATTEN_SEGMENT_LENGTH: f32 = 2.0;
+/// Number of sub-scanlines per pixel row. The horizontal coverage of each sub-scanline is computed exactly.
+const SAMPLES_PER_PIXEL: usize = 4;
+/// Cubic bezier control point distance for approximating a quarter ellipse.
+const KAPPA: f32 = 0.552_284_8;
+
+/// A segment of a path. Coordinates are in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathSegment {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    /// Quadratic bezier curve: control point, end point.
+    QuadTo(f32, f32, f32, f32),
+    /// Cubic bezier curve: first control point, second control point, end point.
+    CubicTo(f32, f32, f32, f32, f32, f32),
+    Close,
+}
+
+/// Which areas enclosed by a path are filled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FillRule {
+    NonZero,
+    EvenOdd,
+}
+
+/// Shape of the corners between stroked lines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// Shape of the ends of open stroked lines.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    /// Maximum ratio of the miter length to the stroke width. Sharper miter joins are beveled.
+    pub miter_limit: f32,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        StrokeStyle {
+            width: 1.0,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+        }
+    }
+}
+
+/// A vector path of lines and bezier curves, which is filled with anti-aliasing. Strokes are converted to paths and filled as well.
+///
+/// ```
+/// use yargl::util::{Path, FillRule, StrokeStyle};
+/// let mut path = Path::new();
+/// path.move_to(1.0, 1.0);
+/// path.line_to(7.0, 1.0);
+/// path.line_to(7.0, 3.5);
+/// path.line_to(1.0, 3.5);
+/// path.close();
+/// let mask = path.fill(FillRule::NonZero, sdl2::rect::Rect::new(0, 0, 8, 4));
+/// assert_eq!(mask.coverage(2, 1), 1.0);
+/// assert_eq!(mask.coverage(2, 3), 0.5);
+/// assert_eq!(mask.coverage(0, 0), 0.0);
+///
+/// let mut line = Path::new();
+/// line.move_to(0.0, 2.0);
+/// line.line_to(8.0, 2.0);
+/// let stroke = line.stroke(&StrokeStyle { width: 2.0, ..StrokeStyle::default() });
+/// let mask = stroke.fill(FillRule::NonZero, sdl2::rect::Rect::new(0, 0, 8, 4));
+/// assert_eq!(mask.coverage(4, 1), 1.0);
+/// assert_eq!(mask.coverage(4, 0), 0.0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+impl Path {
+    pub fn new() -> Path {
+        Path { segments: Vec::new() }
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    pub fn move_to(&mut self, x: f32, y: f32) {
+        self.segments.push(PathSegment::MoveTo(x, y));
+    }
+
+    pub fn line_to(&mut self, x: f32, y: f32) {
+        self.segments.push(PathSegment::LineTo(x, y));
+    }
+
+    pub fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.segments.push(PathSegment::QuadTo(x1, y1, x, y));
+    }
+
+    pub fn cubic_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.segments.push(PathSegment::CubicTo(x1, y1, x2, y2, x, y));
+    }
+
+    pub fn close(&mut self) {
+        self.segments.push(PathSegment::Close);
+    }
+
+    /// Adds all subpaths of the other path to this path.
+    pub fn append(&mut self, other: &Path) {
+        self.segments.extend_from_slice(&other.segments);
+    }
+
+    /// A copy of the path that is moved by the given offset.
+    pub fn translate(&self, dx: f32, dy: f32) -> Path {
+        let segments = self.segments.iter().map(|segment| match *segment {
+            PathSegment::MoveTo(x, y) => PathSegment::MoveTo(x + dx, y + dy),
+            PathSegment::LineTo(x, y) => PathSegment::LineTo(x + dx, y + dy),
+            PathSegment::QuadTo(x1, y1, x, y) => PathSegment::QuadTo(x1 + dx, y1 + dy, x + dx, y + dy),
+            PathSegment::CubicTo(x1, y1, x2, y2, x, y) => PathSegment::CubicTo(x1 + dx, y1 + dy, x2 + dx, y2 + dy, x + dx, y + dy),
+            PathSegment::Close => PathSegment::Close,
+        }).collect();
+        Path { segments }
+    }
+
+    /// A copy of the path with all points mapped through the transformation.
+    pub fn transform(&self, transform: &Transform) -> Path {
+        let point = |x: f32, y: f32| transform.apply(x, y);
+        let segments = self.segments.iter().map(|segment| match *segment {
+            PathSegment::MoveTo(x, y) => {
+                let (x, y) = point(x, y);
+                PathSegment::MoveTo(x, y)
+            },
+            PathSegment::LineTo(x, y) => {
+                let (x, y) = point(x, y);
+                PathSegment::LineTo(x, y)
+            },
+            PathSegment::QuadTo(x1, y1, x, y) => {
+                let ((x1, y1), (x, y)) = (point(x1, y1), point(x, y));
+                PathSegment::QuadTo(x1, y1, x, y)
+            },
+            PathSegment::CubicTo(x1, y1, x2, y2, x, y) => {
+                let ((x1, y1), (x2, y2), (x, y)) = (point(x1, y1), point(x2, y2), point(x, y));
+                PathSegment::CubicTo(x1, y1, x2, y2, x, y)
+            },
+            PathSegment::Close => PathSegment::Close,
+        }).collect();
+        Path { segments }
+    }
+
+    /// An ellipse around the given center.
+    pub fn ellipse(cx: f32, cy: f32, rx: f32, ry: f32) -> Path {
+        let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+        let mut path = Path::new();
+        path.move_to(cx + rx, cy);
+        path.cubic_to(cx + rx, cy + ky, cx + kx, cy + ry, cx, cy + ry);
+        path.cubic_to(cx - kx, cy + ry, cx - rx, cy + ky, cx - rx, cy);
+        path.cubic_to(cx - rx, cy - ky, cx - kx, cy - ry, cx, cy - ry);
+        path.cubic_to(cx + kx, cy - ry, cx + rx, cy - ky, cx + rx, cy);
+        path.close();
+        path
+    }
+
+    /// The outline of a rectangle with elliptical corners.
+    pub fn rounded_rect(rect: &RoundedRect) -> Path {
+        let (left, top, right, bottom) = (rect.x, rect.y, rect.x + rect.width, rect.y + rect.height);
+        let [top_left, top_right, bottom_right, bottom_left] = rect.radii;
+        let mut path = Path::new();
+        path.move_to(left + top_left.x, top);
+        path.line_to(right - top_right.x, top);
+        path.cubic_to(right - top_right.x * (1.0 - KAPPA), top, right, top + top_right.y * (1.0 - KAPPA), right, top + top_right.y);
+        path.line_to(right, bottom - bottom_right.y);
+        path.cubic_to(right, bottom - bottom_right.y * (1.0 - KAPPA), right - bottom_right.x * (1.0 - KAPPA), bottom, right - bottom_right.x, bottom);
+        path.line_to(left + bottom_left.x, bottom);
+        path.cubic_to(left + bottom_left.x * (1.0 - KAPPA), bottom, left, bottom - bottom_left.y * (1.0 - KAPPA), left, bottom - bottom_left.y);
+        path.line_to(left, top + top_left.y);
+        path.cubic_to(left, top + top_left.y * (1.0 - KAPPA), left + top_left.x * (1.0 - KAPPA), top, left + top_left.x, top);
+        path.close();
+        path
+    }
+
+    /// Adds a closed polygon. Polygons are always added with the same orientation, so that overlapping polygons are joined when filled with the non-zero rule.
+    fn add_polygon(&mut self, points: &[(f32, f32)]) {
+        if points.len() < 3 {
+            return;
+        }
+        let area: f32 = (0..points.len()).map(|i| {
+            let ((x0, y0), (x1, y1)) = (points[i], points[(i + 1) % points.len()]);
+            x0 * y1 - x1 * y0
+        }).sum();
+        let mut ordered = points.to_vec();
+        if area < 0.0 {
+            ordered.reverse();
+        }
+        self.move_to(ordered[0].0, ordered[0].1);
+        for (x, y) in ordered.iter().skip(1) {
+            self.line_to(*x, *y);
+        }
+        self.close();
+    }
+
+    /// Converts all curves to lines. Returns the points of each subpath, and whether the subpath is closed.
+    pub fn flatten(&self) -> Vec<(Vec<(f32, f32)>, bool)> {
+        let mut subpaths = Vec::new();
+        let mut points: Vec<(f32, f32)> = Vec::new();
+        let mut current = (0.0, 0.0);
+        let segment_count = |length: f32| (length / FLATTEN_SEGMENT_LENGTH).ceil().clamp(1.0, 256.0) as usize;
+        for segment in self.segments.iter() {
+            match *segment {
+                PathSegment::MoveTo(x, y) => {
+                    if points.len() > 1 {
+                        subpaths.push((std::mem::take(&mut points), false));
+                    }
+                    points.clear();
+                    points.push((x, y));
+                },
+                PathSegment::LineTo(x, y) => {
+                    if points.is_empty() {
+                        points.push(current);
+                    }
+                    points.push((x, y));
+                },
+                PathSegment::QuadTo(x1, y1, x, y) => {
+                    if points.is_empty() {
+                        points.push(current);
+                    }
+                    let (x0, y0) = current;
+                    let n = segment_count(distance((x0, y0), (x1, y1)) + distance((x1, y1), (x, y)));
+                    for i in 1..=n {
+                        let t = i as f32 / n as f32;
+                        let mt = 1.0 - t;
+                        points.push((mt * mt * x0 + 2.0 * mt * t * x1 + t * t * x, mt * mt * y0 + 2.0 * mt * t * y1 + t * t * y));
+                    }
+                },
+                PathSegment::CubicTo(x1, y1, x2, y2, x, y) => {
+                    if points.is_empty() {
+                        points.push(current);
+                    }
+                    let (x0, y0) = current;
+                    let n = segment_count(distance((x0, y0), (x1, y1)) + distance((x1, y1), (x2, y2)) + distance((x2, y2), (x, y)));
+                    for i in 1..=n {
+                        let t = i as f32 / n as f32;
+                        let mt = 1.0 - t;
+                        points.push((
+                            mt * mt * mt * x0 + 3.0 * mt * mt * t * x1 + 3.0 * mt * t * t * x2 + t * t * t * x,
+                            mt * mt * mt * y0 + 3.0 * mt * mt * t * y1 + 3.0 * mt * t * t * y2 + t * t * t * y,
+                        ));
+                    }
+                },
+                PathSegment::Close => {
+                    if let Some(start) = points.first().copied() {
+                        if points.len() > 1 {
+                            subpaths.push((std::mem::take(&mut points), true));
+                        }
+                        points.clear();
+                        current = start;
+                    }
+                    continue;
+                },
+            }
+            current = *points.last().unwrap_or(&current);
+        }
+        if points.len() > 1 {
+            subpaths.push((points, false));
+        }
+        subpaths
+    }
+
+    /// Bounding box (x, y, width, height) of all points of the path, including control points. None if the path is empty.
+    pub fn bounds(&self) -> Option<sdl2::rect::Rect> {
+        let mut points = Vec::new();
+        for segment in self.segments.iter() {
+            match *segment {
+                PathSegment::MoveTo(x, y) | PathSegment::LineTo(x, y) => points.push((x, y)),
+                PathSegment::QuadTo(x1, y1, x, y) => points.extend([(x1, y1), (x, y)]),
+                PathSegment::CubicTo(x1, y1, x2, y2, x, y) => points.extend([(x1, y1), (x2, y2), (x, y)]),
+                PathSegment::Close => {},
+            }
+        }
+        let (first_x, first_y) = *points.first()?;
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (first_x, first_y, first_x, first_y);
+        for (x, y) in points {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        let (left, top) = (min_x.floor() as i32, min_y.floor() as i32);
+        Some(sdl2::rect::Rect::new(left, top, (max_x.ceil() as i32 - left).max(1) as u32, (max_y.ceil() as i32 - top).max(1) as u32))
+    }
+
+    /// Rasterizes the path into a coverage mask of the given region. Open subpaths are closed implicitly.
+    pub fn fill(&self, fill_rule: FillRule, region: sdl2::rect::Rect) -> Mask {
+        let mut mask = Mask::new(region);
+        // edges from top to bottom, with +1 for downwards and -1 for upwards edges
+        let mut edges: Vec<(f32, f32, f32, f32, i32)> = Vec::new();
+        for (points, _) in self.flatten() {
+            for i in 0..points.len() {
+                let ((x0, y0), (x1, y1)) = (points[i], points[(i + 1) % points.len()]);
+                if y0 < y1 {
+                    edges.push((x0, y0, x1, y1, 1));
+                } else if y1 < y0 {
+                    edges.push((x1, y1, x0, y0, -1));
+                }
+            }
+        }
+        edges.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let width = region.width() as usize;
+        let weight = 1.0 / SAMPLES_PER_PIXEL as f32;
+        let mut crossings: Vec<(f32, i32)> = Vec::new();
+        for row in 0..region.height() as usize {
+            let row_coverage = &mut mask.coverage[row * width..(row + 1) * width];
+            for sample in 0..SAMPLES_PER_PIXEL {
+                let sample_y = region.y() as f32 + row as f32 + (sample as f32 + 0.5) * weight;
+                crossings.clear();
+                for (x0, y0, x1, y1, direction) in edges.iter() {
+                    if *y0 > sample_y {
+                        break;
+                    }
+                    if sample_y < *y1 {
+                        crossings.push((x0 + (sample_y - y0) * (x1 - x0) / (y1 - y0) - region.x() as f32, *direction));
+                    }
+                }
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+                let mut winding = 0;
+                for i in 0..crossings.len().saturating_sub(1) {
+                    winding += crossings[i].1;
+                    let inside = match fill_rule {
+                        FillRule::NonZero => winding != 0,
+                        FillRule::EvenOdd => winding % 2 != 0,
+                    };
+                    if inside {
+                        add_span(row_coverage, crossings[i].0, crossings[i + 1].0, weight);
+                    }
+                }
+            }
+        }
+        for coverage in mask.coverage.iter_mut() {
+            *coverage = coverage.min(1.0);
+        }
+        mask
+    }
+
+    /// Converts the stroke of this path into a path that can be filled with the non-zero rule.
+    pub fn stroke(&self, style: &StrokeStyle) -> Path {
+        let mut outline = Path::new();
+        let half_width = style.width / 2.0;
+        if half_width <= 0.0 {
+            return outline;
+        }
+        let circle = |(x, y): (f32, f32)| -> Vec<(f32, f32)> {
+            let n = (std::f32::consts::TAU * half_width / FLATTEN_SEGMENT_LENGTH).ceil().clamp(8.0, 128.0) as usize;
+            (0..n).map(|i| {
+                let angle = std::f32::consts::TAU * i as f32 / n as f32;
+                (x + angle.cos() * half_width, y + angle.sin() * half_width)
+            }).collect()
+        };
+        for (mut points, closed) in self.flatten() {
+            points.dedup();
+            if closed && points.len() > 1 && points.first() == points.last() {
+                points.pop();
+            }
+            if points.len() < 2 {
+                continue;
+            }
+            let segment_count = if closed { points.len() } else { points.len() - 1 };
+            let segment = |i: usize| (points[i % points.len()], points[(i + 1) % points.len()]);
+            // unit direction and normal (scaled to half the width) of each segment
+            let directions: Vec<((f32, f32), (f32, f32))> = (0..segment_count).map(|i| {
+                let ((x0, y0), (x1, y1)) = segment(i);
+                let length = distance((x0, y0), (x1, y1));
+                let direction = ((x1 - x0) / length, (y1 - y0) / length);
+                (direction, (-direction.1 * half_width, direction.0 * half_width))
+            }).collect();
+            for (i, (_, (nx, ny))) in directions.iter().enumerate() {
+                let ((x0, y0), (x1, y1)) = segment(i);
+                outline.add_polygon(&[(x0 + nx, y0 + ny), (x1 + nx, y1 + ny), (x1 - nx, y1 - ny), (x0 - nx, y0 - ny)]);
+            }
+            // joins at each point where two segments meet
+            let joins = if closed { 0..segment_count } else { 1..segment_count };
+            for i in joins {
+                let (d0, n0) = directions[(i + segment_count - 1) % segment_count];
+                let (d1, n1) = directions[i];
+                let (x, y) = points[i];
+                let turn = d0.0 * d1.1 - d0.1 * d1.0;
+                if turn.abs() < 1e-6 && d0.0 * d1.0 + d0.1 * d1.1 > 0.0 {
+                    continue;
+                }
+                // the join is on the outer side of the turn
+                let side = if turn > 0.0 { -1.0 } else { 1.0 };
+                let outer0 = (x + n0.0 * side, y + n0.1 * side);
+                let outer1 = (x + n1.0 * side, y + n1.1 * side);
+                match style.join {
+                    LineJoin::Round => outline.add_polygon(&circle((x, y))),
+                    LineJoin::Bevel => outline.add_polygon(&[(x, y), outer0, outer1]),
+                    LineJoin::Miter => {
+                        let (mx, my) = (n0.0 + n1.0, n0.1 + n1.1);
+                        let miter_length = (mx * mx + my * my).sqrt();
+                        // cosine of half the angle between the segments
+                        let cos_half_angle = miter_length / (2.0 * half_width);
+                        if cos_half_angle > f32::EPSILON && 1.0 / cos_half_angle <= style.miter_limit {
+                            let tip_distance = half_width / cos_half_angle;
+                            let tip = (x + mx / miter_length * tip_distance * side, y + my / miter_length * tip_distance * side);
+                            outline.add_polygon(&[(x, y), outer0, tip, outer1]);
+                        } else {
+                            outline.add_polygon(&[(x, y), outer0, outer1]);
+                        }
+                    },
+                }
+            }
+            if !closed {
+                let ends = [(points[0], directions[0], -1.0), (points[points.len() - 1], directions[segment_count - 1], 1.0)];
+                for ((x, y), ((dx, dy), (nx, ny)), outwards) in ends {
+                    match style.cap {
+                        LineCap::Butt => {},
+                        LineCap::Round => outline.add_polygon(&circle((x, y))),
+                        LineCap::Square => {
+                            let (ex, ey) = (dx * half_width * outwards, dy * half_width * outwards);
+                            outline.add_polygon(&[(x + nx, y + ny), (x + nx + ex, y + ny + ey), (x - nx + ex, y - ny + ey), (x - nx, y - ny)]);
+                        },
+                    }
+                }
+            }
+        }
+        outline
+    }
+}
+
+fn distance((x0, y0): (f32, f32), (x1, y1): (f32, f32)) -> f32 {
+    ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt()
+}
+
+/// Adds the coverage of a horizontal span of one sub-scanline to a row of pixels. Partially covered pixels at the ends get a fraction of the weight.
+fn add_span(row_coverage: &mut [f32], start: f32, end: f32, weight: f32) {
+    let width = row_coverage.len() as f32;
+    let (start, end) = (start.clamp(0.0, width), end.clamp(0.0, width));
+    if end <= start {
+        return;
+    }
+    let (first, last) = (start.floor() as usize, end.floor() as usize);
+    if first == last {
+        row_coverage[first] += (end - start) * weight;
+        return;
+    }
+    row_coverage[first] += (first as f32 + 1.0 - start) * weight;
+    for coverage in row_coverage[first + 1..last].iter_mut() {
+        *coverage += weight;
+    }
+    if last < row_coverage.len() {
+        row_coverage[last] += (end - last as f32) * weight;
+    }
+}
+
+/// Number of values in a buffer with the given number of values per pixel. None if it does not fit in memory.
+fn buffer_len(width: u32, height: u32, values_per_pixel: usize) -> Option<usize> {
+    (width as usize).checked_mul(height as usize)?.checked_mul(values_per_pixel)
+}
+
+/// Coverage (0 to 1) of each pixel in a region of a canvas, as produced by filling a path.
+#[derive(Debug, Clone)]
+pub struct Mask {
+    pub region: sdl2::rect::Rect,
+    coverage: Vec<f32>,
+}
+
+impl Mask {
+    /// Creates an empty mask for the given region.
+    pub fn new(region: sdl2::rect::Rect) -> Mask {
+        Mask {
+            region,
+            coverage: vec![0.0; buffer_len(region.width(), region.height(), 1).expect("mask size overflows usize")],
+        }
+    }
+
+    /// Coverage of the pixel at the given canvas position. 0 outside of the region.
+    pub fn coverage(&self, px: i32, py: i32) -> f32 {
+        if !self.region.contains_point((px, py)) {
+            return 0.0;
+        }
+        self.coverage[((py - self.region.y()) as u32 * self.region.width() + (px - self.region.x()) as u32) as usize]
+    }
+
+    /// Blurs the coverage with an approximated gaussian blur whose standard deviation is half the radius, as for css shadows.
+    /// Coverage outside of the region is treated as 0, so the region should be large enough for the blurred shape.
+    pub fn blur(&mut self, radius: f32) {
+        blur_plane(&mut self.coverage, self.region.width() as usize, self.region.height() as usize, radius);
+    }
+
+    /// Canvas positions of all pixels that are at least half covered. Used to draw shapes onto the id canvas without anti-aliasing.
+    pub fn covered_points(&self) -> Vec<sdl2::rect::Point> {
+        let region = self.region;
+        (region.top()..region.bottom())
+            .flat_map(|py| (region.left()..region.right()).map(move |px| (px, py)))
+            .filter(|(px, py)| self.coverage(*px, *py) >= 0.5)
+            .map(|(px, py)| sdl2::rect::Point::new(px, py))
+            .collect()
+    }
+}
+
+/// Blurs a plane of values with an approximated gaussian blur whose standard deviation is half the radius. Values outside of the plane are 0.
+fn blur_plane(values: &mut [f32], width: usize, height: usize, radius: f32) {
+    if radius <= 0.0 {
+        return;
+    }
+    // three box blurs with this size have the variance of the gaussian
+    let sigma = radius / 2.0;
+    let box_radius = (((4.0 * sigma * sigma + 1.0).sqrt() - 1.0) / 2.0).round().max(1.0) as usize;
+    for _ in 0..3 {
+        for y in 0..height {
+            box_blur_line(values, y * width, 1, width, box_radius);
+        }
+        for x in 0..width {
+            box_blur_line(values, x, width, height, box_radius);
+        }
+    }
+}
+
+/// Averages each value of a line with its neighbours within the radius. The line starts at start and has length values that are stride apart.
+fn box_blur_line(values: &mut [f32], start: usize, stride: usize, length: usize, radius: usize) {
+    let line: Vec<f32> = (0..length).map(|i| values[start + i * stride]).collect();
+    let value = |i: isize| if i >= 0 && (i as usize) < length { line[i as usize] } else { 0.0 };
+    let size = (radius * 2 + 1) as f32;
+    let mut sum: f32 = (-(radius as isize)..=radius as isize).map(value).sum();
+    for i in 0..length {
+        values[start + i * stride] = sum / size;
+        sum += value((i + radius + 1) as isize) - value(i as isize - radius as isize);
+    }
+}
+
+#[cfg(target_endian = "little")]
+const PIXMAP_FORMAT: sdl2::pixels::PixelFormatEnum = sdl2::pixels::PixelFormatEnum::ABGR8888;
+#[cfg(target_endian = "big")]
+const PIXMAP_FORMAT: sdl2::pixels::PixelFormatEnum = sdl2::pixels::PixelFormatEnum::RGBA8888;
+
+/// A buffer of RGBA pixels that covers a region of a canvas. Anti-aliased shapes are composited into a pixmap, which is then drawn onto the canvas as a texture.
+#[derive(Clone)]
+pub struct Pixmap {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    data: Vec<u8>,
+}
+
+impl Pixmap {
+    /// Creates a transparent pixmap that covers the given region.
+    pub fn new(region: sdl2::rect::Rect) -> Pixmap {
+        Pixmap {
+            x: region.x(),
+            y: region.y(),
+            width: region.width(),
+            height: region.height(),
+            data: vec![0; buffer_len(region.width(), region.height(), 4).expect("pixmap size overflows usize")],
+        }
+    }
+
+    /// Creates a pixmap at the origin from straight-alpha RGBA bytes. Returns None if the data does not match the size.
+    pub fn from_rgba(width: u32, height: u32, data: Vec<u8>) -> Option<Pixmap> {
+        if buffer_len(width, height, 4) != Some(data.len()) {
+            return None;
+        }
+        Some(Pixmap { x: 0, y: 0, width, height, data })
+    }
+
+    /// Creates a pixmap from the premultiplied RGBA bytes of a render target, with all alpha values multiplied by the opacity.
+    /// Returns None if the data does not match the region.
+    pub fn from_premultiplied(region: sdl2::rect::Rect, mut data: Vec<u8>, opacity: f32) -> Option<Pixmap> {
+        if buffer_len(region.width(), region.height(), 4) != Some(data.len()) {
+            return None;
+        }
+        for pixel in data.chunks_exact_mut(4) {
+            let alpha = pixel[3] as f32;
+            if alpha > 0.0 {
+                for channel in pixel[..3].iter_mut() {
+                    *channel = (*channel as f32 * 255.0 / alpha).round().min(255.0) as u8;
+                }
+            }
+            pixel[3] = (alpha * opacity.clamp(0.0, 1.0)).round() as u8;
+        }
+        Some(Pixmap { x: region.x(), y: region.y(), width: region.width(), height: region.height(), data })
+    }
+
+    /// Returns the pixmap mapped through the transformation, with bilinear filtering. Only the visible part of the result is kept.
+    pub fn transformed(&self, transform: &Transform, visible: sdl2::rect::Rect) -> Option<Pixmap> {
+        let inverse = transform.invert()?;
+        let region = transform.bounds(sdl2::rect::Rect::new(self.x, self.y, self.width, self.height), visible)?;
+        let mut pixmap = Pixmap::new(region);
+        // samples are interpolated with premultiplied alpha, so that transparent pixels do not darken the edges
+        let sample = |x: i32, y: i32| -> [f32; 4] {
+            let (x, y) = (x - self.x, y - self.y);
+            if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+                return [0.0; 4];
+            }
+            let i = ((y as u32 * self.width + x as u32) * 4) as usize;
+            let alpha = self.data[i + 3] as f32 / 255.0;
+            [self.data[i] as f32 * alpha, self.data[i + 1] as f32 * alpha, self.data[i + 2] as f32 * alpha, alpha]
+        };
+        for py in region.top()..region.bottom() {
+            for px in region.left()..region.right() {
+                let (x, y) = inverse.apply(px as f32 + 0.5, py as f32 + 0.5);
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor() as i32, y.floor() as i32);
+                let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+                let mut color = [0.0; 4];
+                for (sx, sy, weight) in [(x0, y0, (1.0 - fx) * (1.0 - fy)), (x0 + 1, y0, fx * (1.0 - fy)), (x0, y0 + 1, (1.0 - fx) * fy), (x0 + 1, y0 + 1, fx * fy)] {
+                    if weight > 0.0 {
+                        for (channel, value) in sample(sx, sy).into_iter().enumerate() {
+                            color[channel] += value * weight;
+                        }
+                    }
+                }
+                if color[3] > 0.0 {
+                    let i = (((py - region.y()) as u32 * region.width() + (px - region.x()) as u32) * 4) as usize;
+                    for channel in 0..3 {
+                        pixmap.data[i + channel] = (color[channel] / color[3]).round().min(255.0) as u8;
+                    }
+                    pixmap.data[i + 3] = (color[3] * 255.0).round().min(255.0) as u8;
+                }
+            }
+        }
+        Some(pixmap)
+    }
+
+    /// Applies a filter effect to all pixels of the pixmap. Blurs and shadows that reach past the pixmap's region are cut off.
+    pub fn filter(&mut self, filter: &Filter) {
+        match *filter {
+            Filter::Blur(radius) => self.blur(radius),
+            Filter::DropShadow(shadow) => self.drop_shadow(&shadow),
+            Filter::Opacity(amount) => {
+                for pixel in self.data.chunks_exact_mut(4) {
+                    pixel[3] = (pixel[3] as f32 * amount.clamp(0.0, 1.0)).round() as u8;
+                }
+            },
+            _ => {
+                for pixel in self.data.chunks_exact_mut(4) {
+                    let color = filter.apply_color([pixel[0] as f32 / 255.0, pixel[1] as f32 / 255.0, pixel[2] as f32 / 255.0]);
+                    for (channel, value) in color.into_iter().enumerate() {
+                        pixel[channel] = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+                    }
+                }
+            },
+        }
+    }
+
+    fn blur(&mut self, radius: f32) {
+        let (width, height) = (self.width as usize, self.height as usize);
+        // the channels are blurred with premultiplied alpha, so that transparent pixels do not darken the result
+        let mut planes: [Vec<f32>; 4] = std::array::from_fn(|channel| self.data.chunks_exact(4)
+            .map(|pixel| if channel == 3 { pixel[3] as f32 } else { pixel[channel] as f32 * pixel[3] as f32 / 255.0 })
+            .collect());
+        for plane in planes.iter_mut() {
+            blur_plane(plane, width, height, radius);
+        }
+        for (i, pixel) in self.data.chunks_exact_mut(4).enumerate() {
+            let alpha = planes[3][i];
+            for channel in 0..3 {
+                pixel[channel] = if alpha > 0.0 { (planes[channel][i] * 255.0 / alpha).round().clamp(0.0, 255.0) as u8 } else { 0 };
+            }
+            pixel[3] = alpha.round().clamp(0.0, 255.0) as u8;
+        }
+    }
+
+    fn drop_shadow(&mut self, shadow: &Shadow) {
+        let (width, height) = (self.width as i32, self.height as i32);
+        let mut shadow_alpha: Vec<f32> = self.data.chunks_exact(4).map(|pixel| pixel[3] as f32 / 255.0).collect();
+        blur_plane(&mut shadow_alpha, width as usize, height as usize, shadow.blur);
+        let (offset_x, offset_y) = (shadow.offset_x.round() as i32, shadow.offset_y.round() as i32);
+        let original = std::mem::take(&mut self.data);
+        self.data = vec![0; original.len()];
+        for y in 0..height {
+            for x in 0..width {
+                let (source_x, source_y) = (x - offset_x, y - offset_y);
+                if source_x >= 0 && source_y >= 0 && source_x < width && source_y < height {
+                    let coverage = shadow_alpha[(source_y * width + source_x) as usize];
+                    self.blend_pixel(self.x + x, self.y + y, shadow.color, coverage);
+                }
+                let i = ((y * width + x) * 4) as usize;
+                let color = sdl2::pixels::Color::RGB(original[i], original[i + 1], original[i + 2]);
+                self.blend_pixel(self.x + x, self.y + y, color, original[i + 3] as f32 / 255.0);
+            }
+        }
+    }
+
+    /// Multiplies the alpha of all pixels with the mask's coverage, pixels outside of the mask become transparent.
+    pub fn clip(&mut self, mask: &Mask) {
+        for (i, pixel) in self.data.chunks_exact_mut(4).enumerate() {
+            let (px, py) = (self.x + (i as u32 % self.width) as i32, self.y + (i as u32 / self.width) as i32);
+            pixel[3] = (pixel[3] as f32 * mask.coverage(px, py)).round() as u8;
+        }
+    }
+
+    /// Composites the color over all pixels of the pixmap, weighted by the mask's coverage.
+    pub fn fill_mask(&mut self, mask: &Mask, color: sdl2::pixels::Color) {
+        let region = mask.region;
+        for py in region.top()..region.bottom() {
+            for px in region.left()..region.right() {
+                self.blend_pixel(px, py, color, mask.coverage(px, py));
+            }
+        }
+    }
+
+    /// Color of the pixel at the given canvas position. Transparent outside of the pixmap.
+    pub fn pixel(&self, px: i32, py: i32) -> sdl2::pixels::Color {
+        let (x, y) = (px - self.x, py - self.y);
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return sdl2::pixels::Color::RGBA(0, 0, 0, 0);
+        }
+        let i = ((y as u32 * self.width + x as u32) * 4) as usize;
+        sdl2::pixels::Color::RGBA(self.data[i], self.data[i + 1], self.data[i + 2], self.data[i + 3])
+    }
+
+    /// Composites the pixmap over the part of the target pixmap that it overlaps.
+    pub fn draw_onto(&self, target: &mut Pixmap) {
+        let overlap = sdl2::rect::Rect::new(self.x, self.y, self.width, self.height)
+            .intersection(sdl2::rect::Rect::new(target.x, target.y, target.width, target.height));
+        if let Some(overlap) = overlap {
+            for py in overlap.top()..overlap.bottom() {
+                for px in overlap.left()..overlap.right() {
+                    target.blend_pixel(px, py, self.pixel(px, py), 1.0);
+                }
+            }
+        }
+    }
+
+    /// Composites the color over the pixel at the given canvas position. The color's alpha is multiplied by the coverage (0 to 1).
+    pub fn blend_pixel(&mut self, px: i32, py: i32, color: sdl2::pixels::Color, coverage: f32) {
+        let (x, y) = (px - self.x, py - self.y);
+        if coverage <= 0.0 || x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return;
+        }
+        let i = ((y as u32 * self.width + x as u32) * 4) as usize;
+        let source_alpha = color.a as f32 / 255.0 * coverage.min(1.0);
+        let destination_alpha = self.data[i + 3] as f32 / 255.0;
+        let alpha = source_alpha + destination_alpha * (1.0 - source_alpha);
+        if alpha <= 0.0 {
+            return;
+        }
+        for (channel, source) in [color.r, color.g, color.b].into_iter().enumerate() {
+            let destination = self.data[i + channel] as f32;
+            self.data[i + channel] = ((source as f32 * source_alpha + destination * destination_alpha * (1.0 - source_alpha)) / alpha).round() as u8;
+        }
+        self.data[i + 3] = (alpha * 255.0).round() as u8;
+    }
+}
+
+/// A canvas that pixmaps can be drawn onto.
+pub trait PixmapTarget {
+    /// Draws the pixmap onto the canvas, blended with the existing content.
+    fn draw_pixmap(&mut self, pixmap: &Pixmap) -> DrawingResult;
+}
+
+impl PixmapTarget for sdl2::render::Canvas<sdl2::video::Window> {
+    fn draw_pixmap(&mut self, pixmap: &Pixmap) -> DrawingResult {
+        let texture_creator = self.texture_creator();
+        copy_pixmap(self, &texture_creator, pixmap)
+    }
+}
+
+impl PixmapTarget for sdl2::render::Canvas<sdl2::surface::Surface<'_>> {
+    fn draw_pixmap(&mut self, pixmap: &Pixmap) -> DrawingResult {
+        let texture_creator = self.texture_creator();
+        copy_pixmap(self, &texture_creator, pixmap)
+    }
+}
+
+/// The part of the rectangle inside the current clip rectangle of the canvas. None if none of it is visible.
+pub fn visible_clip_rect<T: sdl2::render::RenderTarget>(canvas: &sdl2::render::Canvas<T>, rect: sdl2::rect::Rect) -> Option<sdl2::rect::Rect> {
+    match canvas.clip_rect() {
+        Some(clip_rect) => clip_rect.intersection(rect),
+        None => Some(rect),
+    }
+}
+
+/// Uploads the pixmap into a new texture with alpha blending. The texture can't outlive the texture creator.
+pub fn create_texture<'r, Context>(texture_creator: &'r sdl2::render::TextureCreator<Context>, pixmap: &Pixmap) -> Result<sdl2::render::Texture<'r>, DrawingError> {
+    let mut texture = texture_creator.create_texture_static(PIXMAP_FORMAT, pixmap.width, pixmap.height)
+        .map_err(|e| DrawingError { msg: e.to_string() })?;
+    texture.set_blend_mode(sdl2::render::BlendMode::Blend);
+    texture.update(None, &pixmap.data, (pixmap.width * 4) as usize).map_err(|e| DrawingError { msg: e.to_string() })?;
+    Ok(texture)
+}
+
+/// Uploads the pixmap into a texture and copies it onto the canvas.
+fn copy_pixmap<Target: sdl2::render::RenderTarget, Context>(canvas: &mut sdl2::render::Canvas<Target>, texture_creator: &sdl2::render::TextureCreator<Context>, pixmap: &Pixmap) -> DrawingResult {
+    if pixmap.width == 0 || pixmap.height == 0 {
+        return Ok(DrawingSuccess {});
+    }
+    let texture = create_texture(texture_creator, pixmap)?;
+    canvas.copy(&texture, None, Some(sdl2::rect::Rect::new(pixmap.x, pixmap.y, pixmap.width, pixmap.height)))
+        .map(|_| DrawingSuccess {}).map_err(|msg| DrawingError { msg })
+}
+
+/// A box or text shadow, with lengths in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shadow {
+    pub offset_x: f32,
+    pub offset_y: f32,
+    /// Blur radius, twice the standard deviation of the gaussian blur.
+    pub blur: f32,
+    pub spread: f32,
+    pub color: sdl2::pixels::Color,
+    pub inset: bool,
+}
+
+impl Shadow {
+    /// How far an outer shadow can reach past the shape that casts it, up to three standard deviations of its blur.
+    pub fn extent(&self) -> i32 {
+        (self.offset_x.abs().max(self.offset_y.abs()) + self.spread.max(0.0) + self.blur * 1.5).ceil() as i32
+    }
+}
+
+/// Paints a box shadow into a pixmap. Outer shadows are only painted outside of the border box, inset shadows only inside of the padding box.
+/// - visible [Rect]: Part of the canvas that should be painted.
+///
+/// Returns None if no part of the shadow is visible.
+pub fn paint_box_shadow(border_box: &RoundedRect, padding_box: &RoundedRect, shadow: &Shadow, visible: sdl2::rect::Rect) -> Option<Pixmap> {
+    // the blur reaches up to twice its standard deviation past the shape's edge
+    let margin = shadow.blur.ceil() as i32 + 1;
+    let clip_box = if shadow.inset { padding_box } else { border_box };
+    let shape = if shadow.inset {
+        padding_box.shadow_shape(shadow.offset_x, shadow.offset_y, -shadow.spread)
+    } else {
+        border_box.shadow_shape(shadow.offset_x, shadow.offset_y, shadow.spread)
+    };
+    let bounds = |r: &RoundedRect, margin: i32| sdl2::rect::Rect::new(
+        r.x.floor() as i32 - margin, r.y.floor() as i32 - margin,
+        (r.width.ceil() as i32 + 2 * margin + 1).max(1) as u32, (r.height.ceil() as i32 + 2 * margin + 1).max(1) as u32,
+    );
+    let region = if shadow.inset { bounds(padding_box, 0) } else { bounds(&shape, margin) }.intersection(visible)?;
+    // the shape is rasterized with a margin, so that the blur is not cut off at the region's edges
+    let shape_region = if shadow.inset { region.union(bounds(&shape, margin)) } else { bounds(&shape, margin) };
+    let mut shape_mask = Path::rounded_rect(&shape).fill(FillRule::NonZero, shape_region);
+    shape_mask.blur(shadow.blur);
+    let clip_mask = Path::rounded_rect(clip_box).fill(FillRule::NonZero, region);
+    let mut pixmap = Pixmap::new(region);
+    for py in region.top()..region.bottom() {
+        for px in region.left()..region.right() {
+            let (shape_coverage, clip_coverage) = (shape_mask.coverage(px, py), clip_mask.coverage(px, py));
+            // inset shadows cover the area around the shape
+            let coverage = if shadow.inset { clip_coverage * (1.0 - shape_coverage) } else { shape_coverage * (1.0 - clip_coverage) };
+            pixmap.blend_pixel(px, py, shadow.color, coverage);
+        }
+    }
+    Some(pixmap)
+}
+
+/// A 2D affine transformation that maps (x, y) to (a * x + c * y + e, b * x + d * y + f), like the CSS `matrix()` function.
+///
+/// ```
+/// use yargl::util::Transform;
+/// let transform = Transform::translate(10.0, 0.0).multiply(&Transform::rotate(90.0));
+/// let (x, y) = transform.apply(1.0, 0.0);
+/// assert!((x - 10.0).abs() < 1e-5 && (y - 1.0).abs() < 1e-5);
+/// let (x, y) = transform.invert().unwrap().apply(x, y);
+/// assert!((x - 1.0).abs() < 1e-5 && y.abs() < 1e-5);
+/// assert!(Transform::scale(0.0, 1.0).invert().is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform { a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: 0.0, f: 0.0 };
+
+    pub fn translate(x: f32, y: f32) -> Transform {
+        Transform { e: x, f: y, ..Transform::IDENTITY }
+    }
+
+    pub fn scale(x: f32, y: f32) -> Transform {
+        Transform { a: x, d: y, ..Transform::IDENTITY }
+    }
+
+    /// Clockwise rotation (with y pointing down) by the angle in degrees.
+    pub fn rotate(degrees: f32) -> Transform {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Transform { a: cos, b: sin, c: -sin, d: cos, e: 0.0, f: 0.0 }
+    }
+
+    /// Skew along the x and y axis by the angles in degrees.
+    pub fn skew(x_degrees: f32, y_degrees: f32) -> Transform {
+        Transform { b: y_degrees.to_radians().tan(), c: x_degrees.to_radians().tan(), ..Transform::IDENTITY }
+    }
+
+    /// The transformation that applies the other transformation first and then this one.
+    pub fn multiply(&self, other: &Transform) -> Transform {
+        Transform {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            e: self.a * other.e + self.c * other.f + self.e,
+            f: self.b * other.e + self.d * other.f + self.f,
+        }
+    }
+
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.a * x + self.c * y + self.e, self.b * x + self.d * y + self.f)
+    }
+
+    /// The inverse transformation, None if the transformation collapses the plane (i.e. `scale(0)`).
+    pub fn invert(&self) -> Option<Transform> {
+        let determinant = self.a * self.d - self.b * self.c;
+        if determinant.abs() < f32::EPSILON {
+            return None;
+        }
+        Some(Transform {
+            a: self.d / determinant,
+            b: -self.b / determinant,
+            c: -self.c / determinant,
+            d: self.a / determinant,
+            e: (self.c * self.f - self.d * self.e) / determinant,
+            f: (self.b * self.e - self.a * self.f) / determinant,
+        })
+    }
+
+    /// Bounding box of the transformed rectangle, intersected with the visible rectangle.
+    pub fn bounds(&self, rect: sdl2::rect::Rect, visible: sdl2::rect::Rect) -> Option<sdl2::rect::Rect> {
+        let corners = [(rect.left(), rect.top()), (rect.right(), rect.top()), (rect.right(), rect.bottom()), (rect.left(), rect.bottom())]
+            .map(|(x, y)| self.apply(x as f32, y as f32));
+        let (min_x, max_x) = corners.iter().fold((f32::MAX, f32::MIN), |(min, max), (x, _)| (min.min(*x), max.max(*x)));
+        let (min_y, max_y) = corners.iter().fold((f32::MAX, f32::MIN), |(min, max), (_, y)| (min.min(*y), max.max(*y)));
+        let (left, top) = (min_x.floor() as i32, min_y.floor() as i32);
+        let (right, bottom) = (max_x.ceil() as i32, max_y.ceil() as i32);
+        if right <= left || bottom <= top {
+            return None;
+        }
+        sdl2::rect::Rect::new(left, top, (right - left) as u32, (bottom - top) as u32).intersection(visible)
+    }
+}
+
+/// A filter effect with lengths in pixels. Amounts are 0 to 1 (or more for brightness, contrast and saturate), angles are in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    Blur(f32),
+    Brightness(f32),
+    Contrast(f32),
+    Grayscale(f32),
+    HueRotate(f32),
+    Invert(f32),
+    Opacity(f32),
+    Saturate(f32),
+    Sepia(f32),
+    DropShadow(Shadow),
+}
+
+impl Filter {
+    /// How far the filter can spread content past its original area, up to three standard deviations of a blur.
+    pub fn extent(&self) -> i32 {
+        match self {
+            Filter::Blur(radius) => (radius * 1.5).ceil() as i32,
+            Filter::DropShadow(shadow) => shadow.extent(),
+            _ => 0,
+        }
+    }
+
+    /// Applies a color filter to straight RGB values from 0 to 1. The result may be out of range. Other filters return the color unchanged.
+    ///
+    /// ```
+    /// use yargl::util::Filter;
+    /// assert_eq!(Filter::Invert(1.0).apply_color([1.0, 0.0, 0.25]), [0.0, 1.0, 0.75]);
+    /// let [r, g, b] = Filter::Grayscale(1.0).apply_color([1.0, 0.0, 0.0]);
+    /// assert!((r - 0.2126).abs() < 1e-4 && r == g && g == b);
+    /// let [r, g, b] = Filter::HueRotate(360.0).apply_color([0.2, 0.4, 0.6]);
+    /// assert!((r - 0.2).abs() < 1e-4 && (g - 0.4).abs() < 1e-4 && (b - 0.6).abs() < 1e-4);
+    /// ```
+    pub fn apply_color(&self, [r, g, b]: [f32; 3]) -> [f32; 3] {
+        // color matrices of the filter effects specification
+        let matrix = match *self {
+            Filter::Brightness(amount) => return [r * amount, g * amount, b * amount],
+            Filter::Contrast(amount) => return [r, g, b].map(|c| (c - 0.5) * amount + 0.5),
+            Filter::Invert(amount) => return [r, g, b].map(|c| c * (1.0 - amount) + (1.0 - c) * amount),
+            Filter::Grayscale(amount) => {
+                let a = 1.0 - amount;
+                [
+                    [0.2126 + 0.7874 * a, 0.7152 - 0.7152 * a, 0.0722 - 0.0722 * a],
+                    [0.2126 - 0.2126 * a, 0.7152 + 0.2848 * a, 0.0722 - 0.0722 * a],
+                    [0.2126 - 0.2126 * a, 0.7152 - 0.7152 * a, 0.0722 + 0.9278 * a],
+                ]
+            },
+            Filter::Sepia(amount) => {
+                let a = 1.0 - amount;
+                [
+                    [0.393 + 0.607 * a, 0.769 - 0.769 * a, 0.189 - 0.189 * a],
+                    [0.349 - 0.349 * a, 0.686 + 0.314 * a, 0.168 - 0.168 * a],
+                    [0.272 - 0.272 * a, 0.534 - 0.534 * a, 0.131 + 0.869 * a],
+                ]
+            },
+            Filter::Saturate(s) => [
+                [0.213 + 0.787 * s, 0.715 - 0.715 * s, 0.072 - 0.072 * s],
+                [0.213 - 0.213 * s, 0.715 + 0.285 * s, 0.072 - 0.072 * s],
+                [0.213 - 0.213 * s, 0.715 - 0.715 * s, 0.072 + 0.928 * s],
+            ],
+            Filter::HueRotate(degrees) => {
+                let (sin, cos) = degrees.to_radians().sin_cos();
+                [
+                    [0.213 + cos * 0.787 - sin * 0.213, 0.715 - cos * 0.715 - sin * 0.715, 0.072 - cos * 0.072 + sin * 0.928],
+                    [0.213 - cos * 0.213 + sin * 0.143, 0.715 + cos * 0.285 + sin * 0.140, 0.072 - cos * 0.072 - sin * 0.283],
+                    [0.213 - cos * 0.213 - sin * 0.787, 0.715 - cos * 0.715 + sin * 0.715, 0.072 + cos * 0.928 + sin * 0.072],
+                ]
+            },
+            Filter::Blur(_) | Filter::Opacity(_) | Filter::DropShadow(_) => return [r, g, b],
+        };
+        matrix.map(|[mr, mg, mb]| mr * r + mg * g + mb * b)
+    }
+}
+
+/// A group of elements that is drawn into an offscreen texture, so that it can be composited as a whole
+/// with filters, an opacity and a transformation (applied in this order).
+pub struct Layer {
+    pub filters: Vec<Filter>,
+    pub opacity: f32,
+    pub transform: Option<Transform>,
+}
+
+impl Layer {
+    /// Draws the group with `draw` into a transparent texture that covers the bounds, then composites it onto the canvas.
+    /// The group is drawn at the same canvas coordinates as without the layer, but only the part inside of the bounds is kept.
+    /// - bounds [Rect]: Area of the canvas that the group paints into, including the area its filters spread it to.
+    /// - visible [Rect]: Part of the canvas that transformed layers are painted into.
+    pub fn draw<F: FnOnce(&mut sdl2::render::Canvas<sdl2::video::Window>)>(&self, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, bounds: sdl2::rect::Rect, visible: sdl2::rect::Rect, draw: F) -> DrawingResult {
+        let texture_creator = canvas.texture_creator();
+        let mut texture = texture_creator.create_texture_target(PIXMAP_FORMAT, bounds.width(), bounds.height())
+            .map_err(|e| DrawingError { msg: e.to_string() })?;
+        // changing the render target resets the viewport and clip rectangle, they are restored for the enclosing layer afterwards
+        let (viewport, clip_rect) = (canvas.viewport(), canvas.clip_rect());
+        // the layer is read back and composited in a pixmap, since its colors are premultiplied with their alpha
+        let mut pixmap = None;
+        let result = canvas.with_texture_canvas(&mut texture, |layer_canvas| {
+            layer_canvas.set_draw_color(sdl2::pixels::Color::RGBA(0, 0, 0, 0));
+            layer_canvas.clear();
+            layer_canvas.set_viewport(sdl2::rect::Rect::new(-bounds.x(), -bounds.y(), bounds.right().max(1) as u32, bounds.bottom().max(1) as u32));
+            draw(layer_canvas);
+            pixmap = Some(read_pixmap(layer_canvas, bounds));
+        });
+        canvas.set_viewport(viewport);
+        canvas.set_clip_rect(clip_rect);
+        result.map_err(|e| DrawingError { msg: e.to_string() })?;
+        let Some(pixmap) = pixmap else {
+            return Ok(DrawingSuccess {});
+        };
+        let mut pixmap = pixmap?;
+        for filter in self.filters.iter() {
+            pixmap.filter(filter);
+        }
+        if self.opacity < 1.0 {
+            pixmap.filter(&Filter::Opacity(self.opacity));
+        }
+        match self.transform {
+            Some(transform) => match pixmap.transformed(&transform, visible) {
+                Some(pixmap) => canvas.draw_pixmap(&pixmap),
+                // the transformation collapsed the layer or moved it out of view
+                None => Ok(DrawingSuccess {}),
+            },
+            None => canvas.draw_pixmap(&pixmap),
+        }
+    }
+}
+
+/// The position of the mouse cursor relative to the top left corner of the window, in the window coordinates of [crate::window::Window::get_node_handle_at].
+/// Unlike the position in mouse events, it is also known while the window doesn't receive them, e.g. while something is dragged onto it from another application.
+pub fn global_mouse_position(window: &sdl2::video::Window) -> (i32, i32) {
+    let (mut global_x, mut global_y) = (0, 0);
+    // SAFETY: SDL only writes the two coordinates through the pointers, which point to live integers.
+    unsafe { sdl2::sys::SDL_GetGlobalMouseState(&mut global_x, &mut global_y) };
+    // both positions are in screen coordinates, which are css pixels on scaled displays
+    let (window_x, window_y) = window.position();
+    (global_x - window_x, global_y - window_y)
+}
+
+/// Reads a region of the current render target into a pixmap. The region is given in the coordinates of the canvas' viewport, which layers offset to their bounds.
+/// Parts of the region outside of the render target are transparent.
+/// Layers are transparent, so their content is premultiplied by drawing it with blending, the window itself is opaque.
+pub fn read_pixmap(canvas: &sdl2::render::Canvas<sdl2::video::Window>, region: sdl2::rect::Rect) -> Result<Pixmap, DrawingError> {
+    let viewport = canvas.viewport();
+    let (width, height) = canvas.output_size().map_err(|msg| DrawingError { msg })?;
+    let target_region = match region.intersection(sdl2::rect::Rect::new(-viewport.x(), -viewport.y(), width, height)) {
+        Some(target_region) => target_region,
+        None => return Ok(Pixmap::new(region)),
+    };
+    let pixels = canvas.read_pixels(sdl2::rect::Rect::new(target_region.x() + viewport.x(), target_region.y() + viewport.y(), target_region.width(), target_region.height()), PIXMAP_FORMAT)
+        .map_err(|msg| DrawingError { msg })?;
+    let read = Pixmap::from_premultiplied(target_region, pixels, 1.0).ok_or(DrawingError { msg: "unexpected pixel data size".to_string() })?;
+    if target_region == region {
+        return Ok(read);
+    }
+    let mut pixmap = Pixmap::new(region);
+    let row_length = (read.width * 4) as usize;
+    for row in 0..read.height as usize {
+        let start = ((read.y - region.y()) as usize + row) * (region.width() * 4) as usize + (read.x - region.x()) as usize * 4;
+        pixmap.data[start..start + row_length].copy_from_slice(&read.data[row * row_length..(row + 1) * row_length]);
+    }
+    Ok(pixmap)
+}
+
+/// The id canvas counterpart of a transformed [Layer]: the ids of a group of elements are drawn onto a cleared region of the id canvas
+/// and then mapped through the transformation onto the previous content, so that hit testing matches the painted result.
+pub struct IdLayer {
+    saved: Vec<u8>,
+    region: Option<sdl2::rect::Rect>,
+    transform: Transform,
+}
+
+impl IdLayer {
+    /// Saves and clears the content of the id canvas inside of the bounds of the group.
+    pub fn begin(canvas: &mut sdl2::render::Canvas<sdl2::surface::Surface<'_>>, transform: Transform, bounds: sdl2::rect::Rect) -> IdLayer {
+        let surface = canvas.surface();
+        let region = bounds.intersection(sdl2::rect::Rect::new(0, 0, surface.width(), surface.height()));
+        let pitch = surface.pitch() as usize;
+        let saved = match (region, surface.without_lock()) {
+            (Some(region), Some(pixels)) => (region.top()..region.bottom())
+                .flat_map(|y| {
+                    let start = y as usize * pitch + region.x() as usize * 4;
+                    pixels[start..start + region.width() as usize * 4].iter().copied()
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        if let Some(region) = region {
+            canvas.set_draw_color(sdl2::pixels::Color::RGBA(0, 0, 0, 0));
+            let _ = canvas.fill_rect(region);
+        }
+        IdLayer { saved, region, transform }
+    }
+
+    /// Restores the saved content with the transformed ids of the group on top. Ids are not interpolated, each pixel takes the id of the nearest one.
+    pub fn end(self, canvas: &mut sdl2::render::Canvas<sdl2::surface::Surface<'_>>) {
+        let region = match self.region {
+            Some(region) => region,
+            None => return,
+        };
+        let surface = canvas.surface_mut();
+        let (width, height, pitch) = (surface.width(), surface.height(), surface.pitch() as usize);
+        let target = self.transform.bounds(region, sdl2::rect::Rect::new(0, 0, width, height));
+        let inverse = self.transform.invert();
+        let saved = self.saved;
+        let row_length = region.width() as usize * 4;
+        surface.with_lock_mut(|pixels| {
+            if saved.len() != row_length * region.height() as usize {
+                return;
+            }
+            // the ids of the group are taken out and replaced by the saved content
+            let mut group = Vec::with_capacity(saved.len());
+            for (row, y) in (region.top()..region.bottom()).enumerate() {
+                let start = y as usize * pitch + region.x() as usize * 4;
+                group.extend_from_slice(&pixels[start..start + row_length]);
+                pixels[start..start + row_length].copy_from_slice(&saved[row * row_length..(row + 1) * row_length]);
+            }
+            if let (Some(target), Some(inverse)) = (target, inverse) {
+                for py in target.top()..target.bottom() {
+                    for px in target.left()..target.right() {
+                        let (x, y) = inverse.apply(px as f32 + 0.5, py as f32 + 0.5);
+                        let (x, y) = (x.floor() as i32, y.floor() as i32);
+                        if !region.contains_point((x, y)) {
+                            continue;
+                        }
+                        let source = (y - region.y()) as usize * row_length + (x - region.x()) as usize * 4;
+                        if group[source..source + 4].iter().any(|byte| *byte != 0) {
+                            let destination = py as usize * pitch + px as usize * 4;
+                            pixels[destination..destination + 4].copy_from_slice(&group[source..source + 4]);
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// One side of a border.
+#[derive(Debug, Clone, Copy)]
+pub struct BorderSide {
+    pub width: f32,
+    pub style: css::BorderStyle,
+    pub color: sdl2::pixels::Color,
+}
+
+/// Paints the part of a border that is covered by the mask into a pixmap.
+/// Adjacent sides meet at the line from the outer to the inner corner of the border, so that sides with different colors and widths are mitered.
+/// - border_mask [&Mask]: The border box filled into the region that should be painted.
+/// - border_box [&RoundedRect]: Outer edge of the border.
+/// - sides [&[BorderSide; 4]]: Top, right, bottom and left side of the border.
+pub fn paint_border_box(border_mask: &Mask, border_box: &RoundedRect, sides: &[BorderSide; 4]) -> Pixmap {
+    let region = border_mask.region;
+    let mut pixmap = Pixmap::new(region);
+    let padding_mask = Path::rounded_rect(&border_box.inset(sides.map(|side| side.width))).fill(FillRule::NonZero, region);
+    for py in region.top()..region.bottom() {
+        for px in region.left()..region.right() {
+            let outer_coverage = border_mask.coverage(px, py);
+            if outer_coverage <= 0.0 {
+                continue;
+            }
+            let border_coverage = outer_coverage - padding_mask.coverage(px, py);
+            if border_coverage <= 0.0 {
+                continue;
+            }
+            let (cx, cy) = (px as f32 + 0.5, py as f32 + 0.5);
+            // distance from the outer edge of each side
+            let depths = [cy - border_box.y, border_box.x + border_box.width - cx, border_box.y + border_box.height - cy, cx - border_box.x];
+            // the pixel belongs to the side it is relatively closest to, which results in mitered corners
+            let side_index = (0..4)
+                .filter(|i| sides[*i].width > 0.0)
+                .min_by(|a, b| (depths[*a] / sides[*a].width).total_cmp(&(depths[*b] / sides[*b].width)));
+            if let Some(side_index) = side_index {
+                let along = if side_index % 2 == 0 { cx - border_box.x } else { cy - border_box.y };
+                let (color, pattern_coverage) = border_pattern(&sides[side_index], side_index, depths[side_index], along);
+                pixmap.blend_pixel(px, py, color, border_coverage * pattern_coverage);
+            }
+        }
+    }
+    pixmap
+}
+
+/// Coverage (0 to 1) of a pixel center in the band between start and end. Used for anti-aliased edges of straight border patterns.
+fn band_coverage(value: f32, start: f32, end: f32) -> f32 {
+    ((value - start).min(end - value) + 0.5).clamp(0.0, 1.0)
+}
+
+/// Darkens or lightens a color, used for the 3D border styles.
+fn shade(color: sdl2::pixels::Color, dark: bool) -> sdl2::pixels::Color {
+    let shade_channel = |c: u8| if dark { (c as u16 * 2 / 3) as u8 } else { c + (255 - c) / 3 };
+    sdl2::pixels::Color::RGBA(shade_channel(color.r), shade_channel(color.g), shade_channel(color.b), color.a)
+}
+
+/// Color and coverage of a border side's style at a pixel. depth is the distance from the outer edge of the side, along the position along the side.
+/// side_index is 0 to 3 for the top, right, bottom and left side.
+fn border_pattern(side: &BorderSide, side_index: usize, depth: f32, along: f32) -> (sdl2::pixels::Color, f32) {
+    let width = side.width;
+    // the top and left sides of the 3D styles are shaded as if lit from the bottom right
+    let top_or_left = side_index == 0 || side_index == 3;
+    match side.style {
+        css::BorderStyle::None | css::BorderStyle::Hidden => (side.color, 0.0),
+        css::BorderStyle::Solid => (side.color, 1.0),
+        css::BorderStyle::Dashed => {
+            let period = width * 6.0;
+            (side.color, band_coverage(along.rem_euclid(period), 0.0, width * 3.0))
+        },
+        css::BorderStyle::Dotted => {
+            let radius = width / 2.0;
+            let distance = ((along.rem_euclid(width * 2.0) - radius).powi(2) + (depth - radius).powi(2)).sqrt();
+            (side.color, (radius - distance + 0.5).clamp(0.0, 1.0))
+        },
+        css::BorderStyle::Double => {
+            let line_width = width / 3.0;
+            (side.color, band_coverage(depth, 0.0, line_width).max(band_coverage(depth, width - line_width, width)))
+        },
+        css::BorderStyle::Groove | css::BorderStyle::Ridge => {
+            let outer_half_dark = (side.style == css::BorderStyle::Groove) == top_or_left;
+            (shade(side.color, (depth < width / 2.0) == outer_half_dark), 1.0)
+        },
+        css::BorderStyle::Inset | css::BorderStyle::Outset => {
+            (shade(side.color, (side.style == css::BorderStyle::Inset) == top_or_left), 1.0)
+        },
+    }
+}
+
+/// Geometry of a gradient inside of its box, with the origin at the box's top left corner.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientShape {
+    /// The gradient line goes from the start to the end point.
+    Linear { start: (f32, f32), end: (f32, f32) },
+    /// The gradient line goes from the center to the ellipse with the given radii.
+    Radial { center: (f32, f32), radii: (f32, f32) },
+    /// The gradient goes clockwise around the center, starting at the given angle in radians from the top.
+    Conic { center: (f32, f32), angle: f32 },
+}
+
+impl GradientShape {
+    /// Length of the gradient line in pixels, used to convert stop positions into offsets. A full turn for conic gradients is 1.
+    pub fn line_length(&self) -> f32 {
+        match self {
+            GradientShape::Linear { start, end } => distance(*start, *end),
+            GradientShape::Radial { radii, .. } => radii.0,
+            GradientShape::Conic { .. } => 1.0,
+        }
+    }
+
+    /// Offset of a point along the gradient line, 0 at the start and 1 at the end.
+    pub fn offset_at(&self, x: f32, y: f32) -> f32 {
+        match *self {
+            GradientShape::Linear { start, end } => {
+                let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+                let length_squared = dx * dx + dy * dy;
+                if length_squared <= 0.0 {
+                    return 0.0;
+                }
+                ((x - start.0) * dx + (y - start.1) * dy) / length_squared
+            },
+            GradientShape::Radial { center, radii } => {
+                if radii.0 <= 0.0 || radii.1 <= 0.0 {
+                    return f32::INFINITY;
+                }
+                (((x - center.0) / radii.0).powi(2) + ((y - center.1) / radii.1).powi(2)).sqrt()
+            },
+            GradientShape::Conic { center, angle } => {
+                let point_angle = (x - center.0).atan2(center.1 - y);
+                (point_angle - angle).rem_euclid(std::f32::consts::TAU) / std::f32::consts::TAU
+            },
+        }
+    }
+}
+
+/// A gradient with color stops at resolved offsets along its gradient line.
+///
+/// ```
+/// use yargl::util::{ColorGradient, GradientShape};
+/// use sdl2::pixels::Color;
+/// let shape = GradientShape::Linear { start: (0.0, 0.0), end: (100.0, 0.0) };
+/// // the second stop has no offset and ends up in the middle, the hint moves the midpoint of the last transition
+/// let gradient = ColorGradient::new(shape, &[(Some(Color::RGB(255, 0, 0)), None), (Some(Color::RGB(0, 0, 255)), None), (None, Some(0.9)), (Some(Color::RGB(0, 0, 0)), None)], false).unwrap();
+/// assert_eq!(gradient.color_at(0.0, 0.0), Color::RGB(255, 0, 0));
+/// assert_eq!(gradient.color_at(50.0, 0.0), Color::RGB(0, 0, 255));
+/// assert_eq!(gradient.color_at(25.0, 0.0), Color::RGB(128, 0, 128));
+/// assert_eq!(gradient.color_at(90.0, 0.0), Color::RGB(0, 0, 128));
+/// assert_eq!(gradient.color_at(150.0, 0.0), Color::RGB(0, 0, 0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorGradient {
+    pub shape: GradientShape,
+    /// Offset and color of each stop, in ascending order of offset.
+    stops: Vec<(f32, sdl2::pixels::Color)>,
+    /// Offset of the color hint between each stop and the next one.
+    hints: Vec<Option<f32>>,
+    repeating: bool,
+}
+
+impl ColorGradient {
+    /// Creates a gradient from stops (with a color) and hints (without a color) with optional offsets.
+    /// Stops without offset are placed at the start, the end or evenly between their neighbours, offsets smaller than a previous one are raised to it.
+    /// Returns None if there are less than two stops.
+    pub fn new(shape: GradientShape, items: &[(Option<sdl2::pixels::Color>, Option<f32>)], repeating: bool) -> Option<ColorGradient> {
+        // hints need a stop on both sides
+        let first_stop = items.iter().position(|(color, _)| color.is_some())?;
+        let last_stop = items.iter().rposition(|(color, _)| color.is_some())?;
+        let items: Vec<(Option<sdl2::pixels::Color>, Option<f32>)> = items[first_stop..=last_stop].iter()
+            .filter(|(color, offset)| color.is_some() || offset.is_some())
+            .cloned()
+            .collect();
+        if items.iter().filter(|(color, _)| color.is_some()).count() < 2 {
+            return None;
+        }
+        let mut offsets: Vec<Option<f32>> = items.iter().map(|(_, offset)| *offset).collect();
+        let last = offsets.len() - 1;
+        offsets[0] = offsets[0].or(Some(0.0));
+        offsets[last] = offsets[last].or(Some(1.0));
+        let mut max_offset = f32::NEG_INFINITY;
+        for offset in offsets.iter_mut().flatten() {
+            max_offset = max_offset.max(*offset);
+            *offset = max_offset;
+        }
+        // distribute runs of stops without offset evenly between the previous and next stop with an offset (hints always have one)
+        let stop_indices: Vec<usize> = (0..items.len()).filter(|i| items[*i].0.is_some()).collect();
+        let mut i = 0;
+        while i < stop_indices.len() {
+            if offsets[stop_indices[i]].is_some() {
+                i += 1;
+                continue;
+            }
+            let run_start = i;
+            while offsets[stop_indices[i]].is_none() {
+                i += 1;
+            }
+            let (before, after) = (offsets[stop_indices[run_start - 1]].unwrap_or(0.0), offsets[stop_indices[i]].unwrap_or(1.0));
+            let steps = (i - run_start + 1) as f32;
+            for (n, index) in stop_indices[run_start..i].iter().enumerate() {
+                offsets[*index] = Some(before + (after - before) * (n + 1) as f32 / steps);
+            }
+        }
+        let mut stops = Vec::new();
+        let mut hints = Vec::new();
+        let mut hint = None;
+        for ((color, _), offset) in items.iter().zip(offsets) {
+            let offset = offset.unwrap_or(0.0);
+            match color {
+                Some(color) => {
+                    if !stops.is_empty() {
+                        hints.push(hint.take());
+                    }
+                    stops.push((offset, *color));
+                },
+                None => hint = Some(offset),
+            }
+        }
+        Some(ColorGradient { shape, stops, hints, repeating })
+    }
+
+    /// Color of the gradient at a point. Colors are interpolated with premultiplied alpha.
+    pub fn color_at(&self, x: f32, y: f32) -> sdl2::pixels::Color {
+        let mut offset = self.shape.offset_at(x, y);
+        let (first, _) = self.stops[0];
+        let (last, last_color) = self.stops[self.stops.len() - 1];
+        if self.repeating {
+            if last - first <= 0.0 {
+                return last_color;
+            }
+            offset = first + (offset - first).rem_euclid(last - first);
+        }
+        if offset.is_nan() || offset >= last {
+            return last_color;
+        }
+        for (i, window) in self.stops.windows(2).enumerate() {
+            let ((start, start_color), (end, end_color)) = (window[0], window[1]);
+            if offset > end {
+                continue;
+            }
+            if offset < start || end <= start {
+                return start_color;
+            }
+            let mut t = (offset - start) / (end - start);
+            if let Some(hint) = self.hints.get(i).copied().flatten() {
+                // the hint is where the colors are mixed half and half
+                let h = (hint - start) / (end - start);
+                t = if h <= 0.0 { 1.0 } else if h >= 1.0 { 0.0 } else { t.powf(0.5f32.ln() / h.ln()) };
+            }
+            return mix_colors(start_color, end_color, t);
+        }
+        last_color
+    }
+}
+
+/// Interpolates between two colors with premultiplied alpha, t from 0 (first color) to 1 (second color).
+fn mix_colors(a: sdl2::pixels::Color, b: sdl2::pixels::Color, t: f32) -> sdl2::pixels::Color {
+    let (alpha_a, alpha_b) = (a.a as f32 / 255.0, b.a as f32 / 255.0);
+    let alpha = alpha_a + (alpha_b - alpha_a) * t;
+    let channel = |ca: u8, cb: u8| {
+        if alpha <= 0.0 {
+            return 0;
+        }
+        let premultiplied = ca as f32 * alpha_a + (cb as f32 * alpha_b - ca as f32 * alpha_a) * t;
+        (premultiplied / alpha).round().clamp(0.0, 255.0) as u8
+    };
+    sdl2::pixels::Color::RGBA(channel(a.r, b.r), channel(a.g, b.g), channel(a.b, b.b), (alpha * 255.0).round() as u8)
+}
+
+/// Paints a gradient into the part of a pixmap that is covered by the mask.
+/// - tile [Rect]: Box of the gradient on the canvas.
+/// - repeat [(bool, bool)]: Whether the tile is repeated horizontally and vertically.
+pub fn paint_gradient(pixmap: &mut Pixmap, mask: &Mask, gradient: &ColorGradient, tile: sdl2::rect::Rect, repeat: (bool, bool)) {
+    let (tile_width, tile_height) = (tile.width() as f32, tile.height() as f32);
+    if tile_width <= 0.0 || tile_height <= 0.0 {
+        return;
+    }
+    let region = mask.region;
+    for py in region.top()..region.bottom() {
+        let mut y = py as f32 + 0.5 - tile.y() as f32;
+        if repeat.1 {
+            y = y.rem_euclid(tile_height);
+        } else if y < 0.0 || y >= tile_height {
+            continue;
+        }
+        for px in region.left()..region.right() {
+            let coverage = mask.coverage(px, py);
+            if coverage <= 0.0 {
+                continue;
+            }
+            let mut x = px as f32 + 0.5 - tile.x() as f32;
+            if repeat.0 {
+                x = x.rem_euclid(tile_width);
+            } else if x < 0.0 || x >= tile_width {
+                continue;
+            }
+            pixmap.blend_pixel(px, py, gradient.color_at(x, y), coverage);
+        }
+    }
 }
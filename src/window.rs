@@ -1,8 +1,8 @@
-use std::{collections::{HashMap, HashSet}, rc::Rc};
+use std::{cell::RefCell, collections::{HashMap, HashSet}, rc::Rc};
 
 use tl::VDom;
 
-use crate::{context::Context, css, layout::{NodeLayoutInfo, LayoutValue}, event, util::{self, DrawingError}};
+use crate::{context::Context, css, font, image, svg, layout::{NodeLayoutInfo, LayoutValue}, event, util::{self, DrawingError}};
 
 #[derive(Debug, Clone)]
 pub struct WindowCreationOptions {
@@ -16,72 +16,202 @@ pub struct WindowCreationError {
     pub message: String,
 }
 
-pub struct Window<'a, 'f, 'ff, 's> {
-    ctx: Rc<Context<'f, 'ff>>,
-    sdl_canvas: sdl2::render::Canvas<sdl2::video::Window>,
+// a drag of an element with the primary mouse button
+struct Drag {
+    source: tl::NodeHandle,
+    // position where the button went down, the drag starts when the pointer moved away far enough
+    start: (i32, i32),
+    // None until the drag started
+    data_transfer: Option<Rc<event::DataTransfer>>,
+    // whether the last drag over event was cancelled by the element below the pointer
+    drop_allowed: bool,
+}
+
+/// Distance in css pixels that the pointer must move with a pressed button before a drag starts.
+const DRAG_THRESHOLD: i32 = 4;
+
+/// Characters on which the text of an element can wrap.
+const BREAKABLE: [char; 5] = [' ', '\n', '\t', '\r', '\u{00A0}'];
+
+/// Background of selected text, unless a ::selection style sets one.
+const SELECTION_BACKGROUND: sdl2::pixels::Color = sdl2::pixels::Color::RGB(0xB4, 0xD5, 0xFE);
+
+/// A position in the text of an element, as the number of characters before it. Offsets count the characters of the element's text
+/// before its text-transform, so they stay valid for editing when a transformation turns one character into several.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextPosition {
+    pub node: tl::NodeHandle,
+    pub offset: usize,
+}
+
+pub struct Window<'a, 's> {
+    ctx: Rc<Context>,
+    // the window's canvas. It is taken out while the window is drawn, so that it can be handed to the offscreen canvases of layers.
+    sdl_canvas: Option<sdl2::render::Canvas<sdl2::video::Window>>,
     // id canvas stores the node id for each pixel in the canvas. Used for hit testing.
     id_canvas: sdl2::render::SurfaceCanvas<'s>,
+    // size of the canvas in physical pixels
     width: u32,
     height: u32,
+    // physical pixels per css pixel. Layout and painting work in physical pixels, css lengths are scaled by this ratio.
+    device_pixel_ratio: f32,
     html_file: String,
     vdom: VDom<'a>,
     computed_parents: HashMap<tl::NodeHandle, Option<tl::NodeHandle>>,
+    // parsed styles of the style and link nodes, read again when one of these nodes changes
+    styles: Vec<Rc<css::Style>>,
     computed_styles: HashMap<tl::NodeHandle, css::ComputedStyle>,
     computed_layouts: HashMap<tl::NodeHandle, NodeLayoutInfo>,
+    // decoded <img> and background images, loaded during layout. They are uploaded into textures when they are drawn.
+    images: image::ImageCache,
+    // documents of inline <svg> elements, parsed on first use. None for other elements.
+    svg_documents: RefCell<HashMap<tl::NodeHandle, Option<Rc<svg::Document>>>>,
+    // the last rendering of each inline <svg> element, with the region and current color it was rendered for
+    svg_renderings: HashMap<tl::NodeHandle, (sdl2::rect::Rect, sdl2::pixels::Color, util::Pixmap)>,
     pub pointer_down_events: event::GenericEventReceiver<event::PointerDownEvent>,
     pub pointer_up_events: event::GenericEventReceiver<event::PointerUpEvent>,
     pub pointer_move_events: event::GenericEventReceiver<event::PointerMoveEvent>,
     pub scroll_events: event::GenericEventReceiver<event::ScrollEvent>,
+    pub gesture_events: event::GenericEventReceiver<event::GestureEvent>,
+    pub key_down_events: event::GenericEventReceiver<event::KeyDownEvent>,
+    pub key_up_events: event::GenericEventReceiver<event::KeyUpEvent>,
+    pub text_input_events: event::GenericEventReceiver<event::TextInputEvent>,
+    pub pointer_over_events: event::GenericEventReceiver<event::PointerOverEvent>,
+    pub pointer_out_events: event::GenericEventReceiver<event::PointerOutEvent>,
+    pub pointer_enter_events: event::GenericEventReceiver<event::PointerEnterEvent>,
+    pub pointer_leave_events: event::GenericEventReceiver<event::PointerLeaveEvent>,
+    pub click_events: event::GenericEventReceiver<event::ClickEvent>,
+    pub double_click_events: event::GenericEventReceiver<event::DoubleClickEvent>,
+    pub focus_events: event::GenericEventReceiver<event::FocusEvent>,
+    pub blur_events: event::GenericEventReceiver<event::BlurEvent>,
+    pub resize_events: event::GenericEventReceiver<event::ResizeEvent>,
+    pub move_events: event::GenericEventReceiver<event::MoveEvent>,
+    pub window_focus_events: event::GenericEventReceiver<event::WindowFocusEvent>,
+    pub close_requested_events: event::GenericEventReceiver<event::CloseRequestedEvent>,
+    pub window_state_events: event::GenericEventReceiver<event::WindowStateEvent>,
+    pub display_change_events: event::GenericEventReceiver<event::DisplayChangeEvent>,
+    pub drag_start_events: event::GenericEventReceiver<event::DragStartEvent>,
+    pub drag_over_events: event::GenericEventReceiver<event::DragOverEvent>,
+    pub drop_events: event::GenericEventReceiver<event::DropEvent>,
+    pub drag_end_events: event::GenericEventReceiver<event::DragEndEvent>,
+    /// Keyboard shortcuts, which are triggered before the key down listeners.
+    pub accelerators: event::AcceleratorRegistry,
+    // element that receives keyboard input, and whether the focus is indicated (:focus-visible)
+    focused: Option<tl::NodeHandle>,
+    focus_visible: bool,
+    // element below each pointer, by pointer id (:hover)
+    hovered: HashMap<i64, tl::NodeHandle>,
+    // element on which each pointer went down with its primary button, by pointer id (:active)
+    pressed: HashMap<i64, tl::NodeHandle>,
+    // whether the window was restyled since it was drawn
+    needs_redraw: bool,
+    drag: Option<Drag>,
+    // index of the display that shows the window
+    display_index: i32,
+    closed: bool,
+    // styles of the ::selection pseudo-element of each element
+    selection_styles: HashMap<tl::NodeHandle, css::ComputedStyle>,
+    // selected text from the anchor, where the selection started, to the focus, where it ends
+    selection: Option<(TextPosition, TextPosition)>,
+    // whether the focus of the selection follows the mouse, while its primary button is pressed
+    selecting: bool,
 }
 
-impl Window<'_, '_, '_, '_> {
+impl Window<'_, '_> {
     /// Creates a new window and parses the given html.
-    pub fn new<'a, 'f, 'ff, 's>(ctx: Rc<Context<'f, 'ff>>, options: &WindowCreationOptions, html: &'a str, html_filename: Option<&str>) -> Result<Window<'a, 'f, 'ff, 's>, WindowCreationError> {
+    pub fn new<'a, 's>(ctx: Rc<Context>, options: &WindowCreationOptions, html: &'a str, html_filename: Option<&str>) -> Result<Window<'a, 's>, WindowCreationError> {
         let sdl_window = ctx.video_subsystem.window(&options.title, options.width, options.height)
             .position_centered()
-            //.resizable()
+            .allow_highdpi()
+            .resizable()
             .build()
             .map_err(|e| WindowCreationError { message: format!("Failed to create window: {}", e) })?;
         let canvas = sdl_window
         .into_canvas()
         .present_vsync()
         .build().map_err(|e| WindowCreationError { message: format!("Failed to create canvas: {}", e) })?;
+        // on scaled displays the drawable is larger than the window
+        let (drawable_width, drawable_height) = canvas.output_size()
+            .map_err(|e| WindowCreationError { message: format!("Failed to get drawable size: {}", e) })?;
+        let device_pixel_ratio = if options.width > 0 { drawable_width as f32 / options.width as f32 } else { 1.0 };
         let vdom = tl::parse(html, tl::ParserOptions::default()).map_err(|e| WindowCreationError { message: format!("Failed to parse html: {}", e) })?;
-        let surface = sdl2::surface::Surface::new(options.width, options.height, sdl2::pixels::PixelFormatEnum::RGBA8888)
+        let surface = sdl2::surface::Surface::new(drawable_width, drawable_height, sdl2::pixels::PixelFormatEnum::RGBA8888)
             .map_err(|e| WindowCreationError { message: format!("Failed to create id surface: {}", e) })?;
         let id_canvas = sdl2::render::SurfaceCanvas::from_surface(surface).
             map_err(|e| WindowCreationError { message: format!("Failed to create id canvas: {}", e) })?;
+        let images = image::ImageCache::new();
         let mut w = Window {
             ctx: ctx.clone(),
-            sdl_canvas: canvas,
+            sdl_canvas: Some(canvas),
             id_canvas,
-            width: options.width,
-            height: options.height,
+            width: drawable_width,
+            height: drawable_height,
+            device_pixel_ratio,
             vdom,
             html_file: html_filename.unwrap_or("").to_string(),
             computed_parents: HashMap::new(),
+            styles: Vec::new(),
             computed_styles: HashMap::new(),
             computed_layouts: HashMap::new(),
+            images,
+            svg_documents: RefCell::new(HashMap::new()),
+            svg_renderings: HashMap::new(),
             pointer_down_events: event::GenericEventReceiver::new(),
             pointer_up_events: event::GenericEventReceiver::new(),
             pointer_move_events: event::GenericEventReceiver::new(),
             scroll_events: event::GenericEventReceiver::new(),
+            gesture_events: event::GenericEventReceiver::new(),
+            key_down_events: event::GenericEventReceiver::new(),
+            key_up_events: event::GenericEventReceiver::new(),
+            text_input_events: event::GenericEventReceiver::new(),
+            pointer_over_events: event::GenericEventReceiver::new(),
+            pointer_out_events: event::GenericEventReceiver::new(),
+            pointer_enter_events: event::GenericEventReceiver::new(),
+            pointer_leave_events: event::GenericEventReceiver::new(),
+            click_events: event::GenericEventReceiver::new(),
+            double_click_events: event::GenericEventReceiver::new(),
+            focus_events: event::GenericEventReceiver::new(),
+            blur_events: event::GenericEventReceiver::new(),
+            resize_events: event::GenericEventReceiver::new(),
+            move_events: event::GenericEventReceiver::new(),
+            window_focus_events: event::GenericEventReceiver::new(),
+            close_requested_events: event::GenericEventReceiver::new(),
+            window_state_events: event::GenericEventReceiver::new(),
+            display_change_events: event::GenericEventReceiver::new(),
+            drag_start_events: event::GenericEventReceiver::new(),
+            drag_over_events: event::GenericEventReceiver::new(),
+            drop_events: event::GenericEventReceiver::new(),
+            drag_end_events: event::GenericEventReceiver::new(),
+            accelerators: event::AcceleratorRegistry::new(),
+            focused: None,
+            focus_visible: false,
+            hovered: HashMap::new(),
+            pressed: HashMap::new(),
+            needs_redraw: false,
+            drag: None,
+            display_index: 0,
+            closed: false,
+            selection_styles: HashMap::new(),
+            selection: None,
+            selecting: false,
         };
+        w.display_index = w.sdl_window().display_index().unwrap_or(0);
         w.compute_parents();
         w.compute_styles();
         w.compute_layout();
         Ok(w)
     }
 
-    /// Retrieves the top node at the given cursor position. None if the position is outside the canvas.
+    /// Retrieves the top node at the given cursor position (in window coordinates). None if the position is outside the canvas.
     pub fn get_node_at(&self, x: i32, y: i32) -> Option<&tl::Node> {
         match self.get_node_handle_at(x, y) {
             Some(node_handle) => node_handle.get(self.vdom.parser()),
             None => None
         }
     }
-    /// Retrieves the handle of the top node at the given cursor position. None if the position is outside the canvas.
+    /// Retrieves the handle of the top node at the given cursor position (in window coordinates). None if the position is outside the canvas.
     pub fn get_node_handle_at(&self, x: i32, y: i32) -> Option<tl::NodeHandle> {
+        let (x, y) = ((x as f32 * self.device_pixel_ratio) as i32, (y as f32 * self.device_pixel_ratio) as i32);
         if x < 0 || y < 0 || x >= (self.width as i32) || y >= (self.height as i32) {
             return None;
         }
@@ -95,17 +225,102 @@ impl Window<'_, '_, '_, '_> {
         }
 
     }
+    /// The given node and its ancestors, starting at the root. Events propagate along this path.
+    pub fn event_path(&self, node_handle: tl::NodeHandle) -> Vec<tl::NodeHandle> {
+        let mut path = vec![node_handle];
+        while let Some(parent) = self.computed_parents.get(path.last().unwrap()).copied().flatten() {
+            path.push(parent);
+        }
+        path.reverse();
+        path
+    }
+    /// The cursor of the top node at the given cursor position, [css::Cursor::Auto] if neither the node nor its ancestors set one.
+    pub fn cursor_at(&self, x: i32, y: i32) -> css::Cursor {
+        self.get_node_handle_at(x, y)
+            .and_then(|node_handle| self.inherited_value::<css::Cursor>(node_handle, "cursor"))
+            .unwrap_or(css::Cursor::Auto)
+    }
+    /// The element that keyboard events are dispatched to, None if no element has focus.
+    pub fn focused(&self) -> Option<tl::NodeHandle> {
+        self.focused
+    }
+    /// Moves the keyboard focus to the given element, or removes it with None.
+    /// Dispatches blur and focus events and restyles the window if the focus changed.
+    pub fn focus(&mut self, node_handle: Option<tl::NodeHandle>) -> event::EventReturnCode {
+        if self.focused == node_handle {
+            return event::EventReturnCode::Continue;
+        }
+        let return_code = self.move_focus(node_handle, true);
+        self.restyle();
+        return_code
+    }
+    /// The focusable elements in the order of sequential (Tab) navigation: elements with a positive tabindex in ascending order,
+    /// then the others in document order. Elements with a negative tabindex and elements that are not rendered are skipped.
+    pub fn tab_order(&self) -> Vec<tl::NodeHandle> {
+        let mut order: Vec<(i32, tl::NodeHandle)> = self.get_all_handles(self.vdom.children(), None).into_iter()
+            .map(|(node_handle, _)| node_handle)
+            .filter(|node_handle| self.is_focusable(*node_handle) && self.is_rendered(*node_handle))
+            .filter_map(|node_handle| match self.get_attribute(node_handle, "tabindex").and_then(|tabindex| tabindex.trim().parse::<i32>().ok()) {
+                Some(tabindex) if tabindex < 0 => None,
+                Some(tabindex) if tabindex > 0 => Some((tabindex, node_handle)),
+                _ => Some((i32::MAX, node_handle)),
+            })
+            .collect();
+        // the sort is stable, so elements with the same tabindex stay in document order
+        order.sort_by_key(|(tabindex, _)| *tabindex);
+        order.into_iter().map(|(_, node_handle)| node_handle).collect()
+    }
+    /// Moves the focus to the next element in [Window::tab_order], or to the previous one if backwards is true, wrapping around at the end.
+    /// The focus is indicated like after any keyboard navigation (:focus-visible).
+    pub fn focus_next(&mut self, backwards: bool) -> event::EventReturnCode {
+        let order = self.tab_order();
+        if order.is_empty() {
+            return event::EventReturnCode::Continue;
+        }
+        let next = match (self.focused.and_then(|focused| order.iter().position(|node_handle| *node_handle == focused)), backwards) {
+            (Some(index), false) => (index + 1) % order.len(),
+            (Some(index), true) => (index + order.len() - 1) % order.len(),
+            (None, false) => 0,
+            (None, true) => order.len() - 1,
+        };
+        let return_code = self.move_focus(Some(order[next]), true);
+        self.restyle();
+        return_code
+    }
+    /// Closes the window: it is hidden and doesn't receive events anymore. [crate::context::Context::poll_events] closes a window
+    /// when the user asks to, unless a listener cancels the [event::CloseRequestedEvent].
+    pub fn close(&mut self) {
+        self.canvas_mut().window_mut().hide();
+        self.closed = true;
+    }
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+    /// Whether the window was restyled since it was last drawn. [crate::context::Context::poll_events] redraws these windows.
+    pub fn needs_redraw(&self) -> bool {
+        self.needs_redraw
+    }
     /// Get the underlying sdl window.
     pub fn sdl_window(&self) -> &sdl2::video::Window {
-        self.sdl_canvas.window()
+        self.canvas().window()
+    }
+    fn canvas(&self) -> &sdl2::render::Canvas<sdl2::video::Window> {
+        self.sdl_canvas.as_ref().expect("the canvas is only taken out while drawing")
+    }
+    fn canvas_mut(&mut self) -> &mut sdl2::render::Canvas<sdl2::video::Window> {
+        self.sdl_canvas.as_mut().expect("the canvas is only taken out while drawing")
     }
-    /// Viewport width.
+    /// Viewport width in css pixels.
     pub fn width(&self) -> u32 {
-        self.width
+        (self.width as f32 / self.device_pixel_ratio).round() as u32
     }
-    /// Viewport height.
+    /// Viewport height in css pixels.
     pub fn height(&self) -> u32 {
-        self.height
+        (self.height as f32 / self.device_pixel_ratio).round() as u32
+    }
+    /// Number of physical pixels per css pixel, i.e. 2 on a display that is scaled by 200%.
+    pub fn device_pixel_ratio(&self) -> f32 {
+        self.device_pixel_ratio
     }
 
     /// Redraws the whole window.
@@ -117,17 +332,301 @@ impl Window<'_, '_, '_, '_> {
         self.id_canvas.set_draw_color(sdl2::pixels::Color::RGBA(0, 0, 0, 0));
         self.id_canvas.clear();
         self.id_canvas.set_blend_mode(sdl2::render::BlendMode::None);
-        self.sdl_canvas.set_draw_color(sdl2::pixels::Color::RGBA(0xFF, 0xFF, 0xFF, 0xFF));
-        self.sdl_canvas.clear();
-        self.sdl_canvas.set_blend_mode(sdl2::render::BlendMode::Blend);
-        let all_handles = self.get_all_handles(self.vdom.children(), None);
-        for (node_handle, _parent_handle) in all_handles {
-            match self.draw_element(&node_handle) {
-                Ok(_) => {},
-                Err(_err) => {}
+        let mut canvas = match self.sdl_canvas.take() {
+            Some(canvas) => canvas,
+            None => return,
+        };
+        canvas.set_draw_color(sdl2::pixels::Color::RGBA(0xFF, 0xFF, 0xFF, 0xFF));
+        canvas.clear();
+        canvas.set_blend_mode(sdl2::render::BlendMode::Blend);
+        let all_handles: Vec<tl::NodeHandle> = self.get_all_handles(self.vdom.children(), None).into_iter().map(|(node_handle, _)| node_handle).collect();
+        let selected: HashMap<tl::NodeHandle, (usize, usize)> = self.selected_ranges().into_iter().map(|(node_handle, start, end)| (node_handle, (start, end))).collect();
+        self.draw_nodes(&mut canvas, &all_handles, &selected, &mut Vec::new());
+        canvas.present();
+        self.sdl_canvas = Some(canvas);
+        self.needs_redraw = false;
+    }
+
+    /// Draws the nodes, which are given in tree order, onto the canvas.
+    /// Elements with filters, an opacity or a transform are drawn together with their descendants into a layer, which is then filtered,
+    /// composited with the opacity and mapped through the transform. Transformed elements also get an id layer, so that hit testing finds them where they are painted.
+    /// - below [&mut Vec<Pixmap>]: The content below each enclosing layer that backdrop filters inside of it read, outermost first.
+    fn draw_nodes(&mut self, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, nodes: &[tl::NodeHandle], selected: &HashMap<tl::NodeHandle, (usize, usize)>, below: &mut Vec<util::Pixmap>) {
+        let mut index = 0;
+        while index < nodes.len() {
+            let node_handle = nodes[index];
+            // hidden elements keep their layout and their visible descendants are still drawn, but they paint nothing themselves and can't be hit
+            let visible = self.inherited_value::<css::Visibility>(node_handle, "visibility").unwrap_or(css::Visibility::Visible) == css::Visibility::Visible;
+            // the backdrop filter applies to everything drawn before the element, so it is painted before the element's own layer begins
+            if visible {
+                let _ = self.draw_backdrop(canvas, node_handle, below);
+            }
+            let filters = self.element_filters(node_handle, "filter");
+            let opacity = self.opacity(node_handle);
+            let transform = self.transform(node_handle);
+            if filters.is_empty() && opacity.is_none() && transform.is_none() {
+                if visible {
+                    self.draw_node(canvas, node_handle, selected.get(&node_handle).copied());
+                }
+                index += 1;
+                continue;
+            }
+            let descendants = self.descendant_count(node_handle, &nodes[index + 1..]);
+            let group = &nodes[index + 1..index + 1 + descendants];
+            index += 1 + descendants;
+            // the layer only covers the visible part of the area that the group paints into
+            let bounds = match self.group_paint_bounds(node_handle, group) {
+                Some(bounds) => bounds,
+                None => continue,
+            };
+            // backdrop filters inside of the layer can't read the content below it once the layer is the render target, so it is saved before
+            let backdrop_region = group.iter().filter_map(|descendant| self.backdrop(*descendant)).map(|(_, _, region)| region).reduce(|a, b| a.union(b));
+            let saved = backdrop_region.and_then(|region| util::read_pixmap(canvas, region).ok());
+            let saved_below = saved.is_some();
+            below.extend(saved);
+            let layer = util::Layer { filters, opacity: opacity.unwrap_or(1.0), transform };
+            let id_layer = transform.map(|transform| util::IdLayer::begin(&mut self.id_canvas, transform, bounds));
+            let _ = layer.draw(canvas, bounds, sdl2::rect::Rect::new(0, 0, self.width, self.height), |layer_canvas| {
+                if visible {
+                    self.draw_node(layer_canvas, node_handle, selected.get(&node_handle).copied());
+                }
+                self.draw_nodes(layer_canvas, group, selected, below);
+            });
+            if saved_below {
+                below.pop();
+            }
+            if let Some(id_layer) = id_layer {
+                id_layer.end(&mut self.id_canvas);
+            }
+        }
+    }
+
+    /// Draws a visible element and its outline.
+    fn draw_node(&mut self, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, node_handle: tl::NodeHandle, selected: Option<(usize, usize)>) {
+        let _ = self.draw_element(canvas, &node_handle, selected);
+        let _ = self.draw_outline(canvas, node_handle);
+    }
+
+    /// The value of an inherited property: the node's own value, or the value of the closest ancestor that sets it.
+    fn inherited_value<T: std::str::FromStr>(&self, node_handle: tl::NodeHandle, property: &str) -> Option<T> {
+        let mut current = Some(node_handle);
+        while let Some(handle) = current {
+            if let Some(value) = self.computed_styles.get(&handle).and_then(|style| style.get_raw_value(property)).and_then(|value| value.parse::<T>().ok()) {
+                return Some(value);
+            }
+            current = self.computed_parents.get(&handle).copied().flatten();
+        }
+        None
+    }
+
+    /// Whether the first node is an ancestor of the second one.
+    fn is_ancestor(&self, ancestor: tl::NodeHandle, node_handle: tl::NodeHandle) -> bool {
+        let mut current = self.computed_parents.get(&node_handle).copied().flatten();
+        while let Some(parent) = current {
+            if parent == ancestor {
+                return true;
             }
+            current = self.computed_parents.get(&parent).copied().flatten();
         }
-        self.sdl_canvas.present();
+        false
+    }
+
+    /// The opacity of an element (number or percentage), None if it is fully opaque.
+    fn opacity(&self, node_handle: tl::NodeHandle) -> Option<f32> {
+        let opacity = match self.computed_styles.get(&node_handle)?.get_value::<f32>("opacity") {
+            (Some(value), Some(css::Unit::Percent)) => value / 100.0,
+            (Some(value), None) => value,
+            _ => return None,
+        };
+        Some(opacity.clamp(0.0, 1.0)).filter(|opacity| *opacity < 1.0)
+    }
+
+    /// The border box of an element with its corner radii. None if the element has no layout.
+    fn border_box(&self, node_handle: tl::NodeHandle) -> Option<util::RoundedRect> {
+        let layout = self.computed_layouts.get(&node_handle)?;
+        let x = layout.get::<{LayoutValue::X as usize}>()?;
+        let y = layout.get::<{LayoutValue::Y as usize}>()?;
+        let width = layout.get::<{LayoutValue::Width as usize}>()? + layout.get::<{LayoutValue::PaddingLeft as usize}>().unwrap_or(0) + layout.get::<{LayoutValue::PaddingRight as usize}>().unwrap_or(0)
+            + layout.get::<{LayoutValue::BorderLeftWidth as usize}>().unwrap_or(0) + layout.get::<{LayoutValue::BorderRightWidth as usize}>().unwrap_or(0);
+        let height = layout.get::<{LayoutValue::Height as usize}>()? + layout.get::<{LayoutValue::PaddingTop as usize}>().unwrap_or(0) + layout.get::<{LayoutValue::PaddingBottom as usize}>().unwrap_or(0)
+            + layout.get::<{LayoutValue::BorderTopWidth as usize}>().unwrap_or(0) + layout.get::<{LayoutValue::BorderBottomWidth as usize}>().unwrap_or(0);
+        let radius = |x: Option<i32>, y: Option<i32>| util::CornerRadius { x: x.unwrap_or(0) as f32, y: y.or(x).unwrap_or(0) as f32 };
+        let radii = [
+            radius(layout.get::<{LayoutValue::BorderTopLeftRadius as usize}>(), layout.get::<{LayoutValue::BorderTopLeftRadiusY as usize}>()),
+            radius(layout.get::<{LayoutValue::BorderTopRightRadius as usize}>(), layout.get::<{LayoutValue::BorderTopRightRadiusY as usize}>()),
+            radius(layout.get::<{LayoutValue::BorderBottomRightRadius as usize}>(), layout.get::<{LayoutValue::BorderBottomRightRadiusY as usize}>()),
+            radius(layout.get::<{LayoutValue::BorderBottomLeftRadius as usize}>(), layout.get::<{LayoutValue::BorderBottomLeftRadiusY as usize}>()),
+        ];
+        Some(util::RoundedRect::new(x as f32, y as f32, width as f32, height as f32, radii))
+    }
+
+    /// Paints the backdrop filter of an element: the content behind its border box is filtered and drawn over itself.
+    /// Inside of layers, the content below the enclosing layers (outermost first) shows through where the layers are still transparent.
+    fn draw_backdrop(&mut self, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, node_handle: tl::NodeHandle, below: &[util::Pixmap]) -> util::DrawingResult {
+        let (border_box, filters, region) = match self.backdrop(node_handle) {
+            Some(backdrop) => backdrop,
+            None => return Ok(util::DrawingSuccess {}),
+        };
+        let mut backdrop = util::read_pixmap(canvas, region)?;
+        if !below.is_empty() {
+            let mut composed = util::Pixmap::new(region);
+            for pixmap in below.iter() {
+                pixmap.draw_onto(&mut composed);
+            }
+            backdrop.draw_onto(&mut composed);
+            backdrop = composed;
+        }
+        for filter in filters.iter() {
+            backdrop.filter(filter);
+        }
+        backdrop.clip(&util::Path::rounded_rect(&border_box).fill(util::FillRule::NonZero, region));
+        util::PixmapTarget::draw_pixmap(canvas, &backdrop)
+    }
+
+    /// The border box and backdrop filters of an element, and the visible part of the canvas that the filters read. None if the element has no backdrop filter.
+    fn backdrop(&self, node_handle: tl::NodeHandle) -> Option<(util::RoundedRect, Vec<util::Filter>, sdl2::rect::Rect)> {
+        let filters = self.element_filters(node_handle, "backdrop-filter");
+        let border_box = self.border_box(node_handle).filter(|border_box| !filters.is_empty() && border_box.width > 0.0 && border_box.height > 0.0)?;
+        // blurs read the content around the box, up to three standard deviations
+        let margin = filters.iter().map(|filter| filter.extent()).sum::<i32>();
+        let bounds = sdl2::rect::Rect::new(border_box.x as i32 - margin, border_box.y as i32 - margin,
+            (border_box.width as i32 + 2 * margin) as u32, (border_box.height as i32 + 2 * margin) as u32);
+        let region = bounds.intersection(sdl2::rect::Rect::new(0, 0, self.width, self.height))?;
+        Some((border_box, filters, region))
+    }
+
+    /// Paints the outline of an element around its border box, grown by the outline-offset. The outline doesn't take up space and is not hit tested.
+    fn draw_outline(&mut self, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, node_handle: tl::NodeHandle) -> util::DrawingResult {
+        let (outline_box, side) = match self.outline(node_handle) {
+            Some(outline) => outline,
+            None => return Ok(util::DrawingSuccess {}),
+        };
+        let region = match Self::outline_bounds(&outline_box).intersection(sdl2::rect::Rect::new(0, 0, self.width, self.height)) {
+            Some(region) => region,
+            None => return Ok(util::DrawingSuccess {}),
+        };
+        let outline_mask = util::Path::rounded_rect(&outline_box).fill(util::FillRule::NonZero, region);
+        util::PixmapTarget::draw_pixmap(canvas, &util::paint_border_box(&outline_mask, &outline_box, &[side; 4]))
+    }
+
+    /// The outer edge of an element's outline and the style it is painted with. None if the element has no outline.
+    fn outline(&self, node_handle: tl::NodeHandle) -> Option<(util::RoundedRect, util::BorderSide)> {
+        let style = self.computed_styles.get(&node_handle)?;
+        let mut outline = style.get_raw_value("outline").and_then(|v| v.parse::<css::Outline>().ok()).unwrap_or_default();
+        let has_outline_style = style.get_raw_value("outline").is_some() || style.get_raw_value("outline-style").is_some();
+        if let Some(width) = style.get_raw_value("outline-width").and_then(|v| css::parse_dimension(v.as_str())) {
+            outline.width = Some(width);
+        }
+        if let Some(outline_style) = style.get_raw_value("outline-style") {
+            outline.style = if outline_style == "auto" { Some(css::BorderStyle::Solid) } else { outline_style.parse::<css::BorderStyle>().ok() };
+        }
+        if let Some(color) = style.get_value::<css::CssColor>("outline-color").0 {
+            outline.color = Some(color);
+        }
+        // the focus ring of an element with a visible focus is a default outline, unless the element sets an outline style (i.e. outline: none)
+        if !has_outline_style && self.focus_visible && self.focused == Some(node_handle) {
+            outline.style = Some(css::BorderStyle::Solid);
+            outline.width = outline.width.or(Some((2.0, Some(css::Unit::Px))));
+            outline.color = outline.color.or(Some(css::CssColor { sdl_color: sdl2::pixels::Color::RGB(0x00, 0x5F, 0xCC) }));
+        }
+        let outline_style = outline.style.filter(|outline_style| *outline_style != css::BorderStyle::None && *outline_style != css::BorderStyle::Hidden)?;
+        let font_size = self.computed_layouts.get(&node_handle).and_then(|layout| layout.get::<{LayoutValue::FontSize as usize}>()).unwrap_or(0);
+        // the initial width is medium, which is 3px
+        let width = outline.width.map_or(self.resolve_length(3.0, Some(css::Unit::Px), font_size, 0), |(value, unit)| self.resolve_length(value, unit, font_size, 0)) as f32;
+        let offset = style.get_raw_value("outline-offset").and_then(|v| css::parse_dimension(v.as_str()))
+            .map_or(0, |(value, unit)| self.resolve_length(value, unit, font_size, 0)) as f32;
+        let color = outline.color.or_else(|| style.get_value::<css::CssColor>("color").0)
+            .map_or(sdl2::pixels::Color::RGB(0, 0, 0), |color| color.sdl_color);
+        let border_box = self.border_box(node_handle).filter(|_| width > 0.0)?;
+        Some((border_box.shadow_shape(0.0, 0.0, offset + width), util::BorderSide { width, style: outline_style, color }))
+    }
+
+    /// The pixels covered by an outline.
+    fn outline_bounds(outline_box: &util::RoundedRect) -> sdl2::rect::Rect {
+        sdl2::rect::Rect::new(outline_box.x.floor() as i32, outline_box.y.floor() as i32, outline_box.width.ceil() as u32 + 1, outline_box.height.ceil() as u32 + 1)
+    }
+
+    /// The area an element paints into by itself: its border box, grown by its outer box shadows, text shadows and outline.
+    fn element_paint_bounds(&self, node_handle: tl::NodeHandle) -> Option<sdl2::rect::Rect> {
+        let border_box = self.border_box(node_handle)?;
+        let style = self.computed_styles.get(&node_handle)?;
+        let font_size = self.computed_layouts.get(&node_handle).and_then(|layout| layout.get::<{LayoutValue::FontSize as usize}>()).unwrap_or(0);
+        let extent = self.shadows(style, "box-shadow", font_size).into_iter().chain(self.shadows(style, "text-shadow", font_size))
+            .filter(|shadow| !shadow.inset)
+            .map(|shadow| shadow.extent())
+            .max().unwrap_or(0);
+        let bounds = sdl2::rect::Rect::new(border_box.x.floor() as i32 - extent, border_box.y.floor() as i32 - extent,
+            (border_box.width.ceil() as i32 + 2 * extent).max(1) as u32, (border_box.height.ceil() as i32 + 2 * extent).max(1) as u32);
+        Some(match self.outline(node_handle) {
+            Some((outline_box, _)) => bounds.union(Self::outline_bounds(&outline_box)),
+            None => bounds,
+        })
+    }
+
+    /// The area that the nodes, given in tree order, paint into together with their descendants. Filters grow the area they spread content to,
+    /// transformed elements paint into the transformed area. Clipped to the window.
+    fn paint_bounds(&self, nodes: &[tl::NodeHandle]) -> Option<sdl2::rect::Rect> {
+        let visible = sdl2::rect::Rect::new(0, 0, self.width, self.height);
+        let mut bounds: Option<sdl2::rect::Rect> = None;
+        let mut index = 0;
+        while index < nodes.len() {
+            let node_handle = nodes[index];
+            let descendants = self.descendant_count(node_handle, &nodes[index + 1..]);
+            let mut node_bounds = self.group_paint_bounds(node_handle, &nodes[index + 1..index + 1 + descendants]);
+            if let Some(transform) = self.transform(node_handle) {
+                node_bounds = node_bounds.and_then(|node_bounds| transform.bounds(node_bounds, visible));
+            }
+            if let Some(node_bounds) = node_bounds {
+                bounds = Some(bounds.map_or(node_bounds, |bounds| bounds.union(node_bounds)));
+            }
+            index += 1 + descendants;
+        }
+        bounds
+    }
+
+    /// The area that an element and its descendants paint into before the element's transformation is applied, grown by the element's filters.
+    fn group_paint_bounds(&self, node_handle: tl::NodeHandle, descendants: &[tl::NodeHandle]) -> Option<sdl2::rect::Rect> {
+        let bounds = match (self.element_paint_bounds(node_handle), self.paint_bounds(descendants)) {
+            (Some(own_bounds), Some(descendant_bounds)) => own_bounds.union(descendant_bounds),
+            (own_bounds, descendant_bounds) => own_bounds.or(descendant_bounds)?,
+        };
+        let extent = self.element_filters(node_handle, "filter").iter().map(|filter| filter.extent()).sum::<i32>();
+        let bounds = sdl2::rect::Rect::new(bounds.x() - extent, bounds.y() - extent, bounds.width() + 2 * extent as u32, bounds.height() + 2 * extent as u32);
+        bounds.intersection(sdl2::rect::Rect::new(0, 0, self.width, self.height))
+    }
+
+    /// Number of nodes at the start of the list that are descendants of the node. The list is in tree order and follows the node.
+    fn descendant_count(&self, node_handle: tl::NodeHandle, following: &[tl::NodeHandle]) -> usize {
+        following.iter().take_while(|descendant| self.is_ancestor(node_handle, **descendant)).count()
+    }
+
+    /// The transformation of an element in canvas coordinates, around its transform-origin (relative to the border box, centered by default).
+    /// None if the element has no transform.
+    fn transform(&self, node_handle: tl::NodeHandle) -> Option<util::Transform> {
+        let style = self.computed_styles.get(&node_handle)?;
+        let functions = css::parse_transform(style.get_raw_value("transform")?.as_str());
+        if functions.is_empty() {
+            return None;
+        }
+        let border_box = self.border_box(node_handle)?;
+        let (x, y, width, height) = (border_box.x as i32, border_box.y as i32, border_box.width as i32, border_box.height as i32);
+        let font_size = self.computed_layouts.get(&node_handle)?.get::<{LayoutValue::FontSize as usize}>().unwrap_or(0);
+        let origin = style.get_raw_value("transform-origin").and_then(|v| v.parse::<css::Position>().ok()).unwrap_or_default();
+        let origin_x = (x + self.resolve_length(origin.x.0, origin.x.1, font_size, width)) as f32;
+        let origin_y = (y + self.resolve_length(origin.y.0, origin.y.1, font_size, height)) as f32;
+        let transform = functions.iter().fold(util::Transform::translate(origin_x, origin_y), |transform, function| {
+            transform.multiply(&match *function {
+                css::TransformFunction::Matrix([a, b, c, d, e, f]) => util::Transform { a, b, c, d, e, f },
+                css::TransformFunction::Translate(tx, ty) => util::Transform::translate(
+                    self.resolve_length(tx.0, tx.1, font_size, width) as f32,
+                    self.resolve_length(ty.0, ty.1, font_size, height) as f32,
+                ),
+                css::TransformFunction::Scale(sx, sy) => util::Transform::scale(sx, sy),
+                css::TransformFunction::Rotate(angle) => util::Transform::rotate(angle),
+                css::TransformFunction::Skew(ax, ay) => util::Transform::skew(ax, ay),
+            })
+        });
+        Some(transform.multiply(&util::Transform::translate(-origin_x, -origin_y)))
     }
 
     /// Applies the given style to all nodes that match the style's selector.
@@ -136,9 +635,20 @@ impl Window<'_, '_, '_, '_> {
     /// - style: The style to apply.
     pub fn apply_style(&mut self, style: &Rc<css::Style>) {
         for selector in style.selectors.iter() {
-            if let Some(nodes) = self.vdom.query_selector(selector.to_string().as_str()) {
+            // the document is queried without the pseudo-classes, which are matched against the state of the window
+            let query = match selector.without_pseudo_classes().to_string() {
+                query if query.is_empty() => "*".to_string(),
+                query => query,
+            };
+            if let Some(nodes) = self.vdom.query_selector(query.as_str()) {
+                let nodes: Vec<tl::NodeHandle> = nodes.filter(|node_handle| selector.pseudo_classes.iter().all(|pseudo_class| self.matches_pseudo_class(*node_handle, pseudo_class))).collect();
+                // styles of the selected text are kept apart from the styles of the elements
+                let computed_styles = match selector.pseudo_element {
+                    Some(css::PseudoElement::Selection) => &mut self.selection_styles,
+                    None => &mut self.computed_styles,
+                };
                 for node_handle in nodes {
-                    match self.computed_styles.get_mut(&node_handle) {
+                    match computed_styles.get_mut(&node_handle) {
                         Some(computed_style) => {
                             computed_style.apply_style(style.clone(), &selector.specificity())
                         },
@@ -149,7 +659,7 @@ impl Window<'_, '_, '_, '_> {
                             };
                             let mut computed_style = css::ComputedStyle::new(complete_selector);
                             computed_style.apply_style(style.clone(), &selector.specificity());
-                            self.computed_styles.insert(node_handle.clone(), computed_style);
+                            computed_styles.insert(node_handle.clone(), computed_style);
                         }
                     }
                 }
@@ -194,16 +704,8 @@ impl Window<'_, '_, '_, '_> {
                         if path_opt.is_none() {
                             continue;
                         }
-                        let mut path: std::path::PathBuf;
-                        let mut path_str = path_opt.unwrap();
-                        if path_str.starts_with(".") {
-                            path = std::path::PathBuf::from(self.html_file.as_str());
-                            path.pop();
-                            path.push(path_str);
-                        } else {
-                            path = std::path::PathBuf::from(path_str);
-                        }
-                        path_str = path.to_str().unwrap();
+                        let path = self.resolve_path(path_opt.unwrap());
+                        let path_str = path.to_str().unwrap();
                         // parse css
                         match std::fs::read_to_string(path_str) {
                             Ok(css_text) => match css::parse_css(css_text.as_str()) {
@@ -221,6 +723,66 @@ impl Window<'_, '_, '_, '_> {
         return styles;
     }
     
+    /// Resolves a path which is relative to the html file (if it starts with "."). Other paths are returned unchanged.
+    fn resolve_path(&self, path_str: &str) -> std::path::PathBuf {
+        if path_str.starts_with('.') {
+            let mut path = std::path::PathBuf::from(self.html_file.as_str());
+            path.pop();
+            path.push(path_str);
+            path
+        } else {
+            std::path::PathBuf::from(path_str)
+        }
+    }
+
+    /// Returns the resolved path of an <img> element's src attribute. None for other nodes.
+    fn image_source(&self, node_handle: tl::NodeHandle) -> Option<std::path::PathBuf> {
+        let tag = node_handle.get(self.vdom.parser())?.as_tag()?;
+        if !tag.name().as_utf8_str().eq_ignore_ascii_case("img") {
+            return None;
+        }
+        let src = tag.attributes().get("src")??.try_as_utf8_str()?;
+        Some(self.resolve_path(src))
+    }
+
+    /// The document of an inline <svg> element. It is parsed once and kept until an attribute is changed.
+    fn svg_document(&self, node_handle: tl::NodeHandle) -> Option<Rc<svg::Document>> {
+        self.svg_documents.borrow_mut().entry(node_handle)
+            .or_insert_with(|| node_handle.get(self.vdom.parser()).and_then(|node| svg::Document::from_node(node, self.vdom.parser())).map(Rc::new))
+            .clone()
+    }
+
+    /// Intrinsic size of a replaced element (<img> or <svg>) in physical pixels. None for other elements and images that could not be loaded.
+    fn intrinsic_size(&mut self, node_handle: tl::NodeHandle) -> Option<(u32, u32)> {
+        if let Some(document) = self.svg_document(node_handle) {
+            let (width, height) = document.size();
+            return Some(((width * self.device_pixel_ratio).round() as u32, (height * self.device_pixel_ratio).round() as u32));
+        }
+        self.image_source(node_handle).and_then(|path| self.image_size(&path, 1.0)).map(|(width, height)| (width.round() as u32, height.round() as u32))
+    }
+
+    /// Size of an image file in physical pixels, when each css pixel holds `resolution` image pixels.
+    fn image_size(&mut self, path: &std::path::Path, resolution: f32) -> Option<(f32, f32)> {
+        let scale = self.device_pixel_ratio / resolution;
+        self.images.size(path).map(|(width, height)| (width as f32 * scale, height as f32 * scale))
+    }
+
+    /// Loads the fonts declared by @font-face rules into the context's font database.
+    /// Each rule adds the first source that can be loaded to the family named by its font-family property.
+    fn load_font_faces(&self, styles: &[Rc<css::Style>]) {
+        for style in styles.iter().filter(|s| s.at_rule.as_deref() == Some("font-face")) {
+            let (family, _) = style.get_value::<String>("font-family");
+            let (src, _) = style.get_value::<String>("src");
+            if let (Some(family), Some(src)) = (family, src) {
+                for url in css::parse_urls(src.as_str()) {
+                    if self.ctx.load_font_file(family.as_str(), self.resolve_path(url.as_str())).is_ok() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
     /// Returns a vector of all node handles in the document. Sorted in tree order (root, child 1 of root, child 1 of child 1 of root, child 2 of child 1 of root, child 2 of root...).
     /// Call with self.vdom.children() to get all node handles in the document.
     fn get_all_handles<'a, I>(&self, nodes: I, parent: Option<&tl::NodeHandle>) -> Vec<(tl::NodeHandle, Option<tl::NodeHandle>)>
@@ -228,6 +790,10 @@ impl Window<'_, '_, '_, '_> {
         let mut result: Vec<(tl::NodeHandle, Option<tl::NodeHandle>)> = Vec::new();
         for node_handle in nodes {
             result.push((node_handle.clone(), parent.map(|p| p.clone())));
+            // the content of an <svg> element is rendered as part of the element
+            if self.svg_document(*node_handle).is_some() {
+                continue;
+            }
             match node_handle.get(self.vdom.parser()).map_or(None, |node| node.children()) {
                 Some(children) => {
                     result.extend(self.get_all_handles(children.top().iter(), Some(node_handle)));
@@ -240,12 +806,623 @@ impl Window<'_, '_, '_, '_> {
 
     /// Computes the style for each node in the document and stores them in computed_styles.
     fn compute_styles(&mut self) {
-        let styles = self.read_styles();
-        for style in styles.iter() {
-            self.apply_style(&style.clone());
+        self.styles = self.read_styles();
+        self.load_font_faces(&self.styles);
+        let device_pixel_ratio = self.device_pixel_ratio;
+        for style in self.styles.clone().iter().filter(|s| s.at_rule.is_none() && s.matches_media(device_pixel_ratio)) {
+            self.apply_style(style);
+        }
+    }
+
+    /// Recomputes the styles and the layout after the document or the state of its elements changed. Takes effect with the next [Window::draw].
+    /// The styles read by [Window::read_styles] are applied again, without parsing the style and link nodes.
+    pub fn restyle(&mut self) {
+        self.computed_styles.clear();
+        self.selection_styles.clear();
+        let device_pixel_ratio = self.device_pixel_ratio;
+        for style in self.styles.clone().iter().filter(|s| s.at_rule.is_none() && s.matches_media(device_pixel_ratio)) {
+            self.apply_style(style);
+        }
+        self.compute_layout();
+        self.needs_redraw = true;
+    }
+
+    /// The closest element that is dragged when the pointer goes down on the target: an element with draggable="true", an image or a link,
+    /// unless an element in between has draggable="false".
+    fn draggable_element(&self, target: tl::NodeHandle) -> Option<tl::NodeHandle> {
+        for node_handle in self.event_path(target).into_iter().rev() {
+            match self.get_attribute(node_handle, "draggable").as_deref() {
+                Some("true") => return Some(node_handle),
+                Some("false") => return None,
+                _ => {}
+            }
+            let tag_name = node_handle.get(self.vdom.parser()).and_then(|node| node.as_tag()).map(|tag| tag.name().as_utf8_str().to_ascii_lowercase());
+            match tag_name.as_deref() {
+                Some("img") => return Some(node_handle),
+                Some("a") if self.get_attribute(node_handle, "href").is_some() => return Some(node_handle),
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Prepares a drag of the draggable element at the target, which starts when the pointer moves while the button is pressed.
+    pub(crate) fn begin_drag(&mut self, target: Option<tl::NodeHandle>, x: i32, y: i32) {
+        self.drag = target.and_then(|target| self.draggable_element(target)).map(|source| Drag { source, start: (x, y), data_transfer: None, drop_allowed: false });
+    }
+
+    /// Moves a drag: starts it with a drag start event once the pointer moved far enough, then dispatches drag over events to the element below the pointer.
+    /// Returns None if there is no drag in progress, the pointer move is then dispatched as usual.
+    pub(crate) fn drag_move(&mut self, x: i32, y: i32, timestamp: u32) -> Option<event::EventReturnCode> {
+        let drag = self.drag.as_ref()?;
+        let source = drag.source;
+        let data_transfer = match &drag.data_transfer {
+            Some(data_transfer) => data_transfer.clone(),
+            None if (x - drag.start.0).abs() < DRAG_THRESHOLD && (y - drag.start.1).abs() < DRAG_THRESHOLD => return None,
+            None => {
+                let data_transfer = Rc::new(event::DataTransfer::new());
+                let data = event::DragEventData { x, y, data_transfer: data_transfer.clone(), timestamp };
+                let return_code = event::dispatch(self, |window| &window.drag_start_events, &event::Event::new(event::DragStartEvent { data }, Some(source)));
+                if return_code != event::EventReturnCode::Continue {
+                    self.drag = None;
+                    return Some(return_code);
+                }
+                data_transfer
+            }
+        };
+        let target = self.get_node_handle_at(x, y);
+        let data = event::DragEventData { x, y, data_transfer: data_transfer.clone(), timestamp };
+        let return_code = event::dispatch(self, |window| &window.drag_over_events, &event::Event::new(event::DragOverEvent { data }, target));
+        if let Some(drag) = self.drag.as_mut() {
+            drag.data_transfer = Some(data_transfer);
+            drag.drop_allowed = return_code == event::EventReturnCode::Cancel;
+        }
+        Some(return_code)
+    }
+
+    /// Ends a drag when the button is released: dispatches a drop event if the element below the pointer allowed it, and a drag end event.
+    /// The pointer is released without a click. Returns None if no drag started, the pointer up is then dispatched as usual.
+    pub(crate) fn drag_end(&mut self, x: i32, y: i32, timestamp: u32) -> Option<event::EventReturnCode> {
+        let drag = self.drag.take()?;
+        let data_transfer = drag.data_transfer?;
+        if self.pressed.remove(&event::MOUSE_POINTER_ID).is_some() {
+            self.restyle();
+        }
+        let mut return_code = event::EventReturnCode::Continue;
+        if drag.drop_allowed {
+            let target = self.get_node_handle_at(x, y);
+            let data = event::DragEventData { x, y, data_transfer: data_transfer.clone(), timestamp };
+            return_code = event::dispatch(self, |window| &window.drop_events, &event::Event::new(event::DropEvent { data }, target));
+        }
+        if return_code != event::EventReturnCode::Quit {
+            let data = event::DragEventData { x, y, data_transfer, timestamp };
+            return_code = event::dispatch(self, |window| &window.drag_end_events, &event::Event::new(event::DragEndEvent { data, dropped: drag.drop_allowed }, Some(drag.source)));
+        }
+        Some(return_code)
+    }
+
+    /// Adapts the window to the current size of its drawable: updates the device pixel ratio, resizes the id canvas and lays out the document again.
+    /// Restyles the document if the device pixel ratio changed and a style depends on it.
+    pub(crate) fn update_size(&mut self) -> Result<(), DrawingError> {
+        let (drawable_width, drawable_height) = self.canvas().output_size().map_err(|e| DrawingError { msg: e })?;
+        let (window_width, _) = self.sdl_window().size();
+        let previous_ratio = self.device_pixel_ratio;
+        if window_width > 0 {
+            self.device_pixel_ratio = drawable_width as f32 / window_width as f32;
+        }
+        if (drawable_width, drawable_height) != (self.width, self.height) {
+            let surface = sdl2::surface::Surface::new(drawable_width, drawable_height, sdl2::pixels::PixelFormatEnum::RGBA8888).map_err(|e| DrawingError { msg: e })?;
+            self.id_canvas = sdl2::render::SurfaceCanvas::from_surface(surface).map_err(|e| DrawingError { msg: e })?;
+            self.width = drawable_width;
+            self.height = drawable_height;
+        }
+        if self.device_pixel_ratio != previous_ratio && self.styles.iter().any(|style| !style.media.is_empty()) {
+            self.restyle();
+            return Ok(());
+        }
+        self.compute_layout();
+        self.needs_redraw = true;
+        Ok(())
+    }
+
+    /// Checks whether the window moved to another display or the scale of its display changed. Updates the size if the scale changed.
+    pub(crate) fn update_display(&mut self) -> bool {
+        let display_index = self.sdl_window().display_index().unwrap_or(self.display_index);
+        let (drawable_width, _) = self.canvas().output_size().unwrap_or((self.width, self.height));
+        let (window_width, _) = self.sdl_window().size();
+        let scaled = window_width > 0 && drawable_width as f32 / window_width as f32 != self.device_pixel_ratio;
+        if display_index == self.display_index && !scaled {
+            return false;
+        }
+        self.display_index = display_index;
+        if scaled {
+            let _ = self.update_size();
+        }
+        true
+    }
+
+    /// Whether an element matches a pseudo-class in the current state of the window.
+    fn matches_pseudo_class(&self, node_handle: tl::NodeHandle, pseudo_class: &css::PseudoClass) -> bool {
+        let is_self_or_ancestor = |descendant: tl::NodeHandle| descendant == node_handle || self.is_ancestor(node_handle, descendant);
+        match pseudo_class {
+            css::PseudoClass::Hover => self.hovered.values().any(|hovered| is_self_or_ancestor(*hovered)),
+            css::PseudoClass::Active => self.pressed.values().any(|pressed| is_self_or_ancestor(*pressed)),
+            css::PseudoClass::Focus => self.focused == Some(node_handle),
+            css::PseudoClass::FocusVisible => self.focus_visible && self.focused == Some(node_handle),
+            css::PseudoClass::FocusWithin => self.focused.is_some_and(is_self_or_ancestor),
+            css::PseudoClass::Unsupported(_) => false,
+        }
+    }
+
+    /// Whether a change of the given pseudo-classes on the elements in the event paths of the given targets can change the computed styles:
+    /// a style has a selector with one of these pseudo-classes, which matches one of these elements apart from its pseudo-classes.
+    fn state_affects_styles(&self, pseudo_classes: &[css::PseudoClass], targets: &[Option<tl::NodeHandle>]) -> bool {
+        let elements: HashSet<tl::NodeHandle> = targets.iter().flatten().flat_map(|target| self.event_path(*target)).collect();
+        if elements.is_empty() {
+            return false;
+        }
+        self.styles.iter().filter(|style| style.at_rule.is_none() && style.matches_media(self.device_pixel_ratio))
+            .flat_map(|style| style.selectors.iter())
+            .filter(|selector| selector.pseudo_classes.iter().any(|pseudo_class| pseudo_classes.contains(pseudo_class)))
+            .any(|selector| {
+                let query = match selector.without_pseudo_classes().to_string() {
+                    query if query.is_empty() => "*".to_string(),
+                    query => query,
+                };
+                self.vdom.query_selector(query.as_str()).is_some_and(|mut nodes| nodes.any(|node_handle| elements.contains(&node_handle)))
+            })
+    }
+
+    /// Whether an element can receive the keyboard focus: enabled form controls, links and elements with a tabindex.
+    fn is_focusable(&self, node_handle: tl::NodeHandle) -> bool {
+        let tag = match node_handle.get(self.vdom.parser()).and_then(|node| node.as_tag()) {
+            Some(tag) => tag,
+            None => return false,
+        };
+        if tag.attributes().get("tabindex").flatten().is_some_and(|tabindex| tabindex.as_utf8_str().trim().parse::<i32>().is_ok()) {
+            return true;
+        }
+        match tag.name().as_utf8_str().to_ascii_lowercase().as_str() {
+            "input" => !tag.attributes().get("type").flatten().is_some_and(|input_type| input_type.as_utf8_str().eq_ignore_ascii_case("hidden"))
+                && !tag.attributes().contains("disabled"),
+            "button" | "select" | "textarea" => !tag.attributes().contains("disabled"),
+            "a" | "area" => tag.attributes().contains("href"),
+            _ => false,
+        }
+    }
+
+    /// Whether an element is rendered: neither it nor one of its ancestors has display: none, and it is visible.
+    fn is_rendered(&self, node_handle: tl::NodeHandle) -> bool {
+        let displayed = self.event_path(node_handle).iter()
+            .all(|node_handle| self.computed_styles.get(node_handle).and_then(|style| style.get_value::<css::Display>("display").0) != Some(css::Display::None));
+        displayed && self.inherited_value::<css::Visibility>(node_handle, "visibility").unwrap_or(css::Visibility::Visible) == css::Visibility::Visible
+    }
+
+    /// Moves the focus without restyling. Dispatches a blur event to the element that loses the focus and a focus event to the one that receives it.
+    fn move_focus(&mut self, node_handle: Option<tl::NodeHandle>, visible: bool) -> event::EventReturnCode {
+        let previous = self.focused;
+        self.focus_visible = visible;
+        if previous == node_handle {
+            return event::EventReturnCode::Continue;
+        }
+        self.focused = node_handle;
+        let mut return_code = event::EventReturnCode::Continue;
+        if previous.is_some() {
+            return_code = event::dispatch(self, |window| &window.blur_events, &event::Event::new_non_bubbling(event::BlurEvent { related_target: node_handle }, previous));
+        }
+        if node_handle.is_some() && return_code != event::EventReturnCode::Quit {
+            return_code = event::dispatch(self, |window| &window.focus_events, &event::Event::new_non_bubbling(event::FocusEvent { related_target: previous }, node_handle));
+        }
+        return_code
+    }
+
+    /// Updates the element below a pointer (None if the pointer left the window or was lifted), dispatches the pointer out, leave, over and enter
+    /// events and restyles the window if the element changed and a :hover style applies to the elements that were or are below the pointer.
+    pub(crate) fn set_pointer_target(&mut self, pointer_id: i64, target: Option<tl::NodeHandle>, x: i32, y: i32, timestamp: u32) -> event::EventReturnCode {
+        let previous = match target {
+            Some(target) => self.hovered.insert(pointer_id, target),
+            None => self.hovered.remove(&pointer_id),
+        };
+        if previous == target {
+            return event::EventReturnCode::Continue;
+        }
+        let previous_path = previous.map(|previous| self.event_path(previous)).unwrap_or_default();
+        let path = target.map(|target| self.event_path(target)).unwrap_or_default();
+        let data = |related_target| event::PointerBoundaryEventData { x, y, pointer_id, related_target, timestamp };
+        let mut return_code = event::EventReturnCode::Continue;
+        if previous.is_some() {
+            return_code = event::dispatch(self, |window| &window.pointer_out_events, &event::Event::new(event::PointerOutEvent { data: data(target) }, previous));
+        }
+        // elements are left from the inside out and entered from the outside in
+        for node_handle in previous_path.iter().rev().filter(|node_handle| !path.contains(node_handle)) {
+            if return_code != event::EventReturnCode::Quit {
+                return_code = event::dispatch(self, |window| &window.pointer_leave_events, &event::Event::new_non_bubbling(event::PointerLeaveEvent { data: data(target) }, Some(*node_handle)));
+            }
+        }
+        if target.is_some() && return_code != event::EventReturnCode::Quit {
+            return_code = event::dispatch(self, |window| &window.pointer_over_events, &event::Event::new(event::PointerOverEvent { data: data(previous) }, target));
+        }
+        for node_handle in path.iter().filter(|node_handle| !previous_path.contains(node_handle)) {
+            if return_code != event::EventReturnCode::Quit {
+                return_code = event::dispatch(self, |window| &window.pointer_enter_events, &event::Event::new_non_bubbling(event::PointerEnterEvent { data: data(previous) }, Some(*node_handle)));
+            }
+        }
+        if self.state_affects_styles(&[css::PseudoClass::Hover], &[previous, target]) {
+            self.restyle();
+        }
+        return_code
+    }
+
+    /// Marks the target as pressed by a pointer's primary button. Unless the pointer down event was cancelled,
+    /// the focus moves to the closest focusable element at the pointer, or is removed if there is none.
+    /// Restyles the window if an :active or :focus style applies to the elements whose state changed.
+    pub(crate) fn press_pointer(&mut self, pointer_id: i64, target: Option<tl::NodeHandle>, cancelled: bool) -> event::EventReturnCode {
+        let previous = match target {
+            Some(target) => self.pressed.insert(pointer_id, target),
+            None => self.pressed.remove(&pointer_id),
+        };
+        let (previous_focused, previous_focus_visible) = (self.focused, self.focus_visible);
+        let mut return_code = event::EventReturnCode::Continue;
+        if !cancelled {
+            let focusable = target.and_then(|target| self.event_path(target).into_iter().rev().find(|node_handle| self.is_focusable(*node_handle)));
+            return_code = self.move_focus(focusable, false);
+        }
+        let pressed_changed = previous != target && self.state_affects_styles(&[css::PseudoClass::Active], &[previous, target]);
+        let focus_changed = (previous_focused, previous_focus_visible) != (self.focused, self.focus_visible)
+            && self.state_affects_styles(&[css::PseudoClass::Focus, css::PseudoClass::FocusVisible, css::PseudoClass::FocusWithin], &[previous_focused, self.focused]);
+        if pressed_changed || focus_changed {
+            self.restyle();
+        }
+        return_code
+    }
+
+    /// Releases a pointer's primary button. Dispatches a click event, and a double click event on the second click, if the pointer goes up on the element on which it went down.
+    /// Restyles the window if an :active style applies to the element on which the pointer went down.
+    pub(crate) fn release_pointer(&mut self, data: event::PointerEventData, target: Option<tl::NodeHandle>) -> event::EventReturnCode {
+        let pressed = match self.pressed.remove(&data.pointer_id) {
+            Some(pressed) => pressed,
+            None => return event::EventReturnCode::Continue,
+        };
+        if self.state_affects_styles(&[css::PseudoClass::Active], &[Some(pressed)]) {
+            self.restyle();
+        }
+        if target != Some(pressed) {
+            return event::EventReturnCode::Continue;
+        }
+        let double_click = data.mouse_data.as_ref().is_some_and(|mouse_data| mouse_data.clicks == 2);
+        let mut return_code = event::dispatch(self, |window| &window.click_events, &event::Event::new(event::ClickEvent { data: data.clone() }, target));
+        if double_click && return_code != event::EventReturnCode::Quit {
+            return_code = event::dispatch(self, |window| &window.double_click_events, &event::Event::new(event::DoubleClickEvent { data }, target));
+        }
+        return_code
+    }
+
+    /// Whether an element draws its text: it has a font-family, a color and text.
+    fn draws_text(&self, node_handle: tl::NodeHandle) -> bool {
+        self.computed_styles.get(&node_handle).is_some_and(|style| style.get_value::<String>("font-family").0.is_some() && style.get_value::<css::CssColor>("color").0.is_some())
+            && node_handle.get(self.vdom.parser()).is_some_and(|node| !node.inner_text(self.vdom.parser()).is_empty())
+    }
+
+    /// The rendered elements that draw text, in document order. A selection extends over these elements.
+    fn text_elements(&self) -> Vec<tl::NodeHandle> {
+        self.get_all_handles(self.vdom.children(), None).into_iter()
+            .map(|(node_handle, _)| node_handle)
+            .filter(|node_handle| self.draws_text(*node_handle) && self.is_rendered(*node_handle))
+            .collect()
+    }
+
+    /// The characters of an element's text. Offsets of [TextPosition] index these characters.
+    fn text_chars(&self, node_handle: tl::NodeHandle) -> Vec<char> {
+        node_handle.get(self.vdom.parser()).map(|node| node.inner_text(self.vdom.parser()).chars().collect()).unwrap_or_default()
+    }
+
+    /// The characters of the text that an element draws, after its text-transform, each with the offset of the character of its text that it comes from.
+    fn drawn_chars(&self, node_handle: tl::NodeHandle) -> Vec<(char, usize)> {
+        let text = node_handle.get(self.vdom.parser()).map(|node| node.inner_text(self.vdom.parser()).to_string()).unwrap_or_default();
+        let text_transform = self.text_style(node_handle).text_transform;
+        text_transform.apply(text.as_str()).chars().zip(text_transform.source_indices(text.as_str())).collect()
+    }
+
+    /// Positions of the characters of an element's text as [Window::draw] draws them, in physical pixels.
+    fn glyph_positions(&self, node_handle: tl::NodeHandle) -> Vec<font::GlyphPosition> {
+        let (style, layout) = match (self.computed_styles.get(&node_handle), self.computed_layouts.get(&node_handle)) {
+            (Some(style), Some(layout)) => (style, layout),
+            _ => return Vec::new(),
+        };
+        let (font_family, node) = match (style.get_value::<String>("font-family").0, node_handle.get(self.vdom.parser())) {
+            (Some(font_family), Some(node)) => (font_family, node),
+            _ => return Vec::new(),
+        };
+        let x = layout.get::<{LayoutValue::X as usize}>().unwrap_or(0);
+        let y = layout.get::<{LayoutValue::Y as usize}>().unwrap_or(0);
+        let width = layout.get::<{LayoutValue::Width as usize}>().unwrap_or(0);
+        let font_size = layout.get::<{LayoutValue::FontSize as usize}>().unwrap_or(0);
+        let fonts = self.ctx.fonts.borrow();
+        match fonts.find(font_family.as_str()) {
+            Some(font) => font.glyph_positions(&font::TextLayoutParams {
+                text: node.inner_text(self.vdom.parser()).as_ref(),
+                line_height: font_size,
+                x0: x,
+                y0: y,
+                text_style: &self.text_style(node_handle),
+                max_width: Some(width),
+                break_on: &HashSet::from(BREAKABLE),
+            }),
+            None => Vec::new(),
         }
     }
 
+    /// The position in the text that is closest to the given position (in window coordinates), in the element that draws the text at that position.
+    /// None if no text is drawn there.
+    pub fn text_position_at(&self, x: i32, y: i32) -> Option<TextPosition> {
+        let target = self.get_node_handle_at(x, y)?;
+        let node_handle = self.event_path(target).into_iter().rev().find(|node_handle| self.draws_text(*node_handle))?;
+        let (x, y) = ((x as f32 * self.device_pixel_ratio) as i32, (y as f32 * self.device_pixel_ratio) as i32);
+        let glyphs: Vec<font::GlyphPosition> = self.glyph_positions(node_handle).into_iter().filter(|glyph| glyph.index.is_some()).collect();
+        // glyphs index the characters after the text-transform
+        let sources: Vec<usize> = self.drawn_chars(node_handle).into_iter().map(|(_, source)| source).collect();
+        // the line at the position, or the closest line above or below it
+        let line = glyphs.iter().min_by_key(|glyph| (glyph.y - y).max(y - glyph.y - glyph.height + 1).max(0)).map(|glyph| glyph.line);
+        let mut offset = 0;
+        for glyph in glyphs.iter().filter(|glyph| Some(glyph.line) == line) {
+            let index = glyph.index.and_then(|index| sources.get(index).copied()).unwrap_or(0);
+            // a position in the left half of a character is before it, in the right half after it
+            if x < glyph.x + glyph.advance / 2 {
+                offset = index;
+                break;
+            }
+            offset = index + 1;
+        }
+        Some(TextPosition { node: node_handle, offset })
+    }
+
+    /// The selection from its anchor to its focus, None if no text is selected.
+    pub fn selection(&self) -> Option<(TextPosition, TextPosition)> {
+        self.selection
+    }
+
+    /// Selects the text from the anchor to the focus, or removes the selection with None.
+    /// The selection extends over all elements that draw text between the positions in document order.
+    pub fn set_selection(&mut self, selection: Option<(TextPosition, TextPosition)>) {
+        self.selection = selection;
+        self.selecting = false;
+        self.needs_redraw = true;
+    }
+
+    /// Selects all text of the document.
+    pub fn select_all(&mut self) {
+        let text_elements = self.text_elements();
+        let selection = match (text_elements.first(), text_elements.last()) {
+            (Some(first), Some(last)) => Some((TextPosition { node: *first, offset: 0 }, TextPosition { node: *last, offset: self.text_chars(*last).len() })),
+            _ => None,
+        };
+        self.set_selection(selection);
+    }
+
+    /// The selected range of the text of each element, as (element, start, end) in document order. The end may exceed the length of the text.
+    fn selected_ranges(&self) -> Vec<(tl::NodeHandle, usize, usize)> {
+        let (anchor, focus) = match self.selection {
+            Some(selection) => selection,
+            None => return Vec::new(),
+        };
+        let text_elements = self.text_elements();
+        let order = |position: TextPosition| text_elements.iter().position(|node_handle| *node_handle == position.node).map(|index| (index, position.offset));
+        let (start, end) = match (order(anchor), order(focus)) {
+            (Some(anchor), Some(focus)) if anchor <= focus => (anchor, focus),
+            (Some(anchor), Some(focus)) => (focus, anchor),
+            _ => return Vec::new(),
+        };
+        (start.0..=end.0).map(|index| (
+            text_elements[index],
+            if index == start.0 { start.1 } else { 0 },
+            if index == end.0 { end.1 } else { usize::MAX },
+        )).filter(|(_, from, to)| from < to).collect()
+    }
+
+    /// The plain text of the selection. Elements are separated by line breaks, unless both are inline.
+    pub fn selected_text(&self) -> String {
+        let ranges = self.selected_ranges();
+        let mut text = String::new();
+        let mut previous_inline = None;
+        for (node_handle, from, to) in ranges.iter() {
+            // the text of an element is part of the text of its ancestors
+            if ranges.iter().any(|(other, ..)| self.is_ancestor(*other, *node_handle)) {
+                continue;
+            }
+            let inline = self.computed_styles.get(node_handle).and_then(|style| style.get_value::<css::Display>("display").0) == Some(css::Display::Inline);
+            if previous_inline.is_some_and(|previous_inline| !(previous_inline && inline)) && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            previous_inline = Some(inline);
+            // the text is copied as it is drawn
+            text.extend(self.drawn_chars(*node_handle).into_iter().filter(|(_, source)| source >= from && source < to).map(|(c, _)| c));
+        }
+        text
+    }
+
+    /// A value of the ::selection style of an element or of its closest ancestor that sets it.
+    fn selection_value<T: std::str::FromStr>(&self, node_handle: tl::NodeHandle, property: &str) -> Option<T> {
+        self.event_path(node_handle).into_iter().rev()
+            .find_map(|node_handle| self.selection_styles.get(&node_handle).and_then(|style| style.get_raw_value(property)).and_then(|value| value.parse::<T>().ok()))
+    }
+
+    /// Starts a selection at a press of the primary mouse button: a new one, or the current one is extended to the position if shift is held.
+    /// Pressing where no text is drawn removes the selection, pressing on a draggable element keeps it.
+    pub(crate) fn begin_selection(&mut self, x: i32, y: i32, extend: bool) {
+        if self.drag.is_some() {
+            return;
+        }
+        let position = self.text_position_at(x, y);
+        self.selection = match (position, self.selection) {
+            (Some(position), Some((anchor, _))) if extend => Some((anchor, position)),
+            (Some(position), _) => Some((position, position)),
+            (None, _) => None,
+        };
+        self.selecting = position.is_some();
+        self.needs_redraw = true;
+    }
+
+    /// Moves the focus of the selection with the mouse while its button is pressed. Positions where no text is drawn are skipped.
+    pub(crate) fn select_to(&mut self, x: i32, y: i32) {
+        if !self.selecting {
+            return;
+        }
+        if let (Some(position), Some((anchor, focus))) = (self.text_position_at(x, y), self.selection) {
+            if position != focus {
+                self.selection = Some((anchor, position));
+                self.needs_redraw = true;
+            }
+        }
+    }
+
+    /// Stops following the mouse with the selection when its button is released.
+    pub(crate) fn end_selection(&mut self) {
+        self.selecting = false;
+    }
+
+    /// The text on the system clipboard. None if the clipboard is empty or holds no text.
+    pub fn clipboard_text(&self) -> Option<String> {
+        let clipboard = self.ctx.video_subsystem.clipboard();
+        if !clipboard.has_clipboard_text() {
+            return None;
+        }
+        clipboard.clipboard_text().ok().filter(|text| !text.is_empty())
+    }
+
+    /// Puts the text on the system clipboard.
+    pub fn set_clipboard_text(&self, text: &str) -> Result<(), String> {
+        self.ctx.video_subsystem.clipboard().set_clipboard_text(text)
+    }
+
+    /// Whether the text of an element can be edited: a textarea, or an element with contenteditable, that is not disabled.
+    /// Since elements can't be added to the document, only elements whose content is a single text node are editable.
+    fn is_editable(&self, node_handle: tl::NodeHandle) -> bool {
+        let tag = match node_handle.get(self.vdom.parser()).and_then(|node| node.as_tag()) {
+            Some(tag) => tag,
+            None => return false,
+        };
+        let editable = match tag.attributes().get("contenteditable") {
+            Some(value) => !value.is_some_and(|value| value.as_utf8_str().eq_ignore_ascii_case("false")),
+            None => tag.name().as_utf8_str().eq_ignore_ascii_case("textarea"),
+        };
+        let children = tag.children();
+        editable && !tag.attributes().contains("disabled")
+            && matches!(children.top().as_slice(), [child] if matches!(child.get(self.vdom.parser()), Some(tl::Node::Raw(_))))
+    }
+
+    /// Replaces the characters from start to end of the text of an editable element. Returns the offset after the inserted text.
+    fn replace_text(&mut self, node_handle: tl::NodeHandle, start: usize, end: usize, text: &str) -> usize {
+        let child = node_handle.get(self.vdom.parser()).and_then(|node| node.children()).and_then(|children| children.top().get(0).copied());
+        let mut offset = start;
+        if let Some(tl::Node::Raw(bytes)) = child.and_then(|child| child.get_mut(self.vdom.parser_mut())) {
+            let mut chars: Vec<char> = bytes.as_utf8_str().chars().collect();
+            let start = start.min(chars.len());
+            chars.splice(start..end.clamp(start, chars.len()), text.chars());
+            let _ = bytes.set(chars.into_iter().collect::<String>());
+            offset = start + text.chars().count();
+        }
+        self.restyle();
+        offset
+    }
+
+    /// The default action of a key that is pressed with the focus on this window, unless the key down event was cancelled:
+    /// - Ctrl+A (Cmd+A on macOS) selects the text of the focused editable element, or of the whole document
+    /// - Ctrl+C copies the selected text to the clipboard
+    /// - Ctrl+X cuts the selected text of the focused editable element
+    /// - Ctrl+V pastes the clipboard text into the focused editable element, replacing its selected text
+    /// - Shift with the arrow keys, Home or End moves the focus of the selection within its element
+    pub(crate) fn key_default_action(&mut self, keycode: sdl2::keyboard::Keycode, modifiers: sdl2::keyboard::Mod) {
+        use sdl2::keyboard::Keycode;
+        let primary = modifiers.intersects(event::primary_modifier());
+        let shift = modifiers.intersects(sdl2::keyboard::Mod::LSHIFTMOD | sdl2::keyboard::Mod::RSHIFTMOD);
+        let editable = self.focused.filter(|focused| self.is_editable(*focused));
+        // the selected range of the focused editable element, or the end of its text
+        let edited_range = editable.map(|editable| match self.selection {
+            Some((anchor, focus)) if anchor.node == editable && focus.node == editable => (editable, anchor.offset.min(focus.offset), anchor.offset.max(focus.offset)),
+            _ => {
+                let length = self.text_chars(editable).len();
+                (editable, length, length)
+            },
+        });
+        match keycode {
+            Keycode::A if primary => match editable {
+                Some(editable) => {
+                    let length = self.text_chars(editable).len();
+                    self.set_selection(Some((TextPosition { node: editable, offset: 0 }, TextPosition { node: editable, offset: length })));
+                },
+                None => self.select_all(),
+            },
+            Keycode::C if primary => {
+                let text = self.selected_text();
+                if !text.is_empty() {
+                    let _ = self.set_clipboard_text(text.as_str());
+                }
+            },
+            Keycode::X if primary => {
+                if let Some((editable, start, end)) = edited_range.filter(|(_, start, end)| start < end) {
+                    let _ = self.set_clipboard_text(self.selected_text().as_str());
+                    let offset = self.replace_text(editable, start, end, "");
+                    self.set_selection(Some((TextPosition { node: editable, offset }, TextPosition { node: editable, offset })));
+                }
+            },
+            Keycode::V if primary => {
+                if let (Some((editable, start, end)), Some(text)) = (edited_range, self.clipboard_text()) {
+                    let offset = self.replace_text(editable, start, end, text.as_str());
+                    self.set_selection(Some((TextPosition { node: editable, offset }, TextPosition { node: editable, offset })));
+                }
+            },
+            Keycode::Left | Keycode::Right | Keycode::Home | Keycode::End if shift => {
+                if let Some((anchor, focus)) = self.selection {
+                    let length = self.text_chars(focus.node).len();
+                    let offset = match keycode {
+                        Keycode::Left => focus.offset.saturating_sub(1),
+                        Keycode::Right => (focus.offset + 1).min(length),
+                        Keycode::Home => 0,
+                        _ => length,
+                    };
+                    self.set_selection(Some((anchor, TextPosition { node: focus.node, offset })));
+                }
+            },
+            _ => {},
+        }
+    }
+
+    /// Sets an attribute of an element, or removes it if the value is None, and restyles the window.
+    pub fn set_attribute(&mut self, node_handle: tl::NodeHandle, name: &str, value: Option<&str>) {
+        let tag = match node_handle.get_mut(self.vdom.parser_mut()).and_then(|node| node.as_tag_mut()) {
+            Some(tag) => tag,
+            None => return,
+        };
+        // a changed style or link node may add, remove or replace a stylesheet
+        let stylesheet = ["style", "link"].iter().any(|name| tag.name().as_utf8_str().eq_ignore_ascii_case(name));
+        let mut key = tl::Bytes::new();
+        let _ = key.set(name);
+        match value {
+            Some(value) => {
+                let mut bytes = tl::Bytes::new();
+                let _ = bytes.set(value);
+                tag.attributes_mut().insert(key, Some(bytes));
+            },
+            None => {
+                tag.attributes_mut().remove(key);
+            }
+        }
+        // the attribute may belong to an inline <svg> element or one of its shapes
+        self.svg_documents.borrow_mut().clear();
+        self.svg_renderings.clear();
+        if stylesheet {
+            self.styles = self.read_styles();
+            self.load_font_faces(&self.styles);
+        }
+        self.restyle();
+    }
+
+    /// The value of an attribute of an element. None if the element doesn't have the attribute, or if it has no value.
+    pub fn get_attribute(&self, node_handle: tl::NodeHandle, name: &str) -> Option<String> {
+        let tag = node_handle.get(self.vdom.parser())?.as_tag()?;
+        tag.attributes().get(name).flatten().map(|value| value.as_utf8_str().to_string())
+    }
+
     /// Stores the parent node of each node.
     fn compute_parents(&mut self) {
         let all_handles = self.get_all_handles(self.vdom.children().iter(), None);
@@ -287,15 +1464,8 @@ impl Window<'_, '_, '_, '_> {
         }
     }
 
-    /// Fills a quarter of a circle using midpoint circle algorithm.
-    fn fill_quarter_circle(&mut self, x0: i32, y0: i32, r: i32, x_direction: i32, y_direction: i32, color: sdl2::pixels::Color, id_color: sdl2::pixels::Color) -> util::DrawingResult {
-        util::fill_quarter_circle(x0, y0, r, x_direction, y_direction, color, &mut self.sdl_canvas)?;
-        util::fill_quarter_circle(x0, y0, r, x_direction, y_direction, id_color, &mut self.id_canvas)?;
-        Ok(util::DrawingSuccess {})
-    }
-
     /// Draws the background of a node onto the window and the id canvas.
-    fn draw_background(&mut self, node_handle: tl::NodeHandle) -> util::DrawingResult {
+    fn draw_background(&mut self, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, node_handle: tl::NodeHandle) -> util::DrawingResult {
         if let Some(layout) = self.computed_layouts.get(&node_handle) {
             if let Some(style) = self.computed_styles.get(&node_handle) {
                 let (display, _) = style.get_value::<css::Display>("display");
@@ -314,118 +1484,97 @@ impl Window<'_, '_, '_, '_> {
                 let border_left_width = layout.get::<{LayoutValue::BorderLeftWidth as usize}>().unwrap_or(0);
                 let border_bottom_width = layout.get::<{LayoutValue::BorderBottomWidth as usize}>().unwrap_or(0);
                 let border_right_width = layout.get::<{LayoutValue::BorderRightWidth as usize}>().unwrap_or(0);
-                let border_top_left_radius = layout.get::<{LayoutValue::BorderTopLeftRadius as usize}>().unwrap_or(0);
-                let border_top_right_radius = layout.get::<{LayoutValue::BorderTopRightRadius as usize}>().unwrap_or(0);
-                let border_bottom_left_radius = layout.get::<{LayoutValue::BorderBottomLeftRadius as usize}>().unwrap_or(0);
-                let border_bottom_right_radius = layout.get::<{LayoutValue::BorderBottomRightRadius as usize}>().unwrap_or(0);
+                let radius = |x: Option<i32>, y: Option<i32>| util::CornerRadius { x: x.unwrap_or(0) as f32, y: y.or(x).unwrap_or(0) as f32 };
+                let radii = [
+                    radius(layout.get::<{LayoutValue::BorderTopLeftRadius as usize}>(), layout.get::<{LayoutValue::BorderTopLeftRadiusY as usize}>()),
+                    radius(layout.get::<{LayoutValue::BorderTopRightRadius as usize}>(), layout.get::<{LayoutValue::BorderTopRightRadiusY as usize}>()),
+                    radius(layout.get::<{LayoutValue::BorderBottomRightRadius as usize}>(), layout.get::<{LayoutValue::BorderBottomRightRadiusY as usize}>()),
+                    radius(layout.get::<{LayoutValue::BorderBottomLeftRadius as usize}>(), layout.get::<{LayoutValue::BorderBottomLeftRadiusY as usize}>()),
+                ];
 
                 // TODO mask raw layout values (if overflow is hidden or scroll or auto)
         
                 let full_width = width + padding_left + padding_right + border_left_width + border_right_width;
                 let full_height = height + padding_top + padding_bottom + border_top_width + border_bottom_width;
-                let left_x_offset = border_top_left_radius.max(border_bottom_left_radius);
-                let right_x_offset = border_top_right_radius.max(border_bottom_right_radius);
-                let inner_width = full_width - left_x_offset - right_x_offset;
-                let top_y_offset = border_top_left_radius.max(border_top_right_radius);
-                let bottom_y_offset = border_bottom_left_radius.max(border_bottom_right_radius);
-                let inner_height = full_height - top_y_offset - bottom_y_offset;
+                if full_width <= 0 || full_height <= 0 {
+                    return Ok(util::DrawingSuccess {});
+                }
+                let border_box = util::RoundedRect::new(x as f32, y as f32, full_width as f32, full_height as f32, radii);
 
                 let id: u32 = node_handle.get_inner();
                 let id_color = util::pack_id_color(id);
 
                 let (background_color_opt, _) = style.get_value::<css::CssColor>("background-color");
                 let background_color = background_color_opt.unwrap_or(css::CssColor {sdl_color: sdl2::pixels::Color::RGBA(0xFF, 0xFF, 0xFF, 0x0)});
-                self.sdl_canvas.set_draw_color(background_color.sdl_color);
+                let border_styles = Self::border_styles(style);
+                let side = |width: i32, style_index: usize, color_property: &str| util::BorderSide {
+                    width: width as f32,
+                    style: border_styles[style_index],
+                    color: style.get_value::<css::CssColor>(color_property).0.unwrap_or(background_color.clone()).sdl_color,
+                };
+                let sides = [
+                    side(border_top_width, 0, "border-top-color"),
+                    side(border_right_width, 1, "border-right-color"),
+                    side(border_bottom_width, 2, "border-bottom-color"),
+                    side(border_left_width, 3, "border-left-color"),
+                ];
+
+                // Borders and corners are painted with anti-aliasing, the area inside of them is filled directly.
+                let [top_left, top_right, bottom_right, bottom_left] = border_box.radii;
+                let top = (border_top_width as f32).max(top_left.y).max(top_right.y).ceil() as i32;
+                let top = top.min(full_height);
+                let bottom = ((border_bottom_width as f32).max(bottom_left.y).max(bottom_right.y).ceil() as i32).min(full_height - top);
+                let left = ((border_left_width as f32).max(top_left.x).max(bottom_left.x).ceil() as i32).min(full_width);
+                let right = ((border_right_width as f32).max(top_right.x).max(bottom_right.x).ceil() as i32).min(full_width - left);
+                let middle_height = full_height - top - bottom;
+                let edge_regions = [
+                    (x, y, full_width, top),
+                    (x, y + full_height - bottom, full_width, bottom),
+                    (x, y + top, left, middle_height),
+                    (x + full_width - right, y + top, right, middle_height),
+                ];
+                // outer shadows are painted below the background, inset shadows above it, the first shadow on top
+                let shadows = self.shadows(style, "box-shadow", layout.get::<{LayoutValue::FontSize as usize}>().unwrap_or(0));
+                let padding_box = border_box.inset(sides.map(|side| side.width));
+                let visible = sdl2::rect::Rect::new(0, 0, self.width, self.height);
+                for shadow in shadows.iter().rev().filter(|shadow| !shadow.inset) {
+                    if let Some(pixmap) = util::paint_box_shadow(&border_box, &padding_box, shadow, visible) {
+                        util::PixmapTarget::draw_pixmap(canvas, &pixmap)?;
+                    }
+                }
+                let border_path = util::Path::rounded_rect(&border_box);
+                let border_masks: Vec<util::Mask> = edge_regions.into_iter()
+                    .filter(|(_, _, region_width, region_height)| *region_width > 0 && *region_height > 0)
+                    .map(|(region_x, region_y, region_width, region_height)| {
+                        border_path.fill(util::FillRule::NonZero, sdl2::rect::Rect::new(region_x, region_y, region_width as u32, region_height as u32))
+                    })
+                    .collect();
                 self.id_canvas.set_draw_color(id_color);
+                for border_mask in border_masks.iter() {
+                    let mut background = util::Pixmap::new(border_mask.region);
+                    background.fill_mask(border_mask, background_color.sdl_color);
+                    util::PixmapTarget::draw_pixmap(canvas, &background)?;
+                    let hit_points = border_mask.covered_points();
+                    self.id_canvas.draw_points(hit_points.as_slice()).map_err(|msg| DrawingError {msg})?;
+                }
+                if full_width - left - right > 0 && middle_height > 0 {
+                    let inner_rect = sdl2::rect::Rect::new(x + left, y + top, (full_width - left - right) as u32, middle_height as u32);
+                    canvas.set_draw_color(background_color.sdl_color);
+                    canvas.fill_rect(inner_rect).map_err(|msg| DrawingError {msg})?;
+                    self.id_canvas.fill_rect(inner_rect).map_err(|msg| DrawingError {msg})?;
+                }
 
-                let inner_rect = sdl2::rect::Rect::new(x + left_x_offset, y + top_y_offset, inner_width as u32, inner_height as u32);
-                self.sdl_canvas.fill_rect(inner_rect).map_err(|msg| DrawingError {msg})?;
-                self.id_canvas.fill_rect(inner_rect).map_err(|msg| DrawingError {msg})?;
-                let left_rect = sdl2::rect::Rect::new(x, y + top_y_offset, left_x_offset as u32, inner_height as u32);
-                self.sdl_canvas.fill_rect(left_rect).map_err(|msg| DrawingError {msg})?;
-                self.id_canvas.fill_rect(left_rect).map_err(|msg| DrawingError {msg})?;
-                let right_rect = sdl2::rect::Rect::new(x + left_x_offset + inner_width as i32, y + top_y_offset, right_x_offset as u32, inner_height as u32);
-                self.sdl_canvas.fill_rect(right_rect).map_err(|msg| DrawingError {msg})?;
-                self.id_canvas.fill_rect(right_rect).map_err(|msg| DrawingError {msg})?;
-                let top_rect = sdl2::rect::Rect::new(x + left_x_offset, y, inner_width as u32, top_y_offset as u32);
-                self.sdl_canvas.fill_rect(top_rect).map_err(|msg| DrawingError {msg})?;
-                self.id_canvas.fill_rect(top_rect).map_err(|msg| DrawingError {msg})?;
-                let bottom_rect = sdl2::rect::Rect::new(x + left_x_offset, y + top_y_offset + inner_height as i32, inner_width as u32, bottom_y_offset as u32);
-                self.sdl_canvas.fill_rect(bottom_rect).map_err(|msg| DrawingError {msg})?;
-                self.id_canvas.fill_rect(bottom_rect).map_err(|msg| DrawingError {msg})?;
-
-                // TODO figure out how to handle seams between different border colors
-
-                let (border_top_color_opt, _) = style.get_value::<css::CssColor>("border-top-color");
-                let border_top_color = border_top_color_opt.unwrap_or(background_color.clone());
-                let border_top_rect = sdl2::rect::Rect::new(x + left_x_offset, y, inner_width as u32, border_top_width as u32);
-                self.sdl_canvas.set_draw_color(border_top_color.sdl_color);
-                self.sdl_canvas.fill_rect(border_top_rect).map_err(|msg| DrawingError {msg})?;
-                let (border_left_color_opt, _) = style.get_value::<css::CssColor>("border-left-color");
-                let border_left_color = border_left_color_opt.unwrap_or(background_color.clone());
-                let border_left_rect = sdl2::rect::Rect::new(x, y + top_y_offset, border_left_width as u32, inner_height as u32);
-                self.sdl_canvas.set_draw_color(border_left_color.sdl_color);
-                self.sdl_canvas.fill_rect(border_left_rect).map_err(|msg| DrawingError {msg})?;
-                let (border_bottom_color_opt, _) = style.get_value::<css::CssColor>("border-bottom-color");
-                let border_bottom_color = border_bottom_color_opt.unwrap_or(background_color.clone());
-                let border_bottom_rect = sdl2::rect::Rect::new(x + left_x_offset, y + full_height - border_bottom_width as i32, inner_width as u32, border_bottom_width as u32);
-                self.sdl_canvas.set_draw_color(border_bottom_color.sdl_color);
-                self.sdl_canvas.fill_rect(border_bottom_rect).map_err(|msg| DrawingError {msg})?;
-                let (border_right_color_opt, _) = style.get_value::<css::CssColor>("border-right-color");
-                let border_right_color = border_right_color_opt.unwrap_or(background_color.clone());
-                let border_right_rect = sdl2::rect::Rect::new(x + full_width - border_right_width as i32, y + top_y_offset, border_right_width as u32, inner_height as u32);
-                self.sdl_canvas.set_draw_color(border_right_color.sdl_color);
-                self.sdl_canvas.fill_rect(border_right_rect).map_err(|msg| DrawingError {msg})?;
-
-                // TODO how to handle decision between left and top border with?
-                if border_top_left_radius > 0 {
-                    self.fill_quarter_circle(x + left_x_offset, y + top_y_offset, border_top_left_radius, -1, -1, border_top_color.sdl_color, id_color)?;
-                    let inner_top_left_radius = if border_top_left_radius > border_top_width { border_top_left_radius - border_top_width } else { 0 };
-                    self.fill_quarter_circle(x + left_x_offset, y + top_y_offset, inner_top_left_radius, -1, -1, background_color.sdl_color, id_color)?;
-                } else {
-                    let top_left_rect = sdl2::rect::Rect::new(x, y, left_x_offset as u32, top_y_offset as u32);
-                    self.sdl_canvas.fill_rect(top_left_rect).map_err(|msg| DrawingError {msg})?;
-                    self.id_canvas.fill_rect(top_left_rect).map_err(|msg| DrawingError {msg})?;
-                }
-                if border_bottom_left_radius > 0 {
-                    self.fill_quarter_circle(x + left_x_offset, y + top_y_offset + inner_height as i32, border_bottom_left_radius, -1, 1, border_bottom_color.sdl_color, id_color)?;
-                    let inner_bottom_left_radius = if border_bottom_left_radius > border_bottom_width { border_bottom_left_radius - border_bottom_width } else { 0 };
-                    self.fill_quarter_circle(x + left_x_offset, y + top_y_offset + inner_height as i32, inner_bottom_left_radius, -1, 1, background_color.sdl_color, id_color)?;
-                } else {
-                    let bottom_left_rect = sdl2::rect::Rect::new(x, y + top_y_offset + inner_height as i32, left_x_offset as u32, bottom_y_offset as u32);
-                    self.sdl_canvas.fill_rect(bottom_left_rect).map_err(|msg| DrawingError {msg})?;
-                    self.id_canvas.fill_rect(bottom_left_rect).map_err(|msg| DrawingError {msg})?;
-                }
-                if border_top_right_radius > 0 {
-                    self.fill_quarter_circle(x + left_x_offset + inner_width as i32, y + top_y_offset, border_top_right_radius, 1, -1, border_top_color.sdl_color, id_color)?;
-                    let inner_top_right_radius = if border_top_right_radius > border_top_width { border_top_right_radius - border_top_width } else { 0 };
-                    self.fill_quarter_circle(x + left_x_offset + inner_width as i32, y + top_y_offset, inner_top_right_radius, 1, -1, background_color.sdl_color, id_color)?;
-                } else {
-                    let top_right_rect = sdl2::rect::Rect::new(x + left_x_offset + inner_width as i32, y, right_x_offset as u32, top_y_offset as u32);
-                    self.sdl_canvas.fill_rect(top_right_rect).map_err(|msg| DrawingError {msg})?;
-                    self.id_canvas.fill_rect(top_right_rect).map_err(|msg| DrawingError {msg})?;
-                    
-                }
-                if border_bottom_right_radius > 0 {
-                    // draw borders in corner
-                    self.fill_quarter_circle(x + left_x_offset + inner_width as i32, y + top_y_offset + inner_height as i32, border_bottom_right_radius, 1, 1, border_bottom_color.sdl_color, id_color)?;
-                    let inner_bottom_right_radius = if border_bottom_right_radius > border_bottom_width { border_bottom_right_radius - border_bottom_width } else { 0 };
-                    // fill corner background
-                    self.fill_quarter_circle(x + left_x_offset + inner_width as i32, y + top_y_offset + inner_height as i32, inner_bottom_right_radius, 1, 1, background_color.sdl_color, id_color)?;
-                } else {
-                    // fill corner background
-                    self.sdl_canvas.set_draw_color(background_color.sdl_color);
-                    let bottom_right_rect = sdl2::rect::Rect::new(x + left_x_offset + inner_width as i32, y + top_y_offset + inner_height as i32, right_x_offset as u32, bottom_y_offset as u32);
-                    self.sdl_canvas.fill_rect(bottom_right_rect).map_err(|msg| DrawingError {msg})?;
-                    self.id_canvas.fill_rect(bottom_right_rect).map_err(|msg| DrawingError {msg})?;
-                    // draw borders in corner
-                    self.sdl_canvas.set_draw_color(border_bottom_color.sdl_color);
-                    let border_bottom_right_bottom_rect = sdl2::rect::Rect::new(x + left_x_offset + inner_width as i32, y + full_height - border_bottom_width as i32, right_x_offset as u32, border_bottom_width as u32);
-                    self.sdl_canvas.fill_rect(border_bottom_right_bottom_rect).map_err(|msg| DrawingError {msg})?;
-                    self.id_canvas.fill_rect(border_bottom_right_bottom_rect).map_err(|msg| DrawingError {msg})?;
-                    let border_bottom_right_right_rect = sdl2::rect::Rect::new(x + full_width - border_right_width as i32, y + top_y_offset + inner_height as i32, border_right_width as u32, bottom_y_offset as u32);
-                    self.sdl_canvas.fill_rect(border_bottom_right_right_rect).map_err(|msg| DrawingError {msg})?;
-                    self.id_canvas.fill_rect(border_bottom_right_right_rect).map_err(|msg| DrawingError {msg})?;
+                // the background image is painted above the background color and below the border
+                let padding_rect = sdl2::rect::Rect::new(x + border_left_width, y + border_top_width,
+                    (width + padding_left + padding_right).max(0) as u32, (height + padding_top + padding_bottom).max(0) as u32);
+                self.draw_background_images(canvas, node_handle, &border_box, padding_rect)?;
+                for shadow in shadows.iter().rev().filter(|shadow| shadow.inset) {
+                    if let Some(pixmap) = util::paint_box_shadow(&border_box, &padding_box, shadow, visible) {
+                        util::PixmapTarget::draw_pixmap(canvas, &pixmap)?;
+                    }
+                }
+                for border_mask in border_masks.iter() {
+                    util::PixmapTarget::draw_pixmap(canvas, &util::paint_border_box(border_mask, &border_box, &sides))?;
                 }
 
                 return Ok(util::DrawingSuccess {});
@@ -434,9 +1583,293 @@ impl Window<'_, '_, '_, '_> {
         Err(DrawingError {msg: "no layout or style".to_string()})
     }
 
+    /// Draws the layers of an element's background-image, sized and positioned relative to the padding box. The first layer is drawn on top.
+    /// Gradients are clipped to the border box including its rounded corners, url() images to the border box rectangle.
+    fn draw_background_images(&mut self, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, node_handle: tl::NodeHandle, border_box: &util::RoundedRect, padding_rect: sdl2::rect::Rect) -> util::DrawingResult {
+        let style = self.computed_styles.get(&node_handle).ok_or(DrawingError { msg: "missing style".to_string() })?;
+        let font_size = self.computed_layouts.get(&node_handle).and_then(|l| l.get::<{LayoutValue::FontSize as usize}>()).unwrap_or(0);
+        let images: Vec<css::BackgroundImage> = match style.get_raw_value("background-image") {
+            Some(value) => css::split_top_level(value.as_str(), |c| c == ',').into_iter().filter_map(|layer| layer.parse().ok()).collect(),
+            None => return Ok(util::DrawingSuccess {})
+        };
+        // the values of the other properties are repeated if there are less of them than images
+        let layer_value = |property: &str, index: usize| style.get_raw_value(property).and_then(|value| {
+            let layers = css::split_top_level(value.as_str(), |c| c == ',');
+            layers.get(index % layers.len().max(1)).map(|layer| layer.to_string())
+        });
+        let layers: Vec<(css::BackgroundImage, css::BackgroundSize, css::Position, css::BackgroundRepeat)> = images.into_iter().enumerate().map(|(i, image)| (
+            image,
+            layer_value("background-size", i).and_then(|v| v.parse().ok()).unwrap_or(css::BackgroundSize::Size(None, None)),
+            layer_value("background-position", i).and_then(|v| v.parse().ok()).unwrap_or(css::Position { x: (0.0, None), y: (0.0, None) }),
+            layer_value("background-repeat", i).and_then(|v| v.parse().ok()).unwrap_or(css::BackgroundRepeat::Repeat),
+        )).collect();
+
+        let border_rect = sdl2::rect::Rect::new(border_box.x as i32, border_box.y as i32, border_box.width as u32, border_box.height as u32);
+        let mut result = Ok(util::DrawingSuccess {});
+        for (image, size, position, repeat) in layers.into_iter().rev() {
+            let source = image.source(self.device_pixel_ratio).map(|(url, resolution)| (self.resolve_path(url), resolution));
+            let intrinsic_size = match &source {
+                Some((path, resolution)) => match self.image_size(path, *resolution) {
+                    Some((width, height)) if width > 0.0 && height > 0.0 => Some((width, height)),
+                    _ => continue
+                },
+                None => None,
+            };
+            let (tile_width, tile_height) = self.background_tile_size(size, intrinsic_size, padding_rect, font_size);
+            // percentages align the same point of the image and the padding box
+            let origin_x = padding_rect.x() + self.resolve_length(position.x.0, position.x.1, font_size, padding_rect.width() as i32 - tile_width);
+            let origin_y = padding_rect.y() + self.resolve_length(position.y.0, position.y.1, font_size, padding_rect.height() as i32 - tile_height);
+            let tile = sdl2::rect::Rect::new(origin_x, origin_y, tile_width as u32, tile_height as u32);
+            let layer_result = match (&image, source) {
+                (css::BackgroundImage::Gradient(gradient), _) => self.draw_gradient(canvas, gradient, tile, repeat, border_box, font_size),
+                (_, Some((path, _))) => self.draw_image_tiles(canvas, &path, tile, repeat, border_rect),
+                _ => Ok(util::DrawingSuccess {}),
+            };
+            result = result.and(layer_result);
+        }
+        result
+    }
+
+    /// Size of one tile of a background image according to background-size. Images without an intrinsic size (gradients) fill the padding box by default.
+    fn background_tile_size(&self, size: css::BackgroundSize, intrinsic_size: Option<(f32, f32)>, padding_rect: sdl2::rect::Rect, font_size: i32) -> (i32, i32) {
+        let area = (padding_rect.width() as f32, padding_rect.height() as f32);
+        let (width, height) = match (size, intrinsic_size) {
+            (css::BackgroundSize::Cover, Some(intrinsic_size)) => image::object_fit_size(css::ObjectFit::Cover, intrinsic_size, area),
+            (css::BackgroundSize::Contain, Some(intrinsic_size)) => image::object_fit_size(css::ObjectFit::Contain, intrinsic_size, area),
+            (css::BackgroundSize::Cover | css::BackgroundSize::Contain, None) => area,
+            (css::BackgroundSize::Size(width, height), _) => {
+                let resolve = |size: Option<(f32, Option<css::Unit>)>, percent_base: f32| size.map(|(value, unit)| self.resolve_length(value, unit, font_size, percent_base as i32) as f32);
+                // an auto size keeps the image's aspect ratio
+                match (resolve(width, area.0), resolve(height, area.1), intrinsic_size) {
+                    (Some(width), Some(height), _) => (width, height),
+                    (Some(width), None, Some((image_width, image_height))) => (width, width * image_height / image_width),
+                    (None, Some(height), Some((image_width, image_height))) => (height * image_width / image_height, height),
+                    (None, None, Some(intrinsic_size)) => intrinsic_size,
+                    (width, height, None) => (width.unwrap_or(area.0), height.unwrap_or(area.1)),
+                }
+            }
+        };
+        (width.round().max(1.0) as i32, height.round().max(1.0) as i32)
+    }
+
+    /// Draws an image at the tile, repeated to cover the clip rectangle. The image is uploaded once for all of its tiles.
+    fn draw_image_tiles(&mut self, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, path: &std::path::Path, tile: sdl2::rect::Rect, repeat: css::BackgroundRepeat, clip_rect: sdl2::rect::Rect) -> util::DrawingResult {
+        let (repeat_x, repeat_y) = repeat.axes();
+        // tiles start at or before the start of the clip rectangle, so that repeated images cover it completely
+        let tile_positions = |origin: i32, tile: u32, start: i32, end: i32, repeat: bool| -> Vec<i32> {
+            if repeat {
+                (start - (start - origin).rem_euclid(tile as i32)..end).step_by(tile as usize).collect()
+            } else {
+                vec![origin]
+            }
+        };
+        let xs = tile_positions(tile.x(), tile.width(), clip_rect.left(), clip_rect.right(), repeat_x);
+        let ys = tile_positions(tile.y(), tile.height(), clip_rect.top(), clip_rect.bottom(), repeat_y);
+
+        // the tiles are clipped to the painting area within the clip rectangle of the enclosing elements
+        let saved_clip_rect = canvas.clip_rect();
+        let Some(clip_rect) = util::visible_clip_rect(canvas, clip_rect) else {
+            return Ok(util::DrawingSuccess {});
+        };
+        // SVG images are rendered once at the tile size
+        if let Some(mut pixmap) = self.images.render(path, tile.width(), tile.height(), self.device_pixel_ratio).cloned() {
+            let mut result = Ok(util::DrawingSuccess {});
+            canvas.set_clip_rect(clip_rect);
+            for tile_y in ys.iter() {
+                for tile_x in xs.iter() {
+                    (pixmap.x, pixmap.y) = (*tile_x, *tile_y);
+                    result = result.and(util::PixmapTarget::draw_pixmap(canvas, &pixmap));
+                }
+            }
+            canvas.set_clip_rect(saved_clip_rect);
+            return result;
+        }
+        let mut result = Ok(());
+        let texture_creator = canvas.texture_creator();
+        if let Some(image) = self.images.load(path) {
+            let texture = util::create_texture(&texture_creator, image)?;
+            canvas.set_clip_rect(clip_rect);
+            for tile_y in ys.iter() {
+                for tile_x in xs.iter() {
+                    result = result.and(canvas.copy(&texture, None, sdl2::rect::Rect::new(*tile_x, *tile_y, tile.width(), tile.height())));
+                }
+            }
+            canvas.set_clip_rect(saved_clip_rect);
+        }
+        result.map(|_| util::DrawingSuccess {}).map_err(|msg| DrawingError { msg })
+    }
+
+    /// Draws a gradient at the tile, repeated as given and clipped to the border box.
+    fn draw_gradient(&mut self, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, gradient: &css::Gradient, tile: sdl2::rect::Rect, repeat: css::BackgroundRepeat, border_box: &util::RoundedRect, font_size: i32) -> util::DrawingResult {
+        let color_gradient = match self.resolve_gradient(gradient, tile.width() as f32, tile.height() as f32, font_size) {
+            Some(color_gradient) => color_gradient,
+            None => return Ok(util::DrawingSuccess {})
+        };
+        let border_rect = sdl2::rect::Rect::new(border_box.x as i32, border_box.y as i32, border_box.width as u32, border_box.height as u32);
+        // only the visible part is painted
+        let region = match border_rect.intersection(sdl2::rect::Rect::new(0, 0, self.width, self.height)) {
+            Some(region) => region,
+            None => return Ok(util::DrawingSuccess {})
+        };
+        let mask = util::Path::rounded_rect(border_box).fill(util::FillRule::NonZero, region);
+        let mut pixmap = util::Pixmap::new(region);
+        util::paint_gradient(&mut pixmap, &mask, &color_gradient, tile, repeat.axes());
+        util::PixmapTarget::draw_pixmap(canvas, &pixmap)
+    }
+
+    /// Resolves a gradient's geometry and stop positions for a box of the given size.
+    fn resolve_gradient(&self, gradient: &css::Gradient, width: f32, height: f32, font_size: i32) -> Option<util::ColorGradient> {
+        let length = |(value, unit): (f32, Option<css::Unit>), percent_base: f32| match unit {
+            Some(css::Unit::Percent) => value * percent_base / 100.0,
+            _ => self.resolve_length(value, unit, font_size, 0) as f32,
+        };
+        let center = |position: &css::Position| (length(position.x, width), length(position.y, height));
+        let shape = match &gradient.kind {
+            css::GradientKind::Linear(direction) => {
+                let (dx, dy) = match *direction {
+                    css::GradientDirection::Angle(degrees) => (degrees.to_radians().sin(), -degrees.to_radians().cos()),
+                    // towards a corner, the gradient line is perpendicular to the diagonal between the other two corners
+                    css::GradientDirection::To(x, y) if x != 0 && y != 0 => (x as f32 * height, y as f32 * width),
+                    css::GradientDirection::To(x, y) => (x as f32, y as f32),
+                };
+                let norm = (dx * dx + dy * dy).sqrt();
+                if norm <= 0.0 {
+                    return None;
+                }
+                let (dx, dy) = (dx / norm, dy / norm);
+                // the gradient line is long enough for the corners to have the first and last color
+                let half_length = ((width * dx).abs() + (height * dy).abs()) / 2.0;
+                let (cx, cy) = (width / 2.0, height / 2.0);
+                util::GradientShape::Linear { start: (cx - dx * half_length, cy - dy * half_length), end: (cx + dx * half_length, cy + dy * half_length) }
+            },
+            css::GradientKind::Radial { shape, size, position } => {
+                let (cx, cy) = center(position);
+                let sides = |closest: bool| {
+                    let (horizontal, vertical) = (cx.abs().min((width - cx).abs()), cy.abs().min((height - cy).abs()));
+                    if closest { (horizontal, vertical) } else { (cx.abs().max((width - cx).abs()), cy.abs().max((height - cy).abs())) }
+                };
+                let circle = *shape == css::RadialShape::Circle;
+                let radii = match *size {
+                    css::RadialSize::ClosestSide | css::RadialSize::FarthestSide => {
+                        let closest = *size == css::RadialSize::ClosestSide;
+                        let (x, y) = sides(closest);
+                        if !circle { (x, y) } else if closest { (x.min(y), x.min(y)) } else { (x.max(y), x.max(y)) }
+                    },
+                    css::RadialSize::ClosestCorner | css::RadialSize::FarthestCorner => {
+                        let (x, y) = sides(*size == css::RadialSize::ClosestCorner);
+                        // an ellipse keeps the aspect ratio of the sides and passes through the corner
+                        if circle { (x.hypot(y), x.hypot(y)) } else { (x * std::f32::consts::SQRT_2, y * std::f32::consts::SQRT_2) }
+                    },
+                    css::RadialSize::Radii(x, y) => {
+                        let x = length(x, width);
+                        (x, y.map_or(x, |y| length(y, height)))
+                    },
+                };
+                util::GradientShape::Radial { center: (cx, cy), radii }
+            },
+            css::GradientKind::Conic { from, position } => util::GradientShape::Conic { center: center(position), angle: from.to_radians() },
+        };
+        let line_length = shape.line_length();
+        let stops: Vec<(Option<sdl2::pixels::Color>, Option<f32>)> = gradient.stops.iter().map(|stop| (
+            stop.color.as_ref().map(|color| color.sdl_color),
+            stop.position.map(|(value, unit)| match unit {
+                Some(css::Unit::Percent) => value / 100.0,
+                _ if line_length > 0.0 => length((value, unit), 0.0) / line_length,
+                _ => 0.0,
+            }),
+        )).collect();
+        util::ColorGradient::new(shape, &stops, gradient.repeating)
+    }
+
+    /// Draws the image of an <img> element into its content box, sized by object-fit and positioned by object-position.
+    fn draw_image(&mut self, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, node_handle: tl::NodeHandle, path: &std::path::Path) -> util::DrawingResult {
+        let (image_width, image_height) = match self.image_size(path, 1.0) {
+            Some(size) => size,
+            None => return Ok(util::DrawingSuccess {})
+        };
+        let layout = self.computed_layouts.get(&node_handle).ok_or(DrawingError { msg: "missing layout".to_string() })?;
+        let style = self.computed_styles.get(&node_handle).ok_or(DrawingError { msg: "missing style".to_string() })?;
+        let x = layout.get::<{LayoutValue::X as usize}>().ok_or(DrawingError { msg: "missing x".to_string() })?;
+        let y = layout.get::<{LayoutValue::Y as usize}>().ok_or(DrawingError { msg: "missing y".to_string() })?;
+        let width = layout.get::<{LayoutValue::Width as usize}>().ok_or(DrawingError { msg: "missing width".to_string() })?;
+        let height = layout.get::<{LayoutValue::Height as usize}>().ok_or(DrawingError { msg: "missing height".to_string() })?;
+        if width <= 0 || height <= 0 {
+            return Ok(util::DrawingSuccess {});
+        }
+        let content_x = x + layout.get::<{LayoutValue::BorderLeftWidth as usize}>().unwrap_or(0) + layout.get::<{LayoutValue::PaddingLeft as usize}>().unwrap_or(0);
+        let content_y = y + layout.get::<{LayoutValue::BorderTopWidth as usize}>().unwrap_or(0) + layout.get::<{LayoutValue::PaddingTop as usize}>().unwrap_or(0);
+        let font_size = layout.get::<{LayoutValue::FontSize as usize}>().unwrap_or(0);
+        let fit = style.get_raw_value("object-fit").and_then(|v| v.parse::<css::ObjectFit>().ok()).unwrap_or(css::ObjectFit::Fill);
+        let position = style.get_raw_value("object-position").and_then(|v| v.parse::<css::Position>().ok()).unwrap_or_default();
+
+        let (fit_width, fit_height) = image::object_fit_size(fit, (image_width, image_height), (width as f32, height as f32));
+        let (fit_width, fit_height) = (fit_width.round() as i32, fit_height.round() as i32);
+        let image_x = content_x + self.resolve_length(position.x.0, position.x.1, font_size, width - fit_width);
+        let image_y = content_y + self.resolve_length(position.y.0, position.y.1, font_size, height - fit_height);
+        if fit_width <= 0 || fit_height <= 0 {
+            return Ok(util::DrawingSuccess {});
+        }
+        let content_rect = sdl2::rect::Rect::new(content_x, content_y, width as u32, height as u32);
+        let image_rect = sdl2::rect::Rect::new(image_x, image_y, fit_width as u32, fit_height as u32);
+        let saved_clip_rect = canvas.clip_rect();
+        let Some(content_rect) = util::visible_clip_rect(canvas, content_rect) else {
+            return Ok(util::DrawingSuccess {});
+        };
+        if let Some(mut pixmap) = self.images.render(path, image_rect.width(), image_rect.height(), self.device_pixel_ratio).cloned() {
+            (pixmap.x, pixmap.y) = (image_rect.x(), image_rect.y());
+            canvas.set_clip_rect(content_rect);
+            let result = util::PixmapTarget::draw_pixmap(canvas, &pixmap);
+            canvas.set_clip_rect(saved_clip_rect);
+            return result;
+        }
+        let mut result = Ok(());
+        let texture_creator = canvas.texture_creator();
+        if let Some(image) = self.images.load(path) {
+            let texture = util::create_texture(&texture_creator, image)?;
+            // cover and none may overflow the content box
+            canvas.set_clip_rect(content_rect);
+            result = canvas.copy(&texture, None, image_rect);
+            canvas.set_clip_rect(saved_clip_rect);
+        }
+        result.map(|_| util::DrawingSuccess {}).map_err(|msg| DrawingError { msg })
+    }
+
+    /// Draws an inline <svg> element into its content box, with currentColor being the element's color.
+    fn draw_svg(&mut self, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, node_handle: tl::NodeHandle, document: &svg::Document) -> util::DrawingResult {
+        let layout = self.computed_layouts.get(&node_handle).ok_or(DrawingError { msg: "missing layout".to_string() })?;
+        let x = layout.get::<{LayoutValue::X as usize}>().ok_or(DrawingError { msg: "missing x".to_string() })?;
+        let y = layout.get::<{LayoutValue::Y as usize}>().ok_or(DrawingError { msg: "missing y".to_string() })?;
+        let width = layout.get::<{LayoutValue::Width as usize}>().ok_or(DrawingError { msg: "missing width".to_string() })?;
+        let height = layout.get::<{LayoutValue::Height as usize}>().ok_or(DrawingError { msg: "missing height".to_string() })?;
+        if width <= 0 || height <= 0 {
+            return Ok(util::DrawingSuccess {});
+        }
+        let content_x = x + layout.get::<{LayoutValue::BorderLeftWidth as usize}>().unwrap_or(0) + layout.get::<{LayoutValue::PaddingLeft as usize}>().unwrap_or(0);
+        let content_y = y + layout.get::<{LayoutValue::BorderTopWidth as usize}>().unwrap_or(0) + layout.get::<{LayoutValue::PaddingTop as usize}>().unwrap_or(0);
+        let current_color = self.computed_styles.get(&node_handle).and_then(|style| style.get_value::<css::CssColor>("color").0)
+            .map_or(sdl2::pixels::Color::RGB(0, 0, 0), |color| color.sdl_color);
+        let region = sdl2::rect::Rect::new(content_x, content_y, width as u32, height as u32);
+        let rendered = self.svg_renderings.get(&node_handle).is_some_and(|(rendered_region, color, _)| *rendered_region == region && *color == current_color);
+        if !rendered {
+            let pixmap = document.render(region, current_color, self.device_pixel_ratio);
+            self.svg_renderings.insert(node_handle, (region, current_color, pixmap));
+        }
+        match self.svg_renderings.get(&node_handle) {
+            Some((_, _, pixmap)) => util::PixmapTarget::draw_pixmap(canvas, pixmap),
+            None => Ok(util::DrawingSuccess {}),
+        }
+    }
+
     /// Draws a node into this window's canvas. The node has to be part of this window's vdom.
-    fn draw_element(&mut self, node_handle: &tl::NodeHandle) -> util::DrawingResult {
-        self.draw_background(node_handle.clone())?;
+    /// The selected range of its text is highlighted with the colors of its ::selection style.
+    fn draw_element(&mut self, canvas: &mut sdl2::render::Canvas<sdl2::video::Window>, node_handle: &tl::NodeHandle, selected: Option<(usize, usize)>) -> util::DrawingResult {
+        self.draw_background(canvas, node_handle.clone())?;
+        if let Some(path) = self.image_source(*node_handle) {
+            self.draw_image(canvas, *node_handle, &path)?;
+        }
+        if let Some(document) = self.svg_document(*node_handle) {
+            return self.draw_svg(canvas, *node_handle, &document);
+        }
         if let Some(node) = node_handle.get(self.vdom.parser()) {
             if let Some(style) = self.computed_styles.get(node_handle) {
                 if let Some(layout) = self.computed_layouts.get(node_handle) {
@@ -444,7 +1877,7 @@ impl Window<'_, '_, '_, '_> {
                     let x = layout.get::<{LayoutValue::X as usize}>().ok_or(DrawingError{msg: "missing x".to_string()})?;
                     let y = layout.get::<{LayoutValue::Y as usize}>().ok_or(DrawingError{msg: "missing y".to_string()})?;
                     let width = layout.get::<{LayoutValue::Width as usize}>().ok_or(DrawingError{msg: "missing width".to_string()})?;
-                    //let height = layout.get::<{LayoutValue::Height as usize}>().ok_or(DrawingError{msg: "missing height".to_string()})?;
+                    let height = layout.get::<{LayoutValue::Height as usize}>().ok_or(DrawingError{msg: "missing height".to_string()})?;
                     let font_size = layout.get::<{LayoutValue::FontSize as usize}>().ok_or(DrawingError{msg: "missing font size".to_string()})?;
                     // Draw text
                     let text = node.inner_text(self.vdom.parser()).to_string();
@@ -453,14 +1886,54 @@ impl Window<'_, '_, '_, '_> {
                     if font_family_opt.is_some() && font_color_opt.is_some() {
                         let font_family = font_family_opt.unwrap();
                         let font_color = font_color_opt.unwrap();
-                        self.sdl_canvas.set_draw_color(font_color.sdl_color);
-                        for font_name in font_family.split(',') {
-                            let font_name = font_name.trim();
-                            if let Some(font) = self.ctx.fonts.get(font_name.to_lowercase().as_str()) {
-                                let breakable = HashSet::from_iter(vec![' ', '\n', '\t', '\r', '\u{00A0}'].into_iter());
-                                font.render_text(text.as_str(), font_size, font_size, x, y, Some(width), &breakable, Some(&mut self.sdl_canvas));
-                                break;
+                        let text_style = self.text_style(*node_handle);
+                        canvas.set_draw_color(font_color.sdl_color);
+                        let fonts = self.ctx.fonts.borrow();
+                        if let Some(font) = fonts.find(font_family.as_str()) {
+                            let breakable = HashSet::from(BREAKABLE);
+                            // text that overflows the element is cut off, unless overflow is visible
+                            let (overflow, _) = style.get_value::<css::Overflow>("overflow");
+                            let saved_clip_rect = canvas.clip_rect();
+                            let clip = if overflow.is_some() && overflow != Some(css::Overflow::Visible) {
+                                match util::visible_clip_rect(canvas, sdl2::rect::Rect::new(x, y, width.max(0) as u32, height.max(0) as u32)) {
+                                    Some(clip) => Some(clip),
+                                    None => return Ok(util::DrawingSuccess {}),
+                                }
+                            } else {
+                                saved_clip_rect
+                            };
+                            canvas.set_clip_rect(clip);
+                            let params = font::TextLayoutParams { text: text.as_str(), line_height: font_size, x0: x, y0: y, text_style: &text_style, max_width: Some(width), break_on: &breakable };
+                            // one rectangle per line around the selected characters
+                            let mut selection_rects: Vec<sdl2::rect::Rect> = Vec::new();
+                            // the selected characters after the text-transform
+                            let mut drawn_range = 0..0;
+                            if let Some((start, end)) = selected {
+                                let glyphs = font.glyph_positions(&params);
+                                let sources = text_style.text_transform.source_indices(text.as_str());
+                                drawn_range = sources.partition_point(|source| *source < start)..sources.partition_point(|source| *source < end);
+                                for glyph in glyphs.iter().filter(|glyph| glyph.index.is_some_and(|index| drawn_range.contains(&index))) {
+                                    let rect = sdl2::rect::Rect::new(glyph.x, glyph.y, glyph.advance.max(0) as u32, glyph.height.max(0) as u32);
+                                    match selection_rects.last_mut() {
+                                        Some(last) if last.y() == glyph.y => *last = last.union(rect),
+                                        _ => selection_rects.push(rect),
+                                    }
+                                }
+                            }
+                            let selection_color = self.selection_value::<css::CssColor>(*node_handle, "color");
+                            if !selection_rects.is_empty() {
+                                let background = self.selection_value::<css::CssColor>(*node_handle, "background-color").map_or(SELECTION_BACKGROUND, |color| color.sdl_color);
+                                canvas.set_draw_color(background);
+                                let _ = canvas.fill_rects(&selection_rects);
+                                canvas.set_draw_color(font_color.sdl_color);
                             }
+                            font.render_text(text.as_str(), font_size, font_size, x, y, &text_style, Some(width), &breakable, Some(&mut *canvas));
+                            // the selected characters are drawn again in the selection color
+                            if let Some(selection_color) = selection_color.filter(|_| !selection_rects.is_empty()) {
+                                canvas.set_draw_color(selection_color.sdl_color);
+                                font.render_text_range(&params, drawn_range, canvas);
+                            }
+                            canvas.set_clip_rect(saved_clip_rect);
                         }
                     }
                     return Ok(util::DrawingSuccess {});
@@ -478,19 +1951,7 @@ impl Window<'_, '_, '_, '_> {
     /// 
     /// Returns [Option< i32 >]: Pixel value.
     fn calc_size_top_down<const WHICH: usize>(&self, value: Option<f32>, unit: Option<css::Unit>, node_handle: tl::NodeHandle) -> Option<i32> {
-        // magic numbers from https://developer.mozilla.org/en-US/docs/Learn/CSS/Building_blocks/Values_and_units
         match unit {
-            Some(css::Unit::Px) => Some(value.unwrap_or(0.0) as i32),
-            Some(css::Unit::Vw) => Some((value.unwrap_or(0.0) * (self.width as f32) / 100.0) as i32),
-            Some(css::Unit::Vh) => Some((value.unwrap_or(0.0) * (self.height as f32) / 100.0) as i32),
-            Some(css::Unit::Vmin) => Some((value.unwrap_or(0.0) * (self.width.min(self.height) as f32) / 100.0) as i32),
-            Some(css::Unit::Vmax) => Some((value.unwrap_or(0.0) * (self.width.max(self.height) as f32) / 100.0) as i32),
-            Some(css::Unit::Cm) => Some((value.unwrap_or(0.0) * 37.79527559055118) as i32),
-            Some(css::Unit::Mm) => Some((value.unwrap_or(0.0) * 3.779527559055118) as i32),
-            Some(css::Unit::Q) => Some((value.unwrap_or(0.0) * 0.9448818897637795) as i32),
-            Some(css::Unit::In) => Some((value.unwrap_or(0.0) * 96.0) as i32),
-            Some(css::Unit::Pt) => Some((value.unwrap_or(0.0) * 1.3333333333333333) as i32),
-            Some(css::Unit::Pc) => Some((value.unwrap_or(0.0) * 16.0) as i32),
             Some(css::Unit::Percent) => match LayoutValue::from(WHICH) {
                 // padding, border, margin are calculated relative to the element's width
                 LayoutValue::PaddingBottom | LayoutValue::PaddingLeft | LayoutValue::PaddingRight | LayoutValue::PaddingTop |
@@ -499,6 +1960,11 @@ impl Window<'_, '_, '_, '_> {
                  => self.computed_layouts.get(&node_handle)
                     .map_or(None, |l| l.get::<{LayoutValue::Width as usize}>())
                     .map_or(None, |w| Some((value.unwrap_or(0.0) * (w as f32) / 100.0) as i32)),
+                // vertical radii of elliptical corners are calculated relative to the element's height
+                LayoutValue::BorderTopLeftRadiusY | LayoutValue::BorderTopRightRadiusY | LayoutValue::BorderBottomLeftRadiusY | LayoutValue::BorderBottomRightRadiusY
+                 => self.computed_layouts.get(&node_handle)
+                    .and_then(|l| l.get::<{LayoutValue::Height as usize}>())
+                    .map(|h| (value.unwrap_or(0.0) * (h as f32) / 100.0) as i32),
                 // other relative properties are calculated relative to the same property on the parent
                 _ => match self.computed_parents.get(&node_handle).map_or(None, |v| v.as_ref()) {
                     Some(p) => self.computed_layouts.get(&p)
@@ -524,10 +1990,157 @@ impl Window<'_, '_, '_, '_> {
                     .map_or(None, |l: &NodeLayoutInfo| l.get::<{LayoutValue::FontSize as usize}>())
                     .map_or(None, |v| Some((value.unwrap_or(0.0) * (v as f32)) as i32))
             },
+            Some(unit) => self.absolute_length(value.unwrap_or(0.0), &unit),
+            None => None
+        }
+    }
+
+    /// Calculates the physical pixel value for units that do not depend on an element (px, viewport units, physical units).
+    /// Returns None for relative units (%, em, fr).
+    fn absolute_length(&self, value: f32, unit: &css::Unit) -> Option<i32> {
+        // magic numbers from https://developer.mozilla.org/en-US/docs/Learn/CSS/Building_blocks/Values_and_units
+        let ratio = self.device_pixel_ratio;
+        match unit {
+            css::Unit::Px => Some((value * ratio) as i32),
+            css::Unit::Vw => Some((value * (self.width as f32) / 100.0) as i32),
+            css::Unit::Vh => Some((value * (self.height as f32) / 100.0) as i32),
+            css::Unit::Vmin => Some((value * (self.width.min(self.height) as f32) / 100.0) as i32),
+            css::Unit::Vmax => Some((value * (self.width.max(self.height) as f32) / 100.0) as i32),
+            css::Unit::Cm => Some((value * 37.79527559055118 * ratio) as i32),
+            css::Unit::Mm => Some((value * 3.779527559055118 * ratio) as i32),
+            css::Unit::Q => Some((value * 0.9448818897637795 * ratio) as i32),
+            css::Unit::In => Some((value * 96.0 * ratio) as i32),
+            css::Unit::Pt => Some((value * 1.3333333333333333 * ratio) as i32),
+            css::Unit::Pc => Some((value * 16.0 * ratio) as i32),
             _ => None
         }
     }
 
+    /// Calculates the pixel value of a length that is not part of the layout (i.e. letter-spacing).
+    /// - font_size [i32]: Font size used for em.
+    /// - percent_base [i32]: Length used for %.
+    fn resolve_length(&self, value: f32, unit: Option<css::Unit>, font_size: i32, percent_base: i32) -> i32 {
+        match unit {
+            Some(css::Unit::Em) => (value * font_size as f32) as i32,
+            Some(css::Unit::Percent) => (value * percent_base as f32 / 100.0) as i32,
+            Some(unit) => self.absolute_length(value, &unit).unwrap_or(0),
+            // unitless lengths are css pixels
+            None => (value * self.device_pixel_ratio) as i32
+        }
+    }
+
+    /// Collects the text properties of an element (font selection, alignment, spacing, transformation, decoration).
+    fn text_style(&self, node_handle: tl::NodeHandle) -> font::TextStyle {
+        let mut text_style = font::TextStyle::default();
+        if let Some(style) = self.computed_styles.get(&node_handle) {
+            let layout = self.computed_layouts.get(&node_handle);
+            let font_size = layout.and_then(|l| l.get::<{LayoutValue::FontSize as usize}>()).unwrap_or(0);
+            let width = layout.and_then(|l| l.get::<{LayoutValue::Width as usize}>()).unwrap_or(0);
+            text_style.query = font::FontQuery::from_style(style);
+            if let Some(direction) = style.get_value::<css::Direction>("direction").0 {
+                text_style.direction = direction;
+            }
+            if let Some(unicode_bidi) = style.get_value::<css::UnicodeBidi>("unicode-bidi").0 {
+                text_style.unicode_bidi = unicode_bidi;
+            }
+            if let Some(text_align) = style.get_value::<css::TextAlign>("text-align").0 {
+                text_style.text_align = text_align;
+            }
+            if let Some(text_transform) = style.get_value::<css::TextTransform>("text-transform").0 {
+                text_style.text_transform = text_transform;
+            }
+            if let Some(white_space) = style.get_value::<css::WhiteSpace>("white-space").0 {
+                text_style.white_space = white_space;
+            }
+            // text-overflow and line clamping only apply to elements that hide their overflow
+            let overflow = style.get_value::<css::Overflow>("overflow").0.unwrap_or(css::Overflow::Visible);
+            if overflow != css::Overflow::Visible {
+                if let Some(text_overflow) = style.get_value::<css::TextOverflow>("text-overflow").0 {
+                    text_style.text_overflow = text_overflow;
+                }
+                text_style.line_clamp = style.get_value::<usize>("-webkit-line-clamp").0
+                    .or(style.get_value::<usize>("line-clamp").0);
+            }
+            if let (Some(value), unit) = style.get_value::<f32>("letter-spacing") {
+                text_style.letter_spacing = self.resolve_length(value, unit, font_size, font_size);
+            }
+            if let (Some(value), unit) = style.get_value::<f32>("word-spacing") {
+                text_style.word_spacing = self.resolve_length(value, unit, font_size, font_size);
+            }
+            if let (Some(value), unit) = style.get_value::<f32>("text-indent") {
+                text_style.text_indent = self.resolve_length(value, unit, font_size, width);
+            }
+            // the shorthand is applied first, longhands override it
+            if let Some(decoration) = style.get_raw_value("text-decoration").and_then(|v| v.parse::<css::TextDecoration>().ok()) {
+                text_style.decoration_line = decoration.line;
+                text_style.decoration_color = decoration.color.map(|c| c.sdl_color);
+                text_style.decoration_thickness = decoration.thickness.map(|(value, unit)| self.resolve_length(value, unit, font_size, font_size));
+            }
+            if let Some(line) = style.get_value::<css::TextDecorationLine>("text-decoration-line").0 {
+                text_style.decoration_line = line;
+            }
+            if let Some(color) = style.get_value::<css::CssColor>("text-decoration-color").0 {
+                text_style.decoration_color = Some(color.sdl_color);
+            }
+            if let (Some(value), unit) = style.get_value::<f32>("text-decoration-thickness") {
+                text_style.decoration_thickness = Some(self.resolve_length(value, unit, font_size, font_size));
+            }
+            text_style.shadows = self.shadows(style, "text-shadow", font_size);
+        }
+        text_style
+    }
+
+    /// Resolves the shadows of box-shadow or text-shadow. Shadows without a color use the element's color.
+    fn shadows(&self, style: &css::ComputedStyle, property: &str, font_size: i32) -> Vec<util::Shadow> {
+        let current_color = style.get_value::<css::CssColor>("color").0
+            .map_or(sdl2::pixels::Color::RGB(0, 0, 0), |c| c.sdl_color);
+        let length = |(value, unit): (f32, Option<css::Unit>)| self.resolve_length(value, unit, font_size, 0) as f32;
+        style.get_raw_value(property).map(|value| css::parse_shadows(value.as_str())).unwrap_or_default().into_iter()
+            .map(|shadow| util::Shadow {
+                offset_x: length(shadow.offset_x),
+                offset_y: length(shadow.offset_y),
+                blur: length(shadow.blur),
+                spread: length(shadow.spread),
+                color: shadow.color.map_or(current_color, |c| c.sdl_color),
+                inset: shadow.inset,
+            })
+            .collect()
+    }
+
+    /// The resolved filters of an element, empty if it has no style.
+    fn element_filters(&self, node_handle: tl::NodeHandle, property: &str) -> Vec<util::Filter> {
+        let font_size = self.computed_layouts.get(&node_handle).and_then(|layout| layout.get::<{LayoutValue::FontSize as usize}>()).unwrap_or(0);
+        self.computed_styles.get(&node_handle).map(|style| self.filters(style, property, font_size)).unwrap_or_default()
+    }
+
+    /// Resolves the filter functions of a filter property (`filter` or `backdrop-filter`) of an element.
+    fn filters(&self, style: &css::ComputedStyle, property: &str, font_size: i32) -> Vec<util::Filter> {
+        let current_color = style.get_value::<css::CssColor>("color").0
+            .map_or(sdl2::pixels::Color::RGB(0, 0, 0), |c| c.sdl_color);
+        let length = |(value, unit): (f32, Option<css::Unit>)| self.resolve_length(value, unit, font_size, 0) as f32;
+        style.get_raw_value(property).map(|value| css::parse_filters(value.as_str())).unwrap_or_default().into_iter()
+            .map(|filter| match filter {
+                css::Filter::Blur(radius) => util::Filter::Blur(length(radius)),
+                css::Filter::Brightness(amount) => util::Filter::Brightness(amount),
+                css::Filter::Contrast(amount) => util::Filter::Contrast(amount),
+                css::Filter::Grayscale(amount) => util::Filter::Grayscale(amount),
+                css::Filter::HueRotate(angle) => util::Filter::HueRotate(angle),
+                css::Filter::Invert(amount) => util::Filter::Invert(amount),
+                css::Filter::Opacity(amount) => util::Filter::Opacity(amount),
+                css::Filter::Saturate(amount) => util::Filter::Saturate(amount),
+                css::Filter::Sepia(amount) => util::Filter::Sepia(amount),
+                css::Filter::DropShadow(shadow) => util::Filter::DropShadow(util::Shadow {
+                    offset_x: length(shadow.offset_x),
+                    offset_y: length(shadow.offset_y),
+                    blur: length(shadow.blur),
+                    spread: 0.0,
+                    color: shadow.color.map_or(current_color, |c| c.sdl_color),
+                    inset: false,
+                }),
+            })
+            .collect()
+    }
+
     /// Sets a layout value using self.calc_size_top_down. If the value is already set, it will not be overwritten.
     /// - property [&str]: CSS property name.
     /// - node_handle [&tl::NodeHandle]: Node handle.
@@ -556,16 +2169,64 @@ impl Window<'_, '_, '_, '_> {
         self.set_size_value_top_down::<{LayoutValue::PaddingLeft as usize}>(node_handle);
     }
 
-    /// Attemps to calculate an element's border widths.
+    /// Sets the horizontal and vertical radius of a border corner using self.calc_size_top_down. If the values are already set, they will not be overwritten.
+    /// The property has one value for circular corners, or two values (horizontal and vertical radius) for elliptical corners.
+    fn set_radius_values_top_down<const WHICH_X: usize, const WHICH_Y: usize>(&mut self, node_handle: tl::NodeHandle) {
+        let is_set = self.computed_layouts.get(&node_handle).is_some_and(|l| l.is_set::<{WHICH_X}>() && l.is_set::<{WHICH_Y}>());
+        if is_set {
+            return;
+        }
+        let radii: Vec<(f32, Option<css::Unit>)> = self.computed_styles.get(&node_handle)
+            .and_then(|s| s.get_raw_value(LayoutValue::from(WHICH_X).to_string().as_str()))
+            .map_or(Vec::new(), |v| v.split_whitespace().filter_map(css::parse_dimension).collect());
+        let ((x_value, x_unit), (y_value, y_unit)) = match radii.as_slice() {
+            [radius] => (*radius, *radius),
+            [x_radius, y_radius] => (*x_radius, *y_radius),
+            _ => return
+        };
+        let x = self.calc_size_top_down::<{WHICH_X}>(Some(x_value), x_unit, node_handle);
+        let y = self.calc_size_top_down::<{WHICH_Y}>(Some(y_value), y_unit, node_handle);
+        if let Some(layout_mut) = self.computed_layouts.get_mut(&node_handle) {
+            layout_mut.set::<{WHICH_X}>(x);
+            layout_mut.set::<{WHICH_Y}>(y);
+        }
+    }
+
+    /// Returns the border style of each side (top, right, bottom, left). The border-style shorthand is applied first, longhands override it.
+    /// Sides without a border style are solid.
+    fn border_styles(style: &css::ComputedStyle) -> [css::BorderStyle; 4] {
+        let mut styles = style.get_raw_value("border-style")
+            .map(|v| v.split_whitespace().filter_map(|part| part.parse::<css::BorderStyle>().ok()).collect::<Vec<css::BorderStyle>>())
+            .and_then(|parts| css::expand_sides(&parts))
+            .unwrap_or([css::BorderStyle::Solid; 4]);
+        for (i, property) in ["border-top-style", "border-right-style", "border-bottom-style", "border-left-style"].iter().enumerate() {
+            if let Some(side_style) = style.get_value::<css::BorderStyle>(property).0 {
+                styles[i] = side_style;
+            }
+        }
+        styles
+    }
+
+    /// Attemps to calculate an element's border widths and radii.
     fn layout_element_border(&mut self, node_handle: tl::NodeHandle) {
+        // sides without a visible border style have no width
+        if let Some(styles) = self.computed_styles.get(&node_handle).map(Self::border_styles) {
+            if let Some(layout_mut) = self.computed_layouts.get_mut(&node_handle) {
+                let hidden = |i: usize| if styles[i] == css::BorderStyle::None || styles[i] == css::BorderStyle::Hidden { Some(0) } else { None };
+                if let Some(width) = hidden(0) { layout_mut.set::<{LayoutValue::BorderTopWidth as usize}>(Some(width)); }
+                if let Some(width) = hidden(1) { layout_mut.set::<{LayoutValue::BorderRightWidth as usize}>(Some(width)); }
+                if let Some(width) = hidden(2) { layout_mut.set::<{LayoutValue::BorderBottomWidth as usize}>(Some(width)); }
+                if let Some(width) = hidden(3) { layout_mut.set::<{LayoutValue::BorderLeftWidth as usize}>(Some(width)); }
+            }
+        }
         self.set_size_value_top_down::<{LayoutValue::BorderTopWidth as usize}>(node_handle);
         self.set_size_value_top_down::<{LayoutValue::BorderRightWidth as usize}>(node_handle);
         self.set_size_value_top_down::<{LayoutValue::BorderBottomWidth as usize}>(node_handle);
         self.set_size_value_top_down::<{LayoutValue::BorderLeftWidth as usize}>(node_handle);
-        self.set_size_value_top_down::<{LayoutValue::BorderTopLeftRadius as usize}>(node_handle);
-        self.set_size_value_top_down::<{LayoutValue::BorderTopRightRadius as usize}>(node_handle);
-        self.set_size_value_top_down::<{LayoutValue::BorderBottomRightRadius as usize}>(node_handle);
-        self.set_size_value_top_down::<{LayoutValue::BorderBottomLeftRadius as usize}>(node_handle);
+        self.set_radius_values_top_down::<{LayoutValue::BorderTopLeftRadius as usize}, {LayoutValue::BorderTopLeftRadiusY as usize}>(node_handle);
+        self.set_radius_values_top_down::<{LayoutValue::BorderTopRightRadius as usize}, {LayoutValue::BorderTopRightRadiusY as usize}>(node_handle);
+        self.set_radius_values_top_down::<{LayoutValue::BorderBottomRightRadius as usize}, {LayoutValue::BorderBottomRightRadiusY as usize}>(node_handle);
+        self.set_radius_values_top_down::<{LayoutValue::BorderBottomLeftRadius as usize}, {LayoutValue::BorderBottomLeftRadiusY as usize}>(node_handle);
     }
 
     /// Attempts to calculate an element's margin values.
@@ -590,6 +2251,11 @@ impl Window<'_, '_, '_, '_> {
                     self.set_size_value_top_down::<{LayoutValue::Width as usize}>(node_handle);
                     self.set_size_value_top_down::<{LayoutValue::Height as usize}>(node_handle);
                 },
+                // images are replaced elements, their width and height apply even if they are inline
+                css::Display::Inline if self.image_source(node_handle).is_some() || self.svg_document(node_handle).is_some() => {
+                    self.set_size_value_top_down::<{LayoutValue::Width as usize}>(node_handle);
+                    self.set_size_value_top_down::<{LayoutValue::Height as usize}>(node_handle);
+                },
                 // TODO implement other display types (flex, grid)
                 _ => {}
             }   
@@ -597,8 +2263,26 @@ impl Window<'_, '_, '_, '_> {
     }
     
     /// Determines width and height if they are based on the content width and height.
+    /// Images and inline SVGs use their intrinsic size instead, keeping the aspect ratio if only one of width and height is set.
     fn layout_content_based_width_and_height(&mut self, node_handle: tl::NodeHandle) {
+        let intrinsic_size = self.intrinsic_size(node_handle);
         if let Some(layout_mut) = self.computed_layouts.get_mut(&node_handle) {
+            if let Some((intrinsic_width, intrinsic_height)) = intrinsic_size {
+                let (intrinsic_width, intrinsic_height) = (intrinsic_width as i32, intrinsic_height as i32);
+                match (layout_mut.get::<{LayoutValue::Width as usize}>(), layout_mut.get::<{LayoutValue::Height as usize}>()) {
+                    (None, None) => {
+                        layout_mut.set::<{LayoutValue::Width as usize}>(Some(intrinsic_width));
+                        layout_mut.set::<{LayoutValue::Height as usize}>(Some(intrinsic_height));
+                    },
+                    (Some(width), None) if intrinsic_width > 0 => {
+                        layout_mut.set::<{LayoutValue::Height as usize}>(Some(width * intrinsic_height / intrinsic_width));
+                    },
+                    (None, Some(height)) if intrinsic_height > 0 => {
+                        layout_mut.set::<{LayoutValue::Width as usize}>(Some(height * intrinsic_width / intrinsic_height));
+                    },
+                    _ => {}
+                }
+            }
             if layout_mut.get::<{LayoutValue::Width as usize}>().is_none() {
                 let content_width = layout_mut.get::<{LayoutValue::ContentWidth as usize}>().unwrap_or(0);
                 layout_mut.set::<{LayoutValue::Width as usize}>(Some(content_width));
@@ -619,11 +2303,13 @@ impl Window<'_, '_, '_, '_> {
                 }
                 if let Some(parent_handle) = self.computed_parents.get(&node_handle).map_or(None, |v| v.as_ref()) {
                     let font_size = self.computed_layouts.get(&parent_handle).map_or(None, |l| l.get::<{LayoutValue::FontSize as usize}>()).unwrap_or(0);
-                    if let Some(font) = self.computed_styles.get(&parent_handle)
-                        .map_or(None, |s| s.get_value::<String>("font-family").0)
-                        .map_or(None, |f| f.split(',').find_map(|font_name| self.ctx.fonts.get(font_name.to_lowercase().as_str()))) {
+                    let text_style = self.text_style(*parent_handle);
+                    let fonts = self.ctx.fonts.borrow();
+                    if let Some(font) = self.computed_styles.get(parent_handle)
+                        .and_then(|s| s.get_value::<String>("font-family").0)
+                        .and_then(|f| fonts.find(f.as_str())) {
                         if let Some(parent_layout_mut) = self.computed_layouts.get_mut(&parent_handle) {
-                            font.text_layout(bytes.as_utf8_str().trim().as_ref(), font_size, parent_layout_mut);
+                            font.text_layout(bytes.as_utf8_str().trim().as_ref(), font_size, &text_style, parent_layout_mut);
                         }
                     }
                 }
@@ -655,11 +2341,13 @@ impl Window<'_, '_, '_, '_> {
                 }
                 let mut x = 0;
                 let mut y = 0;
+                let mut line_index = None;
                 if let Some(parent_layout_mut) = self.computed_parents.get(&node_handle).map_or(None, |v| v.as_ref())
                     .map_or(None, |p| self.computed_layouts.get_mut(p)) {
                     match display {
                         Some(css::Display::Inline) | Some(css::Display::InlineBlock) => {
                             (x, y) = parent_layout_mut.reverse_flow_inline(own_width.unwrap_or(0), own_height.unwrap_or(0));
+                            line_index = Some(parent_layout_mut.reverse_line_index() as i32);
                         },
                         Some(css::Display::Block) => {
                             (x, y) = parent_layout_mut.reverse_flow_block(own_width.unwrap_or(0), own_height.unwrap_or(0));
@@ -671,6 +2359,7 @@ impl Window<'_, '_, '_, '_> {
                 if let Some(layout_mut) = self.computed_layouts.get_mut(&node_handle) {
                     layout_mut.set::<{LayoutValue::X as usize}>(Some(x));
                     layout_mut.set::<{LayoutValue::Y as usize}>(Some(y));
+                    layout_mut.set::<{LayoutValue::ReverseLineIndex as usize}>(line_index);
                 }
             },
             _ => {}
@@ -699,6 +2388,28 @@ impl Window<'_, '_, '_, '_> {
         }
         //----TO HERE-----
 
+        // Inline children are placed within their line in the parent's direction, blocks are aligned to the start edge
+        let parent_handle = self.computed_parents.get(&node_handle).map_or(None, |v| v.as_ref());
+        let rtl = parent_handle.and_then(|p| self.computed_styles.get(p))
+            .and_then(|s| s.get_value::<css::Direction>("direction").0) == Some(css::Direction::Rtl);
+        if let Some(parent_layout) = parent_handle.and_then(|p| self.computed_layouts.get(p)) {
+            let parent_width = parent_layout.get::<{LayoutValue::Width as usize}>()
+                .or(parent_layout.get::<{LayoutValue::ContentWidth as usize}>()).unwrap_or(0);
+            if let Some(layout) = self.computed_layouts.get(&node_handle) {
+                let own_x = layout.get::<{LayoutValue::X as usize}>().unwrap_or(0);
+                let own_width = layout.get::<{LayoutValue::Width as usize}>().unwrap_or(0);
+                let x = match layout.get::<{LayoutValue::ReverseLineIndex as usize}>() {
+                    Some(line_index) => Some(parent_layout.resolve_inline_x(own_x, own_width, line_index as usize, rtl, parent_width)),
+                    None if rtl && self.computed_styles.get(&node_handle)
+                        .and_then(|s| s.get_value::<css::Display>("display").0) == Some(css::Display::Block) => Some(parent_width - own_width),
+                    None => None,
+                };
+                if let (Some(x), Some(layout_mut)) = (x, self.computed_layouts.get_mut(&node_handle)) {
+                    layout_mut.set::<{LayoutValue::X as usize}>(Some(x));
+                }
+            }
+        }
+
         // Now convert from relative to absolute (X and Y)
         let mut parent_x = 0;
         let mut parent_y = 0;
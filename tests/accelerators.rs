@@ -0,0 +1,67 @@
+mod common;
+
+#[test]
+fn accelerators() {
+    use std::rc::Rc;
+    use sdl2::keyboard::{Keycode, Mod};
+    use yargl::event::{Accelerator, AcceleratorScope, EventReceiver, EventReturnCode};
+    use common::*;
+
+    let html = r##"<html><head><style>
+        html, body { display: block; width: 200px; }
+        div { display: block; width: 200px; height: 40px; }
+        #inner { height: 20px; }
+    </style></head><body><div id="outer"><div id="inner" tabindex="0"></div></div><div id="other" tabindex="0"></div></body></html>"##;
+    let ctx = Rc::new(yargl::context::init().unwrap());
+    let mut window = open_window(&ctx, html, 200, 100);
+    let (outer, inner, other) = (element_by_id(&window, "outer"), element_by_id(&window, "inner"), element_by_id(&window, "other"));
+    let log: Log = Default::default();
+    let save: Accelerator = "Primary+S".parse().unwrap();
+    // an accelerator that handles the key returns Cancel
+    let record = |name: &'static str, handles: bool| {
+        let log = log.clone();
+        move |_: &mut yargl::window::Window| {
+            log.borrow_mut().push(name.to_string());
+            if handles { EventReturnCode::Cancel } else { EventReturnCode::Continue }
+        }
+    };
+    let global = window.accelerators.add(save, AcceleratorScope::Global, record("global", true)).unwrap();
+    window.accelerators.add(save, AcceleratorScope::FocusWithin(outer), record("outer", true)).unwrap();
+    window.accelerators.add("F5".parse().unwrap(), AcceleratorScope::Global, record("f5", false)).unwrap();
+    {
+        let log = log.clone();
+        window.key_down_events.add_listener(move |event, _| {
+            log.borrow_mut().push(format!("keydown {}", event.0.data.keycode.map(|keycode| keycode.name()).unwrap_or_default()));
+            EventReturnCode::Continue
+        });
+    }
+
+    // an accelerator conflicts with one of the same key, modifiers and scope, but not with one of another scope
+    let conflict = window.accelerators.add(save, AcceleratorScope::Global, record("conflict", true)).err().unwrap();
+    assert_eq!(conflict.existing, global);
+    assert!(window.accelerators.add(save, AcceleratorScope::FocusWithin(other), record("other", true)).is_ok());
+
+    let window_id = window.sdl_window().id();
+    let mut windows = vec![window];
+    let press = |windows: &mut Vec<yargl::window::Window>, keycode: Keycode, keymod: Mod| {
+        push_events(&ctx, vec![key_down(window_id, keycode, keymod)]);
+        ctx.poll_events(windows);
+        take(&log)
+    };
+    let primary = yargl::event::primary_modifier();
+    // without focus the global accelerator handles the key, which then doesn't reach the key down listeners
+    assert_eq!(press(&mut windows, Keycode::S, primary), vec!["global"]);
+    // an accelerator that returns Continue passes the key on
+    assert_eq!(press(&mut windows, Keycode::F5, Mod::NOMOD), vec!["f5", "keydown F5"]);
+    // other modifiers don't match
+    assert_eq!(press(&mut windows, Keycode::S, primary | Mod::LSHIFTMOD), vec!["keydown S"]);
+    // the accelerator of the innermost scope around the focus takes precedence
+    windows[0].focus(Some(inner));
+    assert_eq!(press(&mut windows, Keycode::S, primary), vec!["outer"]);
+    windows[0].focus(Some(other));
+    assert_eq!(press(&mut windows, Keycode::S, primary), vec!["other"]);
+    // a removed accelerator is not triggered anymore
+    windows[0].focus(None);
+    assert!(windows[0].accelerators.remove(global));
+    assert_eq!(press(&mut windows, Keycode::S, primary), vec!["keydown S"]);
+}
@@ -0,0 +1,101 @@
+//! Helpers for the behaviour tests, which drive windows with synthesized SDL events.
+//! Each test file runs in its own process with a single test, since SDL can only be initialized once at a time.
+#![allow(dead_code)]
+
+use std::{cell::RefCell, rc::Rc};
+use sdl2::event::{Event, WindowEvent};
+
+/// Opens a window with the html and draws it, so that elements can be hit by pointer events.
+pub fn open_window<'a>(ctx: &Rc<yargl::context::Context>, html: &'a str, width: u32, height: u32) -> yargl::window::Window<'a, 'a> {
+    let mut window = yargl::window::Window::new(ctx.clone(), &yargl::window::WindowCreationOptions { title: "Test".to_string(), width, height }, html, None).unwrap();
+    window.draw();
+    window
+}
+
+/// Pushes events onto the SDL event queue, they are dispatched by the next [yargl::context::Context::poll_events].
+pub fn push_events(ctx: &yargl::context::Context, events: Vec<Event>) {
+    let event_subsystem = ctx.video_subsystem.sdl().event().unwrap();
+    for event in events {
+        event_subsystem.push_event(event).unwrap();
+    }
+}
+
+/// Pushes raw SDL events onto the event queue, for the events that [push_events] can't convert (i.e. touch events).
+pub fn push_raw_events(events: Vec<sdl2::sys::SDL_Event>) {
+    for mut event in events {
+        // SAFETY: the event is initialized for its type, SDL copies it into the queue
+        assert_eq!(unsafe { sdl2::sys::SDL_PushEvent(&mut event) }, 1);
+    }
+}
+
+/// A touch (FingerDown), move (FingerMotion) or lift (FingerUp) of a finger, at a position normalized to the window size.
+pub fn finger(kind: sdl2::sys::SDL_EventType, finger_id: i64, x: f32, y: f32) -> sdl2::sys::SDL_Event {
+    sdl2::sys::SDL_Event { tfinger: sdl2::sys::SDL_TouchFingerEvent {
+        type_: kind as u32, timestamp: 0, touchId: 1, fingerId: finger_id, x, y, dx: 0.0, dy: 0.0, pressure: 1.0, windowID: 0,
+    } }
+}
+
+/// A rotation and pinch of several fingers around a position normalized to the window size.
+pub fn multi_gesture(x: f32, y: f32, rotation: f32, pinch: f32, num_fingers: u16) -> sdl2::sys::SDL_Event {
+    sdl2::sys::SDL_Event { mgesture: sdl2::sys::SDL_MultiGestureEvent {
+        type_: sdl2::sys::SDL_EventType::SDL_MULTIGESTURE as u32, timestamp: 0, touchId: 1, dTheta: rotation, dDist: pinch, x, y, numFingers: num_fingers, padding: 0,
+    } }
+}
+
+pub fn mouse_motion(window_id: u32, x: i32, y: i32) -> Event {
+    Event::MouseMotion { timestamp: 0, window_id, which: 0, mousestate: sdl2::mouse::MouseState::from_sdl_state(0), x, y, xrel: 0, yrel: 0 }
+}
+
+/// A press (down) or release of the left mouse button. clicks is 2 for the second click of a double click.
+pub fn mouse_button(window_id: u32, down: bool, clicks: u8, x: i32, y: i32) -> Event {
+    let mouse_btn = sdl2::mouse::MouseButton::Left;
+    match down {
+        true => Event::MouseButtonDown { timestamp: 0, window_id, which: 0, mouse_btn, clicks, x, y },
+        false => Event::MouseButtonUp { timestamp: 0, window_id, which: 0, mouse_btn, clicks, x, y },
+    }
+}
+
+pub fn key_down(window_id: u32, keycode: sdl2::keyboard::Keycode, keymod: sdl2::keyboard::Mod) -> Event {
+    Event::KeyDown { timestamp: 0, window_id, keycode: Some(keycode), scancode: sdl2::keyboard::Scancode::from_keycode(keycode), keymod, repeat: false }
+}
+
+pub fn window_event(window_id: u32, win_event: WindowEvent) -> Event {
+    Event::Window { timestamp: 0, window_id, win_event }
+}
+
+/// The id attribute of the element, or an empty string.
+pub fn element_id(window: &yargl::window::Window, node_handle: Option<tl::NodeHandle>) -> String {
+    node_handle.and_then(|node_handle| window.get_attribute(node_handle, "id")).unwrap_or_default()
+}
+
+/// The element with the id attribute, found by hit testing the window. Panics if the element is not drawn.
+pub fn element_by_id(window: &yargl::window::Window, id: &str) -> tl::NodeHandle {
+    let (width, height) = window.sdl_window().size();
+    (0..height as i32).flat_map(|y| (0..width as i32).map(move |x| (x, y)))
+        .filter_map(|(x, y)| window.get_node_handle_at(x, y))
+        .find(|node_handle| window.get_attribute(*node_handle, "id").as_deref() == Some(id))
+        .unwrap()
+}
+
+/// Entries that listeners record, in the order they were called.
+pub type Log = Rc<RefCell<Vec<String>>>;
+
+/// A listener that appends the entry formatted by `entry` to the log and lets the event continue.
+pub fn record_with<T>(log: &Log, entry: impl Fn(&yargl::event::Event<T>, &yargl::window::Window) -> String + 'static)
+    -> impl FnMut(&yargl::event::Event<T>, &mut yargl::window::Window) -> yargl::event::EventReturnCode + 'static {
+    let log = log.clone();
+    move |event, window| {
+        log.borrow_mut().push(entry(event, window));
+        yargl::event::EventReturnCode::Continue
+    }
+}
+
+/// A listener that logs the name and the id of the event's target, i.e. "click button".
+pub fn record<T>(log: &Log, name: &'static str) -> impl FnMut(&yargl::event::Event<T>, &mut yargl::window::Window) -> yargl::event::EventReturnCode + 'static {
+    record_with(log, move |event, window| format!("{} {}", name, element_id(window, event.target())))
+}
+
+/// Takes the recorded entries out of the log.
+pub fn take(log: &Log) -> Vec<String> {
+    log.borrow_mut().drain(..).collect()
+}
@@ -0,0 +1,50 @@
+mod common;
+
+#[test]
+fn drag_and_drop() {
+    use std::{cell::Cell, rc::Rc};
+    use yargl::event::{DragEndEvent, Event, EventReceiver, EventReturnCode};
+    use common::*;
+
+    let html = r##"<html><head><style>
+        html, body { display: block; width: 200px; height: 200px; }
+        div { display: block; width: 200px; height: 50px; }
+    </style></head><body><div id="source" draggable="true"></div><div id="target"></div></body></html>"##;
+    let ctx = Rc::new(yargl::context::init().unwrap());
+    let mut window = open_window(&ctx, html, 200, 200);
+    let log: Log = Default::default();
+    // whether the target accepts the drop by cancelling the drag over event
+    let accept = Rc::new(Cell::new(false));
+    window.drag_start_events.add_listener(record(&log, "dragstart"));
+    window.drag_over_events.add_listener(record(&log, "dragover"));
+    {
+        let accept = accept.clone();
+        window.drag_over_events.add_listener(move |event, window| {
+            if accept.get() && element_id(window, event.target()) == "target" { EventReturnCode::Cancel } else { EventReturnCode::Continue }
+        });
+    }
+    window.drop_events.add_listener(record(&log, "drop"));
+    window.drag_end_events.add_listener(record_with(&log, |event: &Event<DragEndEvent>, window| format!("dragend {} {}", element_id(window, event.target()), event.0.dropped)));
+    window.pointer_move_events.add_listener(record(&log, "move"));
+    window.click_events.add_listener(record(&log, "click"));
+    let window_id = window.sdl_window().id();
+    let mut windows = vec![window];
+
+    // the drag starts once the pointer moved past the threshold, drag over events then replace the pointer move events
+    push_events(&ctx, vec![mouse_button(window_id, true, 1, 10, 10), mouse_motion(window_id, 12, 12)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(take(&log), vec!["move source"]);
+    push_events(&ctx, vec![mouse_motion(window_id, 10, 30), mouse_motion(window_id, 10, 70)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(take(&log), vec!["dragstart source", "dragover source", "dragover target"]);
+    // without a cancelled drag over event the target doesn't accept the drop, and the release is not a click
+    push_events(&ctx, vec![mouse_button(window_id, false, 1, 10, 70)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(take(&log), vec!["dragend source false"]);
+
+    // a cancelled drag over event allows the drop
+    accept.set(true);
+    push_events(&ctx, vec![mouse_button(window_id, true, 1, 10, 10), mouse_motion(window_id, 10, 70), mouse_button(window_id, false, 1, 10, 70)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(take(&log), vec!["dragstart source", "dragover target", "drop target", "dragend source true"]);
+}
@@ -0,0 +1,77 @@
+mod common;
+
+#[test]
+fn event_dispatch() {
+    use std::{cell::Cell, rc::Rc};
+    use yargl::event::{dispatch, Event, EventReceiver, EventReturnCode, ListenerId, ScrollEvent};
+    use common::*;
+
+    let html = r##"<html><head><style>
+        html, body { display: block; width: 200px; height: 100px; }
+        #outer { display: block; width: 200px; height: 100px; }
+        #inner { display: block; width: 100px; height: 50px; }
+    </style></head><body><div id="outer"><div id="inner"></div></div></body></html>"##;
+    let ctx = Rc::new(yargl::context::init().unwrap());
+    let mut window = open_window(&ctx, html, 200, 100);
+    let (outer, inner) = (element_by_id(&window, "outer"), element_by_id(&window, "inner"));
+    let log: Log = Default::default();
+    // what the first listener of the target does
+    let mode = Rc::new(Cell::new(""));
+    let second: Rc<Cell<Option<ListenerId>>> = Default::default();
+    let record = |name: &'static str| {
+        let log = log.clone();
+        move |event: &Event<ScrollEvent>, _: &mut yargl::window::Window| {
+            log.borrow_mut().push(format!("{} {:?}", name, event.phase()));
+            EventReturnCode::Continue
+        }
+    };
+    window.scroll_events.add_element_listener(outer, record("outer"), true);
+    window.scroll_events.add_element_listener(outer, record("outer"), false);
+    {
+        let (log, mode, second) = (log.clone(), mode.clone(), second.clone());
+        window.scroll_events.add_element_listener(inner, move |event, window| {
+            log.borrow_mut().push(format!("first {:?}", event.phase()));
+            match mode.get() {
+                "stop" => event.stop_propagation(),
+                "remove" => { window.scroll_events.remove_listener(second.get().unwrap()); },
+                _ => {},
+            }
+            EventReturnCode::Continue
+        }, false);
+    }
+    {
+        let (log, mode) = (log.clone(), mode.clone());
+        second.set(Some(window.scroll_events.add_element_listener(inner, move |event, _| {
+            log.borrow_mut().push(format!("second {:?}", event.phase()));
+            if mode.get() == "cancel" { EventReturnCode::Cancel } else { EventReturnCode::Continue }
+        }, false)));
+    }
+    window.scroll_events.add_listener(record("window"));
+    let scroll = |bubbles: bool| {
+        let payload = ScrollEvent { x: 0, y: 1, timestamp: 0 };
+        if bubbles { Event::new(payload, Some(inner)) } else { Event::new_non_bubbling(payload, Some(inner)) }
+    };
+
+    // capturing listeners of the ancestors, the listeners of the target, bubbling listeners of the ancestors and then the window's listeners
+    let event = scroll(true);
+    assert!(dispatch(&mut window, |window| &window.scroll_events, &event) == EventReturnCode::Continue);
+    assert_eq!(take(&log), vec!["outer Capturing", "first AtTarget", "second AtTarget", "outer Bubbling", "window Bubbling"]);
+    assert!(event.phase() == yargl::event::EventPhase::None && event.current_target().is_none());
+    // an event that doesn't bubble only reaches the capturing listeners of the ancestors and the target
+    dispatch(&mut window, |window| &window.scroll_events, &scroll(false));
+    assert_eq!(take(&log), vec!["outer Capturing", "first AtTarget", "second AtTarget"]);
+    // stopping the propagation still calls the remaining listeners of the current element
+    mode.set("stop");
+    dispatch(&mut window, |window| &window.scroll_events, &scroll(true));
+    assert_eq!(take(&log), vec!["outer Capturing", "first AtTarget", "second AtTarget"]);
+    // a cancelling listener prevents the default action and stops the propagation
+    mode.set("cancel");
+    let event = scroll(true);
+    assert!(dispatch(&mut window, |window| &window.scroll_events, &event) == EventReturnCode::Cancel);
+    assert!(event.default_prevented());
+    assert_eq!(take(&log), vec!["outer Capturing", "first AtTarget", "second AtTarget"]);
+    // a listener that is removed during the dispatch is not called anymore
+    mode.set("remove");
+    dispatch(&mut window, |window| &window.scroll_events, &scroll(true));
+    assert_eq!(take(&log), vec!["outer Capturing", "first AtTarget", "outer Bubbling", "window Bubbling"]);
+}
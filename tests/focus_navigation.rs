@@ -0,0 +1,57 @@
+mod common;
+
+#[test]
+fn focus_navigation() {
+    use std::rc::Rc;
+    use sdl2::keyboard::{Keycode, Mod};
+    use yargl::event::{EventReceiver, EventReturnCode};
+    use common::*;
+
+    let html = r##"<html><head><style>
+        html, body { display: block; }
+        button, a, div, input { display: block; width: 40px; height: 20px; }
+        #hidden { display: none; }
+        #invisible { visibility: hidden; }
+    </style></head><body>
+        <button id="first"></button>
+        <a id="no-href">x</a>
+        <a id="link" href="#">y</a>
+        <div id="tabindex-2" tabindex="2"></div>
+        <div id="tabindex-1" tabindex="1"></div>
+        <input id="negative" tabindex="-1">
+        <input id="hidden">
+        <button id="invisible"></button>
+        <button id="disabled" disabled></button>
+        <div id="tabindex-0" tabindex="0"></div>
+    </body></html>"##;
+    let ctx = Rc::new(yargl::context::init().unwrap());
+    let mut window = open_window(&ctx, html, 200, 400);
+
+    // elements with a positive tabindex come first in ascending order, then the others in document order
+    let order: Vec<String> = window.tab_order().into_iter().map(|node_handle| element_id(&window, Some(node_handle))).collect();
+    assert_eq!(order, vec!["tabindex-1", "tabindex-2", "first", "link", "tabindex-0"]);
+
+    let log: Log = Default::default();
+    // focus events don't bubble, they are dispatched to the focused element only
+    for node_handle in window.tab_order() {
+        let log = log.clone();
+        window.focus_events.add_element_listener(node_handle, move |event, window| {
+            log.borrow_mut().push(element_id(window, event.target()));
+            EventReturnCode::Continue
+        }, false);
+    }
+    let window_id = window.sdl_window().id();
+    let mut windows = vec![window];
+    let mut tab = |backwards: bool| {
+        let keymod = if backwards { Mod::LSHIFTMOD } else { Mod::NOMOD };
+        push_events(&ctx, vec![key_down(window_id, Keycode::Tab, keymod)]);
+        ctx.poll_events(&mut windows);
+        element_id(&windows[0], windows[0].focused())
+    };
+    // Tab moves along the order and wraps around at the end, Shift+Tab goes back
+    let forwards: Vec<String> = (0..6).map(|_| tab(false)).collect();
+    assert_eq!(forwards, vec!["tabindex-1", "tabindex-2", "first", "link", "tabindex-0", "tabindex-1"]);
+    assert_eq!(tab(true), "tabindex-0");
+    assert_eq!(tab(true), "link");
+    assert_eq!(take(&log), vec!["tabindex-1", "tabindex-2", "first", "link", "tabindex-0", "tabindex-1", "tabindex-0", "link"]);
+}
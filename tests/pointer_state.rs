@@ -0,0 +1,64 @@
+mod common;
+
+#[test]
+fn pointer_state() {
+    use std::rc::Rc;
+    use yargl::event::EventReceiver;
+    use common::*;
+
+    let html = r##"<html><head><style>
+        html, body { display: block; width: 200px; height: 200px; }
+        #outer { display: block; width: 200px; height: 100px; }
+        #outer:hover { background-color: #0000ff; }
+        #inner { display: block; width: 50px; height: 50px; }
+        #inner:active { background-color: #ff0000; }
+    </style></head><body><div id="outer"><button id="inner"></button></div></body></html>"##;
+    let ctx = Rc::new(yargl::context::init().unwrap());
+    let mut window = open_window(&ctx, html, 200, 200);
+    let log: Log = Default::default();
+    let inner = window.get_node_handle_at(10, 10).unwrap();
+    let outer = window.get_node_handle_at(100, 80).unwrap();
+    window.pointer_out_events.add_listener(record(&log, "out"));
+    window.pointer_over_events.add_listener(record(&log, "over"));
+    // enter and leave events don't bubble, they are dispatched to each element that is entered or left
+    for element in [outer, inner] {
+        window.pointer_leave_events.add_element_listener(element, record_with(&log, |event, window| format!("leave {}", element_id(window, event.current_target()))), false);
+        window.pointer_enter_events.add_element_listener(element, record_with(&log, |event, window| format!("enter {}", element_id(window, event.current_target()))), false);
+    }
+    window.click_events.add_listener(record(&log, "click"));
+    window.double_click_events.add_listener(record(&log, "dblclick"));
+    let window_id = window.sdl_window().id();
+    let mut windows = vec![window];
+
+    // boundary events: out and leave of the previous elements before over and enter of the new ones,
+    // elements that contain both positions are neither left nor entered
+    push_events(&ctx, vec![mouse_motion(window_id, 100, 80)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(take(&log), vec!["over outer", "enter outer"]);
+    push_events(&ctx, vec![mouse_motion(window_id, 10, 10)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(take(&log), vec!["out outer", "over inner", "enter inner"]);
+    push_events(&ctx, vec![mouse_motion(window_id, 20, 20)]);
+    ctx.poll_events(&mut windows);
+    assert!(take(&log).is_empty());
+    push_events(&ctx, vec![mouse_motion(window_id, 100, 80)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(take(&log), vec!["out inner", "leave inner", "over outer"]);
+    push_events(&ctx, vec![window_event(window_id, sdl2::event::WindowEvent::Leave)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(take(&log), vec!["out outer", "leave outer"]);
+
+    // a click needs the pointer to go down and up on the same element, the second click of a double click also dispatches a double click
+    push_events(&ctx, vec![mouse_motion(window_id, 10, 10), mouse_button(window_id, true, 1, 10, 10), mouse_button(window_id, false, 1, 10, 10)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(take(&log), vec!["over inner", "enter outer", "enter inner", "click inner"]);
+    push_events(&ctx, vec![mouse_button(window_id, true, 2, 10, 10), mouse_button(window_id, false, 2, 10, 10)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(take(&log), vec!["click inner", "dblclick inner"]);
+    push_events(&ctx, vec![mouse_button(window_id, true, 1, 10, 10), mouse_button(window_id, false, 1, 100, 80)]);
+    ctx.poll_events(&mut windows);
+    assert!(!take(&log).iter().any(|entry| entry.starts_with("click")));
+
+    // the pointer down focuses the button
+    assert_eq!(element_id(&windows[0], windows[0].focused()), "inner");
+}
@@ -0,0 +1,64 @@
+mod common;
+
+#[test]
+fn selection() {
+    use std::rc::Rc;
+    use sdl2::keyboard::{Keycode, Mod};
+    use yargl::window::TextPosition;
+    use common::*;
+
+    let html = r##"<html><head><style>
+        html, body { display: block; width: 300px; }
+        p, textarea { display: block; width: 300px; height: 24px; font-family: arial; font-size: 20px; color: #000000; }
+        div { display: block; width: 300px; height: 24px; }
+        span { display: inline; font-family: arial; font-size: 20px; color: #000000; }
+        .upper { text-transform: uppercase; }
+    </style></head><body>
+        <p id="first">Hello world</p><p id="second">Second line</p>
+        <div><span id="left">ab</span><span id="right">cd</span></div>
+        <p id="upper" class="upper">Straße</p>
+        <textarea id="editable" class="upper">aßb</textarea>
+    </body></html>"##;
+    let ctx = Rc::new(yargl::context::init().unwrap());
+    ctx.load_font_file("arial", "res/font/arial.ttf").unwrap();
+    let mut window = open_window(&ctx, html, 320, 160);
+    let position = |window: &yargl::window::Window, id: &str, offset: usize| TextPosition { node: element_by_id(window, id), offset };
+
+    // a selection extends over the elements between its anchor and focus, in either direction
+    let (anchor, focus) = (position(&window, "first", 6), position(&window, "second", 6));
+    window.set_selection(Some((anchor, focus)));
+    assert_eq!(window.selected_text(), "world\nSecond");
+    window.set_selection(Some((focus, anchor)));
+    assert_eq!(window.selected_text(), "world\nSecond");
+    // inline elements are not separated by line breaks
+    window.set_selection(Some((position(&window, "left", 1), position(&window, "right", 1))));
+    assert_eq!(window.selected_text(), "bc");
+    // offsets count the characters before the text-transform, the text is copied as it is drawn
+    window.set_selection(Some((position(&window, "upper", 4), position(&window, "upper", 6))));
+    assert_eq!(window.selected_text(), "SSE");
+    window.select_all();
+    assert_eq!(window.selected_text(), "Hello world\nSecond line\nabcd\nSTRASSE\nASSB");
+
+    let window_id = window.sdl_window().id();
+    let editable = element_by_id(&window, "editable");
+    let mut windows = vec![window];
+
+    // the mouse selects from where its button goes down to where it is released
+    push_events(&ctx, vec![mouse_button(window_id, true, 1, 1, 10), mouse_motion(window_id, 299, 34), mouse_button(window_id, false, 1, 299, 34)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(windows[0].selected_text(), "Hello world\nSecond line");
+
+    // cutting removes the selected characters of the text, not of the transformed text
+    windows[0].focus(Some(editable));
+    windows[0].set_selection(Some((TextPosition { node: editable, offset: 2 }, TextPosition { node: editable, offset: 3 })));
+    push_events(&ctx, vec![key_down(window_id, Keycode::X, yargl::event::primary_modifier())]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(windows[0].clipboard_text().as_deref(), Some("B"));
+    windows[0].set_selection(Some((TextPosition { node: editable, offset: 0 }, TextPosition { node: editable, offset: 2 })));
+    assert_eq!(windows[0].selected_text(), "ASS");
+    // pasting replaces the selection
+    windows[0].set_clipboard_text("c").unwrap();
+    push_events(&ctx, vec![key_down(window_id, Keycode::V, yargl::event::primary_modifier()), key_down(window_id, Keycode::Home, Mod::LSHIFTMOD)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(windows[0].selected_text(), "C");
+}
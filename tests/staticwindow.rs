@@ -4,11 +4,8 @@ fn static_window() {
     use std::rc::Rc;
     use yargl::event::EventReceiver;
 
-    let mut raw_ctx = yargl::context::init().unwrap();
-    let font_data_arial = std::fs::read("res/font/arial.ttf").unwrap();
-    let arial_font = yargl::font::Font::new(&font_data_arial, 0, 1);
-    raw_ctx.fonts.insert("arial".to_string(), &arial_font);
-    let ctx = Rc::new(raw_ctx);
+    let ctx = Rc::new(yargl::context::init().unwrap());
+    ctx.load_font_file("arial", "res/font/arial.ttf").unwrap();
     let html_filename = "res/html/demo.html";
     let html = std::fs::read_to_string(html_filename).unwrap();
     let mut all_windows = vec![];
@@ -31,7 +28,7 @@ fn static_window() {
     window.draw();
     all_windows.push(window);
     loop {
-        match ctx.poll_events(&all_windows) {
+        match ctx.poll_events(&mut all_windows) {
             yargl::event::EventReturnCode::Continue => {},
             yargl::event::EventReturnCode::Cancel => {},
             yargl::event::EventReturnCode::Quit => {
@@ -0,0 +1,74 @@
+mod common;
+
+#[test]
+fn touch_input() {
+    use std::rc::Rc;
+    use sdl2::{event::Event, sys::SDL_EventType::{SDL_FINGERDOWN, SDL_FINGERMOTION, SDL_FINGERUP}};
+    use yargl::event::{EventReceiver, EventReturnCode};
+    use common::*;
+
+    let html = r##"<html><head><style>
+        html, body { display: block; width: 200px; height: 100px; }
+        div { display: block; width: 200px; height: 50px; }
+    </style></head><body><div id="top"></div><div id="bottom"></div></body></html>"##;
+    let ctx = Rc::new(yargl::context::init().unwrap());
+    let mut window = open_window(&ctx, html, 200, 100);
+    let log: Log = Default::default();
+    {
+        let log = log.clone();
+        window.pointer_down_events.add_listener(move |event, window| {
+            let data = &event.0.data;
+            log.borrow_mut().push(format!("down {} {} {},{}", element_id(window, event.target()), data.pointer_id, data.x, data.y));
+            EventReturnCode::Continue
+        });
+    }
+    {
+        let log = log.clone();
+        window.pointer_move_events.add_listener(move |event, window| {
+            log.borrow_mut().push(format!("move {} {} {},{}", element_id(window, event.target()), event.0.pointer_id, event.0.x, event.0.y));
+            EventReturnCode::Continue
+        });
+    }
+    {
+        let log = log.clone();
+        window.pointer_up_events.add_listener(move |event, window| {
+            let data = &event.0.data;
+            log.borrow_mut().push(format!("up {} {} {},{}", element_id(window, event.target()), data.pointer_id, data.x, data.y));
+            EventReturnCode::Continue
+        });
+    }
+    {
+        let log = log.clone();
+        window.gesture_events.add_listener(move |event, window| {
+            let gesture = &event.0;
+            log.borrow_mut().push(format!("gesture {} {},{} {} {} {}", element_id(window, event.target()), gesture.x, gesture.y, gesture.rotation, gesture.pinch, gesture.num_fingers));
+            EventReturnCode::Continue
+        });
+    }
+    let window_id = window.sdl_window().id();
+    let mut windows = vec![window];
+
+    // finger positions are normalized to the window size, each finger is a pointer of its own until it is lifted
+    push_raw_events(vec![finger(SDL_FINGERDOWN, 7, 0.25, 0.25), finger(SDL_FINGERDOWN, 8, 0.5, 0.75), finger(SDL_FINGERMOTION, 7, 0.25, 0.75), finger(SDL_FINGERUP, 7, 0.25, 0.75)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(take(&log), vec!["down top 2 50,25", "down bottom 3 100,75", "move bottom 2 50,75", "up bottom 2 50,75"]);
+    // a lifted finger gets a new pointer id when it touches again, the other finger keeps its id
+    push_raw_events(vec![finger(SDL_FINGERDOWN, 7, 0.0, 0.0), finger(SDL_FINGERMOTION, 8, 0.5, 0.5)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(take(&log), vec!["down top 4 0,0", "move bottom 3 100,50"]);
+
+    // the mouse events that SDL synthesizes from touch input are skipped
+    let touch_mouse_id = u32::MAX;
+    push_events(&ctx, vec![
+        Event::MouseMotion { timestamp: 0, window_id, which: touch_mouse_id, mousestate: sdl2::mouse::MouseState::from_sdl_state(0), x: 10, y: 10, xrel: 0, yrel: 0 },
+        Event::MouseButtonDown { timestamp: 0, window_id, which: touch_mouse_id, mouse_btn: sdl2::mouse::MouseButton::Left, clicks: 1, x: 10, y: 10 },
+        mouse_motion(window_id, 10, 60),
+    ]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(take(&log), vec!["move bottom 1 10,60"]);
+
+    // gestures are dispatched at the center of the fingers
+    push_raw_events(vec![multi_gesture(0.5, 0.25, 0.5, 0.25, 2)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(take(&log), vec!["gesture top 100,25 0.5 0.25 2"]);
+}
@@ -0,0 +1,49 @@
+mod common;
+
+#[test]
+fn window_lifecycle() {
+    use std::{cell::Cell, rc::Rc};
+    use sdl2::{event::WindowEvent, keyboard::{Keycode, Mod}};
+    use yargl::event::{EventReceiver, EventReturnCode};
+    use common::*;
+
+    let html = r##"<html><head><style>html, body { display: block; width: 100%; height: 100%; }</style></head><body></body></html>"##;
+    let ctx = Rc::new(yargl::context::init().unwrap());
+    let mut window = open_window(&ctx, html, 200, 200);
+    let requests = Rc::new(Cell::new(0));
+    let cancel = Rc::new(Cell::new(true));
+    {
+        let (requests, cancel) = (requests.clone(), cancel.clone());
+        window.close_requested_events.add_listener(move |_, _| {
+            requests.set(requests.get() + 1);
+            if cancel.get() { EventReturnCode::Cancel } else { EventReturnCode::Continue }
+        });
+    }
+    let window_id = window.sdl_window().id();
+    let mut windows = vec![window];
+
+    // a cancelled close request keeps the window open
+    push_events(&ctx, vec![window_event(window_id, WindowEvent::Close)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(requests.get(), 1);
+    assert!(!windows[0].is_closed());
+
+    // otherwise the window is closed
+    cancel.set(false);
+    push_events(&ctx, vec![window_event(window_id, WindowEvent::Close)]);
+    ctx.poll_events(&mut windows);
+    assert_eq!(requests.get(), 2);
+    assert!(windows[0].is_closed());
+
+    // a quit is returned even if more events follow in the same batch
+    let mut window = open_window(&ctx, html, 200, 200);
+    let window_id = window.sdl_window().id();
+    window.close_requested_events.add_listener(|_, _| EventReturnCode::Continue);
+    window.key_down_events.add_listener(|_, _| EventReturnCode::Quit);
+    let mut windows = vec![window];
+    push_events(&ctx, vec![key_down(window_id, Keycode::Q, Mod::NOMOD), mouse_motion(window_id, 10, 10)]);
+    assert!(ctx.poll_events(&mut windows) == EventReturnCode::Quit);
+    // closing the last window quits
+    push_events(&ctx, vec![window_event(window_id, WindowEvent::Close), mouse_motion(window_id, 10, 10), key_down(window_id, Keycode::A, Mod::NOMOD)]);
+    assert!(ctx.poll_events(&mut windows) == EventReturnCode::Quit);
+}